@@ -1,7 +1,9 @@
 use std::ffi::OsString;
 
 use crate::commands;
+use crate::core::color;
 use crate::core::icons;
+use crate::core::settings;
 use crate::core::style::Style;
 use crate::core::theme;
 
@@ -9,11 +11,23 @@ pub fn run(argv: Vec<String>) -> Result<(), String> {
     let mut args = argv.into_iter();
     let _bin = args.next();
 
+    // Global options go before the command, so whatever follows it (file
+    // names, patterns, git arguments) reaches the command untouched.
+    let (rest, depth) = color::take_depth_flag(args.collect())?;
+    if let Some(depth) = depth {
+        color::set_override(depth);
+    }
+    let mut args = rest.into_iter();
+
     let Some(cmd) = args.next() else {
         print_help();
         return Ok(());
     };
 
+    if !matches!(cmd.as_str(), "config" | "help" | "--help" | "-h") {
+        settings::check()?;
+    }
+
     match cmd.as_str() {
         "ls" | "eza" => {
             let ls_args: Vec<OsString> = args.map(OsString::from).collect();
@@ -72,6 +86,10 @@ pub fn run(argv: Vec<String>) -> Result<(), String> {
                 Err("themes supports only: list".to_string())
             }
         }
+        "config" => {
+            let config_args: Vec<String> = args.collect();
+            commands::config::run(&config_args)
+        }
         "help" | "--help" | "-h" => {
             print_help();
             Ok(())
@@ -205,6 +223,13 @@ fn print_help() {
         arg("list"),
         desc("# Theme catalog")
     );
+    println!(
+        "  {} {} {}           {}",
+        opt("dusk"),
+        cmd("config"),
+        arg("show"),
+        desc("# Effective config.toml settings")
+    );
     println!();
     println!("{} {}", opt(icons::ICON_GIT), cmd("Pass-through"));
     println!(
@@ -222,6 +247,16 @@ fn print_help() {
         desc("# rg, or grep fallback if rg is missing")
     );
     println!();
+    println!("{}", cmd("Global options"));
+    println!(
+        "  {} {} {} {}  {}",
+        opt("dusk"),
+        opt("--color-depth"),
+        arg("auto|16|256|truecolor"),
+        cmd("<command>"),
+        desc("# Override detected color support (COLORTERM/TERM)")
+    );
+    println!();
     println!("{}", cmd("Quick start"));
     println!("  {} {} {}", opt("dusk"), cmd("xtree"), arg("--tldr"));
     println!("  {} {} {}", opt("dusk"), cmd("xtree"), arg("--help"));
@@ -0,0 +1,1250 @@
+use std::ops::Range;
+
+use super::syntax::{self, Flavor, Grammar};
+use crate::core::style::Style;
+use crate::core::theme::Theme;
+
+/// Token classes produced by the lexers; `color` maps them onto theme roles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    Plain,
+    Keyword,
+    Type,
+    Function,
+    Constant,
+    Number,
+    String,
+    Escape,
+    Comment,
+    Todo,
+    Attribute,
+    Key,
+    Tag,
+    Heading,
+    Link,
+    Emphasis,
+    Inserted,
+    Deleted,
+}
+
+impl Class {
+    pub fn color(self, theme: &Theme) -> &'static str {
+        match self {
+            Class::Plain => "",
+            Class::Keyword | Class::Tag => theme.accent,
+            Class::Type | Class::Heading => theme.title,
+            Class::Function | Class::Emphasis => theme.info,
+            Class::Constant | Class::Number | Class::Escape => theme.number,
+            Class::String => theme.ok,
+            Class::Comment => theme.subtle,
+            Class::Todo => theme.warn,
+            Class::Attribute | Class::Link => theme.symlink,
+            Class::Key => theme.directory,
+            Class::Inserted => theme.diff_add,
+            Class::Deleted => theme.diff_del,
+        }
+    }
+}
+
+pub type Spans = Vec<(Class, Range<usize>)>;
+
+#[derive(Default)]
+enum Inside {
+    #[default]
+    Code,
+    Comment {
+        depth: usize,
+    },
+    Str {
+        close: String,
+        escapes: bool,
+        multiline: bool,
+    },
+    Heredoc(String),
+    Tag,
+}
+
+struct Fence {
+    marker: String,
+    inner: Option<Box<Highlighter>>,
+}
+
+/// Line-by-line highlighter. State (open comments, strings, heredocs, YAML
+/// block scalars, Markdown fences, CSS nesting) carries over between calls,
+/// so lines must be fed in order.
+pub struct Highlighter {
+    grammar: &'static Grammar,
+    inside: Inside,
+    braces: usize,
+    yaml_block: Option<usize>,
+    heredoc: Option<String>,
+    fence: Option<Fence>,
+}
+
+impl Highlighter {
+    pub fn new(grammar: &'static Grammar) -> Self {
+        Self {
+            grammar,
+            inside: Inside::Code,
+            braces: 0,
+            yaml_block: None,
+            heredoc: None,
+            fence: None,
+        }
+    }
+
+    pub fn paint(&mut self, line: &str, style: &Style, theme: &Theme) -> String {
+        let mut out = String::with_capacity(line.len() + 16);
+        for (class, range) in self.spans(line) {
+            let text = &line[range];
+            match class.color(theme) {
+                "" => out.push_str(text),
+                color => out.push_str(&style.paint(color, text)),
+            }
+        }
+        out
+    }
+
+    pub fn spans(&mut self, line: &str) -> Spans {
+        let mut sp = Out::default();
+        match self.grammar.flavor {
+            Flavor::Code => self.code(line, &mut sp),
+            Flavor::Yaml => self.yaml(line, &mut sp),
+            Flavor::Html => self.html(line, &mut sp),
+            Flavor::Css => self.css(line, &mut sp),
+            Flavor::Markdown => self.markdown(line, &mut sp),
+            Flavor::Diff => diff(line, &mut sp),
+        }
+        sp.0
+    }
+
+    /// Continues an open comment/string/heredoc from `i`; returns where code
+    /// resumes (or the line length if it stays open).
+    fn resume(&mut self, line: &str, i: usize, sp: &mut Out) -> usize {
+        let g = self.grammar;
+        match &mut self.inside {
+            Inside::Code | Inside::Tag => i,
+            Inside::Comment { depth } => {
+                let Some((open, close)) = g.block_comment else {
+                    self.inside = Inside::Code;
+                    return i;
+                };
+                let mut j = i;
+                while j < line.len() {
+                    let rest = &line[j..];
+                    if g.nested_comments && rest.starts_with(open) {
+                        *depth += 1;
+                        j += open.len();
+                    } else if rest.starts_with(close) {
+                        j += close.len();
+                        *depth -= 1;
+                        if *depth == 0 {
+                            comment_text(line, i..j, sp);
+                            self.inside = Inside::Code;
+                            return j;
+                        }
+                    } else {
+                        j += char_len(line, j);
+                    }
+                }
+                comment_text(line, i..line.len(), sp);
+                line.len()
+            }
+            Inside::Str {
+                close,
+                escapes,
+                multiline,
+            } => {
+                let (end, closed) = scan_string(line, i, close, *escapes, sp);
+                if closed || !*multiline {
+                    self.inside = Inside::Code;
+                }
+                end
+            }
+            Inside::Heredoc(delim) => {
+                if line.trim() == delim.as_str() {
+                    sp.push(Class::Keyword, 0..line.len());
+                    self.inside = Inside::Code;
+                } else {
+                    sp.push(Class::String, 0..line.len());
+                }
+                line.len()
+            }
+        }
+    }
+
+    fn code(&mut self, line: &str, sp: &mut Out) {
+        let g = self.grammar;
+        let b = line.as_bytes();
+        let mut i = self.resume(line, 0, sp);
+        if i >= line.len() {
+            return;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if i == 0 && matches!(self.inside, Inside::Code) {
+            let trimmed = &line[indent..];
+            if g.preprocessor && trimmed.starts_with('#') {
+                let end = line_comment_start(g, line, indent + 1).unwrap_or(line.len());
+                sp.push(Class::Attribute, 0..end);
+                if end < line.len() {
+                    comment_text(line, end..line.len(), sp);
+                }
+                return;
+            }
+            if g.section_headers && trimmed.starts_with('[') {
+                let end = line_comment_start(g, line, indent).unwrap_or(line.len());
+                sp.push(Class::Heading, 0..end);
+                if end < line.len() {
+                    comment_text(line, end..line.len(), sp);
+                }
+                return;
+            }
+            if g.key_before == Some(b'=')
+                && let Some(eq) = assignment_key_end(line, indent)
+            {
+                sp.push(Class::Plain, 0..indent);
+                sp.push(Class::Key, indent..eq);
+                i = eq;
+            }
+        }
+
+        while i < b.len() {
+            if !matches!(self.inside, Inside::Code) {
+                i = self.resume(line, i, sp);
+                continue;
+            }
+            let rest = &line[i..];
+            let c = b[i];
+
+            if let Some((open, _)) = g.block_comment
+                && rest.starts_with(open)
+            {
+                sp.push(Class::Comment, i..i + open.len());
+                self.inside = Inside::Comment { depth: 1 };
+                i += open.len();
+                continue;
+            }
+            if line_comment_start(g, line, i) == Some(i) {
+                comment_text(line, i..line.len(), sp);
+                break;
+            }
+            if g.rust_literals
+                && let Some((len, close)) = raw_string_open(rest)
+            {
+                sp.push(Class::String, i..i + len);
+                self.inside = Inside::Str {
+                    close,
+                    escapes: false,
+                    multiline: true,
+                };
+                i += len;
+                continue;
+            }
+            if g.rust_literals && c == b'\'' {
+                if let Some(len) = char_literal(rest) {
+                    sp.push(Class::String, i..i + len);
+                    i += len;
+                } else {
+                    let end = ident_end(line, i + 1);
+                    sp.push(Class::Attribute, i..end);
+                    i = end;
+                }
+                continue;
+            }
+            if let Some(q) = g.quotes.iter().find(|q| rest.starts_with(q.open)) {
+                let start = i;
+                let mark = sp.0.len();
+                sp.push(Class::String, i..i + q.open.len());
+                let (end, closed) = scan_string(line, i + q.open.len(), q.close, q.escapes, sp);
+                if !closed && q.multiline {
+                    self.inside = Inside::Str {
+                        close: q.close.to_string(),
+                        escapes: q.escapes,
+                        multiline: true,
+                    };
+                }
+                if closed && g.key_before.is_some() && next_byte(line, end) == g.key_before {
+                    sp.0.truncate(mark);
+                    sp.push(Class::Key, start..end);
+                }
+                i = end;
+                continue;
+            }
+            if g.rust_extras && (rest.starts_with("#[") || rest.starts_with("#![")) {
+                let end = bracket_end(line, i + rest.find('[').unwrap_or(1));
+                sp.push(Class::Attribute, i..end);
+                i = end;
+                continue;
+            }
+            if g.annotations && c == b'@' && b.get(i + 1).is_some_and(|&n| is_ident_start(n)) {
+                let mut end = ident_end(line, i + 1);
+                while b.get(end) == Some(&b'.')
+                    && b.get(end + 1).is_some_and(|&n| is_ident_start(n))
+                {
+                    end = ident_end(line, end + 1);
+                }
+                sp.push(Class::Attribute, i..end);
+                i = end;
+                continue;
+            }
+            if g.shell_extras && c == b'$' {
+                let end = shell_var_end(line, i);
+                sp.push(
+                    if end > i + 1 {
+                        Class::Constant
+                    } else {
+                        Class::Plain
+                    },
+                    i..end,
+                );
+                i = end;
+                continue;
+            }
+            if g.shell_extras && rest.starts_with("<<") && !rest.starts_with("<<<") {
+                if let Some((end, delim)) = heredoc_open(line, i) {
+                    sp.push(Class::Keyword, i..end);
+                    self.heredoc = Some(delim);
+                    i = end;
+                } else {
+                    sp.push(Class::Plain, i..i + 2);
+                    i += 2;
+                }
+                continue;
+            }
+            if c.is_ascii_digit() || (c == b'.' && b.get(i + 1).is_some_and(u8::is_ascii_digit)) {
+                let end = number_end(line, i, g.number_extra);
+                sp.push(Class::Number, i..end);
+                i = end;
+                continue;
+            }
+            if is_ident_start(c) {
+                i = self.ident(line, i, sp);
+                continue;
+            }
+            let len = char_len(line, i);
+            sp.push(Class::Plain, i..i + len);
+            i += len;
+        }
+
+        if let Some(delim) = self.heredoc.take()
+            && matches!(self.inside, Inside::Code)
+        {
+            self.inside = Inside::Heredoc(delim);
+        }
+    }
+
+    fn ident(&self, line: &str, start: usize, sp: &mut Out) -> usize {
+        let g = self.grammar;
+        let b = line.as_bytes();
+        let mut end = ident_end(line, start);
+        let word = &line[start..end];
+        let next = next_byte(line, end);
+        let class = if g.is_word(g.keywords, word) {
+            Class::Keyword
+        } else if g.is_word(g.types, word) {
+            Class::Type
+        } else if g.is_word(g.constants, word) {
+            Class::Constant
+        } else if g.is_word(g.builtins, word) {
+            Class::Function
+        } else if g.rust_extras && b.get(end) == Some(&b'!') && b.get(end + 1) != Some(&b'=') {
+            end += 1;
+            Class::Function
+        } else if next == Some(b'(') {
+            Class::Function
+        } else if g.key_before.is_some()
+            && next == g.key_before
+            && !line[skip_spaces(line, end) + 1..].starts_with('=')
+        {
+            Class::Key
+        } else if g.caps_are_types && word.starts_with(|c: char| c.is_ascii_uppercase()) {
+            Class::Type
+        } else {
+            Class::Plain
+        };
+        sp.push(class, start..end);
+        end
+    }
+
+    fn yaml(&mut self, line: &str, sp: &mut Out) {
+        let g = self.grammar;
+        let b = line.as_bytes();
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if let Some(block) = self.yaml_block {
+            if line.trim().is_empty() || indent > block {
+                sp.push(Class::String, 0..line.len());
+                return;
+            }
+            self.yaml_block = None;
+        }
+
+        let trimmed = &line[indent..];
+        if trimmed.starts_with('#') {
+            comment_text(line, indent..line.len(), sp);
+            return;
+        }
+        let mut i = indent;
+        if indent == 0 && (trimmed.starts_with("---") || trimmed.starts_with("...")) {
+            sp.push(Class::Keyword, 0..3);
+            i = 3;
+        }
+        sp.push(Class::Plain, 0..i.max(indent));
+        i = i.max(indent);
+        while b.get(i) == Some(&b'-') && matches!(b.get(i + 1), None | Some(b' ')) {
+            sp.push(Class::Keyword, i..i + 1);
+            i = skip_spaces(line, i + 1);
+        }
+        if let Some(colon) = yaml_key_end(line, i) {
+            sp.push(Class::Key, i..colon);
+            sp.push(Class::Plain, colon..colon + 1);
+            i = colon + 1;
+        }
+
+        let mut flow = 0usize;
+        while i < b.len() {
+            let c = b[i];
+            match c {
+                b' ' | b'\t' => {
+                    sp.push(Class::Plain, i..i + 1);
+                    i += 1;
+                }
+                b'#' if i == 0 || b[i - 1] == b' ' => {
+                    comment_text(line, i..line.len(), sp);
+                    return;
+                }
+                b'"' | b'\'' => {
+                    sp.push(Class::String, i..i + 1);
+                    let close = if c == b'"' { "\"" } else { "'" };
+                    i = scan_string(line, i + 1, close, c == b'"', sp).0;
+                }
+                b'&' | b'*' | b'!' => {
+                    let end = line[i..]
+                        .find([' ', ',', ']', '}'])
+                        .map_or(line.len(), |p| i + p);
+                    sp.push(Class::Attribute, i..end);
+                    i = end;
+                }
+                b'|' | b'>'
+                    if line[i + 1..]
+                        .trim_start_matches([
+                            '+', '-', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+                        ])
+                        .trim_start()
+                        .chars()
+                        .next()
+                        .is_none_or(|c| c == '#') =>
+                {
+                    sp.push(Class::Keyword, i..i + 1);
+                    self.yaml_block = Some(indent);
+                    i += 1;
+                }
+                b'[' | b'{' => {
+                    flow += 1;
+                    sp.push(Class::Plain, i..i + 1);
+                    i += 1;
+                }
+                b']' | b'}' | b',' if flow > 0 => {
+                    if c != b',' {
+                        flow -= 1;
+                    }
+                    sp.push(Class::Plain, i..i + 1);
+                    i += 1;
+                }
+                _ => {
+                    let mut end = i;
+                    while end < b.len() {
+                        let e = b[end];
+                        if (e == b' ' && b.get(end + 1) == Some(&b'#'))
+                            || (flow > 0 && matches!(e, b',' | b']' | b'}'))
+                            || (flow > 0
+                                && e == b':'
+                                && matches!(b.get(end + 1), None | Some(b' ')))
+                        {
+                            break;
+                        }
+                        end += char_len(line, end);
+                    }
+                    let scalar = line[i..end].trim_end_matches([' ', '\t']);
+                    let end = i + scalar.len();
+                    let class = if flow > 0 && b.get(end) == Some(&b':') {
+                        Class::Key
+                    } else if g.is_word(g.constants, scalar) {
+                        Class::Constant
+                    } else if is_number(scalar) {
+                        Class::Number
+                    } else {
+                        Class::String
+                    };
+                    let end = end.max(i + char_len(line, i));
+                    sp.push(class, i..end);
+                    i = end;
+                }
+            }
+        }
+    }
+
+    fn html(&mut self, line: &str, sp: &mut Out) {
+        let b = line.as_bytes();
+        let mut i = 0;
+        while i < b.len() {
+            match self.inside {
+                Inside::Comment { .. } => {
+                    i = self.resume(line, i, sp);
+                }
+                Inside::Tag => {
+                    let c = b[i];
+                    if c == b'>' {
+                        sp.push(Class::Plain, i..i + 1);
+                        self.inside = Inside::Code;
+                        i += 1;
+                    } else if line[i..].starts_with("/>") {
+                        sp.push(Class::Plain, i..i + 2);
+                        self.inside = Inside::Code;
+                        i += 2;
+                    } else if c == b'"' || c == b'\'' {
+                        sp.push(Class::String, i..i + 1);
+                        let close = if c == b'"' { "\"" } else { "'" };
+                        i = scan_string(line, i + 1, close, false, sp).0;
+                    } else if c.is_ascii_whitespace() || c == b'=' {
+                        sp.push(Class::Plain, i..i + 1);
+                        i += 1;
+                    } else {
+                        let end = line[i..]
+                            .find(|ch: char| {
+                                ch.is_ascii_whitespace() || matches!(ch, '=' | '>' | '/')
+                            })
+                            .map_or(line.len(), |p| i + p)
+                            .max(i + char_len(line, i));
+                        sp.push(Class::Key, i..end);
+                        i = end;
+                    }
+                }
+                _ => {
+                    let rest = &line[i..];
+                    if rest.starts_with("<!--") {
+                        sp.push(Class::Comment, i..i + 4);
+                        self.inside = Inside::Comment { depth: 1 };
+                        i += 4;
+                    } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                        let end = rest.find('>').map_or(line.len(), |p| i + p + 1);
+                        sp.push(Class::Keyword, i..end);
+                        i = end;
+                    } else if rest.starts_with('<')
+                        && rest[1..]
+                            .trim_start_matches('/')
+                            .starts_with(|c: char| c.is_ascii_alphabetic())
+                    {
+                        let open = if rest.starts_with("</") { 2 } else { 1 };
+                        sp.push(Class::Plain, i..i + open);
+                        let name_end = line[i + open..]
+                            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '>' | '/'))
+                            .map_or(line.len(), |p| i + open + p);
+                        sp.push(Class::Tag, i + open..name_end);
+                        self.inside = Inside::Tag;
+                        i = name_end;
+                    } else if b[i] == b'&'
+                        && let Some(semi) = rest.find(';').filter(|&p| p <= 10)
+                    {
+                        sp.push(Class::Constant, i..i + semi + 1);
+                        i += semi + 1;
+                    } else {
+                        let len = char_len(line, i);
+                        sp.push(Class::Plain, i..i + len);
+                        i += len;
+                    }
+                }
+            }
+        }
+    }
+
+    fn css(&mut self, line: &str, sp: &mut Out) {
+        let b = line.as_bytes();
+        let mut i = 0;
+        while i < b.len() {
+            if !matches!(self.inside, Inside::Code) {
+                i = self.resume(line, i, sp);
+                continue;
+            }
+            let rest = &line[i..];
+            let c = b[i];
+            if rest.starts_with("/*") {
+                sp.push(Class::Comment, i..i + 2);
+                self.inside = Inside::Comment { depth: 1 };
+                i += 2;
+                continue;
+            }
+            if rest.starts_with("//") {
+                comment_text(line, i..line.len(), sp);
+                return;
+            }
+            match c {
+                b'{' => {
+                    self.braces += 1;
+                    sp.push(Class::Plain, i..i + 1);
+                    i += 1;
+                }
+                b'}' => {
+                    self.braces = self.braces.saturating_sub(1);
+                    sp.push(Class::Plain, i..i + 1);
+                    i += 1;
+                }
+                b'"' | b'\'' => {
+                    sp.push(Class::String, i..i + 1);
+                    let close = if c == b'"' { "\"" } else { "'" };
+                    i = scan_string(line, i + 1, close, true, sp).0;
+                }
+                b'@' | b'!' => {
+                    let end = css_ident_end(line, i + 1);
+                    sp.push(Class::Keyword, i..end);
+                    i = end;
+                }
+                b'#' if self.braces > 0 => {
+                    let end = css_ident_end(line, i + 1);
+                    sp.push(Class::Number, i..end);
+                    i = end;
+                }
+                b'.' | b'#' if b.get(i + 1).is_some_and(|&n| is_css_ident(n)) => {
+                    let end = css_ident_end(line, i + 1);
+                    let class = if c == b'.' {
+                        Class::Type
+                    } else {
+                        Class::Constant
+                    };
+                    sp.push(class, i..end);
+                    i = end;
+                }
+                b':' if self.braces == 0 || b.get(i + 1) == Some(&b':') => {
+                    let start = i;
+                    while b.get(i) == Some(&b':') {
+                        i += 1;
+                    }
+                    let end = css_ident_end(line, i);
+                    sp.push(Class::Attribute, start..end);
+                    i = end;
+                }
+                b'[' if self.braces == 0 => {
+                    let end = rest.find(']').map_or(line.len(), |p| i + p + 1);
+                    sp.push(Class::Key, i..end);
+                    i = end;
+                }
+                _ if c.is_ascii_digit()
+                    || (matches!(c, b'-' | b'.')
+                        && b.get(i + 1).is_some_and(u8::is_ascii_digit)) =>
+                {
+                    let mut end = i + 1;
+                    while end < b.len()
+                        && (b[end].is_ascii_alphanumeric() || matches!(b[end], b'.' | b'%'))
+                    {
+                        end += 1;
+                    }
+                    sp.push(Class::Number, i..end);
+                    i = end;
+                }
+                _ if is_css_ident(c) => {
+                    let end = css_ident_end(line, i);
+                    let next = next_byte(line, end);
+                    let class = if self.braces == 0 {
+                        Class::Tag
+                    } else if next == Some(b':') {
+                        Class::Key
+                    } else if next == Some(b'(') {
+                        Class::Function
+                    } else if line[i..end].starts_with("--") {
+                        Class::Constant
+                    } else {
+                        Class::Plain
+                    };
+                    sp.push(class, i..end);
+                    i = end;
+                }
+                _ => {
+                    let len = char_len(line, i);
+                    sp.push(Class::Plain, i..i + len);
+                    i += len;
+                }
+            }
+        }
+    }
+
+    fn markdown(&mut self, line: &str, sp: &mut Out) {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = &line[indent..];
+
+        if let Some(fence) = &mut self.fence {
+            if trimmed.starts_with(fence.marker.as_str())
+                && trimmed
+                    .trim_start_matches(fence.marker.chars().next().unwrap_or('`'))
+                    .trim()
+                    .is_empty()
+            {
+                sp.push(Class::Attribute, 0..line.len());
+                self.fence = None;
+            } else if let Some(inner) = &mut fence.inner {
+                sp.0.extend(inner.spans(line));
+            } else {
+                sp.push(Class::String, 0..line.len());
+            }
+            return;
+        }
+
+        for mark in ['`', '~'] {
+            let run = trimmed.len() - trimmed.trim_start_matches(mark).len();
+            if run >= 3 {
+                let lang = trimmed[run..]
+                    .trim()
+                    .split(|c: char| c.is_whitespace() || matches!(c, ',' | '{'))
+                    .next()
+                    .unwrap_or_default();
+                self.fence = Some(Fence {
+                    marker: trimmed[..run].to_string(),
+                    inner: syntax::by_name(lang).map(|g| Box::new(Highlighter::new(g))),
+                });
+                sp.push(Class::Attribute, 0..line.len());
+                return;
+            }
+        }
+
+        let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+        if (1..=6).contains(&hashes) && matches!(trimmed.as_bytes().get(hashes), None | Some(b' '))
+        {
+            sp.push(Class::Heading, 0..line.len());
+            return;
+        }
+        if trimmed.starts_with('>') {
+            sp.push(Class::Comment, 0..line.len());
+            return;
+        }
+        if trimmed.len() >= 3
+            && trimmed
+                .chars()
+                .all(|c| c == ' ' || c == trimmed.as_bytes()[0] as char)
+            && matches!(trimmed.as_bytes()[0], b'-' | b'=' | b'*' | b'_')
+        {
+            sp.push(Class::Keyword, 0..line.len());
+            return;
+        }
+
+        sp.push(Class::Plain, 0..indent);
+        let mut i = indent;
+        let digits = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        let marker = if trimmed.starts_with("- ")
+            || trimmed.starts_with("* ")
+            || trimmed.starts_with("+ ")
+        {
+            1
+        } else if digits > 0 && matches!(&trimmed[digits..].get(..2), Some(". " | ") ")) {
+            digits + 1
+        } else {
+            0
+        };
+        if marker > 0 {
+            sp.push(Class::Keyword, i..i + marker);
+            i += marker;
+            let rest = &line[i..];
+            for task in [" [ ] ", " [x] ", " [X] "] {
+                if rest.starts_with(task) {
+                    sp.push(Class::Keyword, i..i + task.len());
+                    i += task.len();
+                    break;
+                }
+            }
+        }
+        markdown_inline(line, i, sp);
+    }
+}
+
+fn markdown_inline(line: &str, mut i: usize, sp: &mut Out) {
+    let b = line.as_bytes();
+    while i < b.len() {
+        let rest = &line[i..];
+        let c = b[i];
+        if c == b'`' {
+            let run = rest.len() - rest.trim_start_matches('`').len();
+            let ticks = &rest[..run];
+            if let Some(close) = rest[run..].find(ticks) {
+                let end = i + run + close + run;
+                sp.push(Class::String, i..end);
+                i = end;
+            } else {
+                sp.push(Class::Plain, i..i + run);
+                i += run;
+            }
+            continue;
+        }
+        if c == b'\\' && i + 1 < b.len() {
+            let end = i + 1 + char_len(line, i + 1);
+            sp.push(Class::Escape, i..end);
+            i = end;
+            continue;
+        }
+        if (c == b'*' || c == b'_') && !(c == b'_' && i > 0 && b[i - 1].is_ascii_alphanumeric()) {
+            let delim = if rest.starts_with("**") || rest.starts_with("__") {
+                &rest[..2]
+            } else {
+                &rest[..1]
+            };
+            if let Some(close) = rest[delim.len()..].find(delim).filter(|&p| p > 0)
+                && !rest[delim.len()..].starts_with(' ')
+            {
+                let end = i + delim.len() + close + delim.len();
+                sp.push(Class::Emphasis, i..end);
+                i = end;
+                continue;
+            }
+        }
+        if c == b'[' || (c == b'!' && rest.starts_with("![")) {
+            let open = if c == b'!' { 1 } else { 0 };
+            if let Some(close) = rest.find("](")
+                && close > open
+                && let Some(paren) = rest[close + 2..].find(')')
+            {
+                let text_end = i + close + 1;
+                let end = i + close + 2 + paren + 1;
+                sp.push(Class::Key, i..text_end);
+                sp.push(Class::Link, text_end..end);
+                i = end;
+                continue;
+            }
+        }
+        if c == b'<'
+            && (rest.starts_with("<http://") || rest.starts_with("<https://"))
+            && let Some(close) = rest.find('>')
+        {
+            sp.push(Class::Link, i..i + close + 1);
+            i += close + 1;
+            continue;
+        }
+        let len = char_len(line, i);
+        sp.push(Class::Plain, i..i + len);
+        i += len;
+    }
+}
+
+fn diff(line: &str, sp: &mut Out) {
+    let class = if line.starts_with("+++") || line.starts_with("---") {
+        Class::Heading
+    } else if line.starts_with('+') {
+        Class::Inserted
+    } else if line.starts_with('-') {
+        Class::Deleted
+    } else if line.starts_with("@@") {
+        Class::Number
+    } else if line.starts_with("diff ") || line.starts_with("index ") {
+        Class::Heading
+    } else {
+        Class::Plain
+    };
+    sp.push(class, 0..line.len());
+}
+
+#[derive(Default)]
+struct Out(Spans);
+
+impl Out {
+    /// Appends a span, merging it into the previous one when the class
+    /// matches and the ranges touch.
+    fn push(&mut self, class: Class, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        if let Some((last, prev)) = self.0.last_mut()
+            && *last == class
+            && prev.end == range.start
+        {
+            prev.end = range.end;
+            return;
+        }
+        self.0.push((class, range));
+    }
+}
+
+/// Comment text with TODO/FIXME/XXX/HACK markers split out.
+fn comment_text(line: &str, range: Range<usize>, sp: &mut Out) {
+    let text = &line[range.clone()];
+    let mut last = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let at_word = i == 0 || !text.as_bytes()[i - 1].is_ascii_alphanumeric();
+        let marker = ["TODO", "FIXME", "XXX", "HACK"]
+            .into_iter()
+            .find(|m| at_word && text[i..].starts_with(m));
+        if let Some(m) = marker
+            && !text[i + m.len()..].starts_with(|c: char| c.is_ascii_alphanumeric())
+        {
+            sp.push(Class::Comment, range.start + last..range.start + i);
+            sp.push(Class::Todo, range.start + i..range.start + i + m.len());
+            i += m.len();
+            last = i;
+            continue;
+        }
+        i += char_len(text, i);
+    }
+    sp.push(Class::Comment, range.start + last..range.end);
+}
+
+/// Scans string contents from `i` up to and including `close`; returns the
+/// end offset and whether the string was closed on this line.
+fn scan_string(line: &str, i: usize, close: &str, escapes: bool, sp: &mut Out) -> (usize, bool) {
+    let b = line.as_bytes();
+    let mut seg = i;
+    let mut j = i;
+    while j < b.len() {
+        if escapes && b[j] == b'\\' {
+            sp.push(Class::String, seg..j);
+            let end = if j + 1 < b.len() {
+                j + 1 + char_len(line, j + 1)
+            } else {
+                b.len()
+            };
+            sp.push(Class::Escape, j..end);
+            j = end;
+            seg = j;
+            continue;
+        }
+        if line[j..].starts_with(close) {
+            let end = j + close.len();
+            sp.push(Class::String, seg..end);
+            return (end, true);
+        }
+        j += char_len(line, j);
+    }
+    sp.push(Class::String, seg..b.len());
+    (b.len(), false)
+}
+
+fn line_comment_start(g: &Grammar, line: &str, from: usize) -> Option<usize> {
+    let b = line.as_bytes();
+    let mut i = from;
+    while i < b.len() {
+        if g.line_comments.iter().any(|p| line[i..].starts_with(p))
+            && (!g.comment_after_space || i == 0 || b[i - 1].is_ascii_whitespace())
+        {
+            return Some(i);
+        }
+        if i > from && g.quotes.iter().any(|q| line[i..].starts_with(q.open)) {
+            return None;
+        }
+        i += char_len(line, i);
+    }
+    None
+}
+
+/// End of `key` in `key = value` (bare, dotted or quoted keys).
+fn assignment_key_end(line: &str, start: usize) -> Option<usize> {
+    let b = line.as_bytes();
+    let mut i = start;
+    let mut quote = None;
+    while i < b.len() {
+        let c = b[i];
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == b'"' || c == b'\'' => quote = Some(c),
+            None if c == b'=' => {
+                let key = line[start..i].trim_end();
+                return (!key.is_empty()).then_some(start + key.len());
+            }
+            None if !(c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b' ')) => {
+                return None;
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn yaml_key_end(line: &str, start: usize) -> Option<usize> {
+    let b = line.as_bytes();
+    let first = *b.get(start)?;
+    if first == b'"' || first == b'\'' {
+        let close = line[start + 1..].find(first as char)? + start + 1;
+        return (b.get(close + 1) == Some(&b':') && matches!(b.get(close + 2), None | Some(b' ')))
+            .then_some(close + 1);
+    }
+    if matches!(
+        first,
+        b'[' | b'{' | b'&' | b'*' | b'!' | b'|' | b'>' | b'%' | b'@' | b'`' | b'#'
+    ) {
+        return None;
+    }
+    let mut i = start;
+    while i < b.len() {
+        if b[i] == b':' && matches!(b.get(i + 1), None | Some(b' ')) {
+            return Some(i);
+        }
+        if b[i] == b' ' && b.get(i + 1) == Some(&b'#') {
+            return None;
+        }
+        i += char_len(line, i);
+    }
+    None
+}
+
+/// `r"`, `r#"`, `br##"` ... → (prefix length, closing delimiter).
+fn raw_string_open(rest: &str) -> Option<(usize, String)> {
+    let body = rest
+        .strip_prefix("br")
+        .or_else(|| rest.strip_prefix("cr"))
+        .or_else(|| rest.strip_prefix('r'))?;
+    let hashes = body.len() - body.trim_start_matches('#').len();
+    body[hashes..].starts_with('"').then(|| {
+        (
+            rest.len() - body.len() + hashes + 1,
+            format!("\"{}", "#".repeat(hashes)),
+        )
+    })
+}
+
+/// Length of a Rust char literal at the start of `rest`, or `None` for a
+/// lifetime/label.
+fn char_literal(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        return rest[2..].find('\'').filter(|&p| p <= 10).map(|p| p + 3);
+    }
+    let (idx, second) = chars.next()?;
+    (second == '\'').then_some(idx + 1)
+}
+
+fn bracket_end(line: &str, open: usize) -> usize {
+    let mut depth = 0usize;
+    for (idx, c) in line[open..].char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + idx + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    line.len()
+}
+
+fn shell_var_end(line: &str, start: usize) -> usize {
+    let b = line.as_bytes();
+    match b.get(start + 1) {
+        Some(b'{') => line[start..]
+            .find('}')
+            .map_or(line.len(), |p| start + p + 1),
+        Some(c)
+            if c.is_ascii_digit()
+                || matches!(c, b'@' | b'*' | b'#' | b'?' | b'$' | b'!' | b'-') =>
+        {
+            start + 2
+        }
+        Some(&c) if is_ident_start(c) => ident_end(line, start + 1),
+        _ => start + 1,
+    }
+}
+
+/// `<<EOF`, `<<-'EOF'`, `<< "EOF"` → (end of the operator, delimiter).
+fn heredoc_open(line: &str, start: usize) -> Option<(usize, String)> {
+    let mut i = start + 2;
+    let b = line.as_bytes();
+    if b.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    i = skip_spaces(line, i);
+    let quote = b.get(i).copied().filter(|c| matches!(c, b'\'' | b'"'));
+    if quote.is_some() {
+        i += 1;
+    }
+    let word_end = ident_end(line, i);
+    if word_end == i {
+        return None;
+    }
+    let delim = line[i..word_end].to_string();
+    let end = if quote.is_some() && b.get(word_end) == quote.as_ref() {
+        word_end + 1
+    } else {
+        word_end
+    };
+    Some((end, delim))
+}
+
+fn number_end(line: &str, start: usize, extra: &str) -> usize {
+    let b = line.as_bytes();
+    let mut i = start;
+    while i < b.len() {
+        let c = b[i];
+        let exponent_sign = matches!(c, b'+' | b'-')
+            && i > start
+            && matches!(b[i - 1], b'e' | b'E')
+            && !line[start..].starts_with("0x");
+        if c.is_ascii_alphanumeric()
+            || c == b'_'
+            || exponent_sign
+            || (c == b'.' && b.get(i + 1).is_some_and(u8::is_ascii_digit))
+            || extra.as_bytes().contains(&c)
+        {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+fn is_number(s: &str) -> bool {
+    let s = s.strip_prefix(['-', '+']).unwrap_or(s);
+    !s.is_empty()
+        && (s.parse::<f64>().is_ok()
+            || s.strip_prefix("0x")
+                .or_else(|| s.strip_prefix("0o"))
+                .is_some_and(|h| !h.is_empty() && h.chars().all(|c| c.is_ascii_hexdigit())))
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+fn ident_end(line: &str, start: usize) -> usize {
+    let b = line.as_bytes();
+    let mut i = start;
+    while i < b.len() && (b[i].is_ascii_alphanumeric() || b[i] == b'_') {
+        i += 1;
+    }
+    i
+}
+
+fn is_css_ident(c: u8) -> bool {
+    c.is_ascii_alphabetic() || matches!(c, b'_' | b'-' | b'*')
+}
+
+fn css_ident_end(line: &str, start: usize) -> usize {
+    let b = line.as_bytes();
+    let mut i = start;
+    while i < b.len() && (b[i].is_ascii_alphanumeric() || matches!(b[i], b'_' | b'-')) {
+        i += 1;
+    }
+    i.max(start + usize::from(start < b.len() && b[start] == b'*'))
+}
+
+fn skip_spaces(line: &str, i: usize) -> usize {
+    let b = line.as_bytes();
+    let mut i = i;
+    while i < b.len() && (b[i] == b' ' || b[i] == b'\t') {
+        i += 1;
+    }
+    i
+}
+
+fn next_byte(line: &str, i: usize) -> Option<u8> {
+    line.as_bytes().get(skip_spaces(line, i)).copied()
+}
+
+fn char_len(line: &str, i: usize) -> usize {
+    line[i..].chars().next().map_or(1, char::len_utf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Class, Highlighter};
+    use crate::commands::cat::syntax;
+
+    fn classes(lang: &str, lines: &[&str]) -> Vec<Vec<(Class, String)>> {
+        let mut hl = Highlighter::new(syntax::by_name(lang).unwrap());
+        lines
+            .iter()
+            .map(|line| {
+                hl.spans(line)
+                    .into_iter()
+                    .map(|(class, range)| (class, line[range].to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn class_of(spans: &[(Class, String)], text: &str) -> Option<Class> {
+        spans.iter().find(|(_, t)| t == text).map(|(c, _)| *c)
+    }
+
+    #[test]
+    fn rust_attributes_are_not_comments() {
+        let out = classes("rust", &["#[derive(Debug)]", "struct Foo; // TODO tidy"]);
+        assert_eq!(
+            out[0][0],
+            (Class::Attribute, "#[derive(Debug)]".to_string())
+        );
+        assert_eq!(class_of(&out[1], "struct"), Some(Class::Keyword));
+        assert_eq!(class_of(&out[1], "Foo"), Some(Class::Type));
+        assert_eq!(class_of(&out[1], "TODO"), Some(Class::Todo));
+    }
+
+    #[test]
+    fn block_comments_span_lines() {
+        let out = classes("c", &["int a; /* start", "still comment", "end */ int b;"]);
+        assert_eq!(out[1], vec![(Class::Comment, "still comment".to_string())]);
+        assert_eq!(out[2][0], (Class::Comment, "end */".to_string()));
+        assert_eq!(class_of(&out[2], "int"), Some(Class::Type));
+    }
+
+    #[test]
+    fn rust_raw_strings_keep_state() {
+        let out = classes(
+            "rust",
+            &["let s = r#\"a \"quoted", "// not a comment\"#; x"],
+        );
+        assert_eq!(
+            out[1][0],
+            (Class::String, "// not a comment\"#".to_string())
+        );
+        assert_eq!(out[1][1], (Class::Plain, "; x".to_string()));
+    }
+
+    #[test]
+    fn markdown_bullets_are_list_markers() {
+        let out = classes("markdown", &["* item with `code`", "# Title"]);
+        assert_eq!(out[0][0], (Class::Keyword, "*".to_string()));
+        assert_eq!(class_of(&out[0], "`code`"), Some(Class::String));
+        assert_eq!(out[1], vec![(Class::Heading, "# Title".to_string())]);
+    }
+
+    #[test]
+    fn markdown_fences_use_the_inner_grammar() {
+        let out = classes("markdown", &["```rust", "fn main() {}", "```", "fn"]);
+        assert_eq!(class_of(&out[1], "fn"), Some(Class::Keyword));
+        assert_eq!(out[3], vec![(Class::Plain, "fn".to_string())]);
+    }
+
+    #[test]
+    fn config_keys_are_marked() {
+        let yaml = classes("yaml", &["name: demo", "count: 3", "run: |", "  echo: hi"]);
+        assert_eq!(class_of(&yaml[0], "name"), Some(Class::Key));
+        assert_eq!(class_of(&yaml[1], "3"), Some(Class::Number));
+        assert_eq!(yaml[3], vec![(Class::String, "  echo: hi".to_string())]);
+
+        let toml = classes("toml", &["[package]", "edition = \"2024\""]);
+        assert_eq!(toml[0], vec![(Class::Heading, "[package]".to_string())]);
+        assert_eq!(class_of(&toml[1], "edition"), Some(Class::Key));
+    }
+
+    #[test]
+    fn no_break_spaces_stay_inside_their_token() {
+        let html = classes("html", &["<a\u{a0}b=\"c\">", "<a x\u{a0}y=\"c\">"]);
+        let text = html[0].iter().map(|(_, t)| t.as_str()).collect::<String>();
+        assert_eq!(text, "<a\u{a0}b=\"c\">");
+        assert_eq!(class_of(&html[1], "x\u{a0}y"), Some(Class::Key));
+        assert_eq!(class_of(&html[1], "\"c\""), Some(Class::String));
+
+        let yaml = classes("yaml", &["]\u{a0}", "key: a\u{a0}"]);
+        assert_eq!(yaml[0], vec![(Class::String, "]\u{a0}".to_string())]);
+        assert_eq!(class_of(&yaml[1], "a\u{a0}"), Some(Class::String));
+    }
+
+    #[test]
+    fn shell_heredocs_are_strings() {
+        let out = classes("sh", &["cat <<EOF", "$HOME # literal", "EOF", "echo $HOME"]);
+        assert_eq!(out[1], vec![(Class::String, "$HOME # literal".to_string())]);
+        assert_eq!(class_of(&out[3], "$HOME"), Some(Class::Constant));
+    }
+}
@@ -1,9 +1,25 @@
+use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fs;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 
+mod highlight;
+mod sniff;
+pub(crate) mod syntax;
+mod viewer;
+mod visible;
+
+use highlight::Highlighter;
+use sniff::Encoding;
+pub(crate) use viewer::preview;
+
+use crate::commands::diff::{self, LineChange};
+use crate::commands::dump;
 use crate::core::devicons;
+use crate::core::pager::{self, Paging};
+use crate::core::process;
+use crate::core::settings::{self, Settings};
 use crate::core::style::Style;
 use crate::core::theme;
 
@@ -13,6 +29,14 @@ enum Mode {
     Pretty,
 }
 
+/// What pretty mode does with input that sniffs as binary.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Binary {
+    Notice,
+    Hex,
+    Text,
+}
+
 struct Opts {
     mode: Mode,
     number: Option<bool>,
@@ -20,10 +44,54 @@ struct Opts {
     squeeze_blank: bool,
     show_ends: bool,
     show_tabs: bool,
+    show_nonprinting: bool,
+    binary: Binary,
+    encoding: Option<Encoding>,
     theme: Option<String>,
+    language: Option<&'static syntax::Grammar>,
+    paging: Option<Paging>,
+    view: bool,
+    line_ranges: Vec<LineRange>,
+    highlight_lines: Vec<LineRange>,
     files: Vec<String>,
 }
 
+/// Inclusive 1-based line span from `-r`/`-H`: `N`, `N:M`, `N:`, `:M` or
+/// `N:+K` (K lines after N).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+impl LineRange {
+    fn parse(value: &str) -> Option<Self> {
+        let num = |s: &str| s.trim().parse::<usize>().ok();
+        let (start, end) = match value.split_once(':') {
+            None => {
+                let n = num(value)?;
+                (n, n)
+            }
+            Some((a, b)) => {
+                let start = if a.is_empty() { 1 } else { num(a)? };
+                let end = if b.is_empty() {
+                    usize::MAX
+                } else if let Some(count) = b.strip_prefix('+') {
+                    start.saturating_add(num(count)?)
+                } else {
+                    num(b)?
+                };
+                (start, end)
+            }
+        };
+        (start >= 1 && start <= end).then_some(Self { start, end })
+    }
+
+    fn contains(&self, line: usize) -> bool {
+        (self.start..=self.end).contains(&line)
+    }
+}
+
 impl Opts {
     fn default(mode: Mode) -> Self {
         Self {
@@ -33,7 +101,15 @@ impl Opts {
             squeeze_blank: false,
             show_ends: false,
             show_tabs: false,
+            show_nonprinting: false,
+            binary: Binary::Notice,
+            encoding: None,
             theme: None,
+            language: None,
+            paging: None,
+            view: false,
+            line_ranges: Vec::new(),
+            highlight_lines: Vec::new(),
             files: Vec::new(),
         }
     }
@@ -56,7 +132,21 @@ fn run_with_mode(args: &[OsString], default_mode: Mode) -> Result<(), String> {
         return Ok(());
     }
 
-    let opts = parse(args, default_mode)?;
+    let opts = parse(args, default_mode, settings::current())?;
+    if opts.view {
+        let theme = theme::active(opts.theme.as_deref());
+        return viewer::run(
+            &opts.files,
+            opts.language,
+            opts.encoding,
+            &Style::for_stdout(),
+            &theme,
+        );
+    }
+    let _pager = match opts.mode {
+        Mode::Pretty => pager::start(opts.paging),
+        Mode::Plain => None,
+    };
     let mut style = Style::for_stdout();
     let theme = theme::active(opts.theme.as_deref());
 
@@ -97,8 +187,24 @@ fn run_with_mode(args: &[OsString], default_mode: Mode) -> Result<(), String> {
     Ok(())
 }
 
-fn parse(args: &[OsString], default_mode: Mode) -> Result<Opts, String> {
+fn parse(args: &[OsString], default_mode: Mode, settings: &Settings) -> Result<Opts, String> {
     let mut opts = Opts::default(default_mode);
+    let section = match default_mode {
+        Mode::Plain => "cat",
+        Mode::Pretty => "bat",
+    };
+    if let Some(v) = settings.get_bool(section, "number") {
+        opts.number = Some(v);
+    }
+    if let Some(v) = settings.get_bool(section, "squeeze-blank") {
+        opts.squeeze_blank = v;
+    }
+    // `DUSK_THEME` beats the file; only `--theme` beats the variable.
+    if std::env::var_os("DUSK_THEME").is_none()
+        && let Some(v) = settings.get_str(section, "theme")
+    {
+        opts.theme = Some(v.to_string());
+    }
     let mut it = args.iter().peekable();
 
     while let Some(arg) = it.next() {
@@ -130,6 +236,103 @@ fn parse(args: &[OsString], default_mode: Mode) -> Result<Opts, String> {
             opts.theme = Some(name.to_string_lossy().to_string());
             continue;
         }
+        if s == "--view" {
+            opts.view = true;
+            continue;
+        }
+        if s == "--show-all" {
+            opts.show_nonprinting = true;
+            opts.show_ends = true;
+            opts.show_tabs = true;
+            continue;
+        }
+        if s == "--encoding" || s.starts_with("--encoding=") {
+            let value = match s.strip_prefix("--encoding=") {
+                Some(v) => v.to_string(),
+                None => it
+                    .next()
+                    .ok_or("--encoding requires a name")?
+                    .to_string_lossy()
+                    .to_string(),
+            };
+            opts.encoding = if value == "auto" {
+                None
+            } else {
+                Some(Encoding::parse(&value).ok_or_else(|| {
+                    format!(
+                        "unknown encoding: {value} (use auto, utf-8, utf-16, utf-16le, utf-16be or latin1)"
+                    )
+                })?)
+            };
+            continue;
+        }
+        if s == "--binary" || s.starts_with("--binary=") {
+            let value = match s.strip_prefix("--binary=") {
+                Some(v) => v.to_string(),
+                None => it
+                    .next()
+                    .ok_or("--binary requires notice|hex|text")?
+                    .to_string_lossy()
+                    .to_string(),
+            };
+            opts.binary = match value.as_str() {
+                "notice" => Binary::Notice,
+                "hex" => Binary::Hex,
+                "text" => Binary::Text,
+                _ => return Err("--binary supports: notice | hex | text".to_string()),
+            };
+            continue;
+        }
+        if s == "--paging" {
+            let Some(mode) = it.next() else {
+                return Err("--paging requires auto|always|never".to_string());
+            };
+            opts.paging = Some(Paging::from_flag(&mode.to_string_lossy())?);
+            continue;
+        }
+        if let Some(mode) = s.strip_prefix("--paging=") {
+            opts.paging = Some(Paging::from_flag(mode)?);
+            continue;
+        }
+        let range_flag = match s.as_ref() {
+            "-r" | "--line-range" => Some(("--line-range", None)),
+            "-H" | "--highlight-line" => Some(("--highlight-line", None)),
+            _ => s
+                .strip_prefix("--line-range=")
+                .map(|v| ("--line-range", Some(v.to_string())))
+                .or_else(|| {
+                    s.strip_prefix("--highlight-line=")
+                        .map(|v| ("--highlight-line", Some(v.to_string())))
+                }),
+        };
+        if let Some((flag, value)) = range_flag {
+            let value = match value {
+                Some(v) => v,
+                None => it
+                    .next()
+                    .ok_or_else(|| format!("{flag} requires N, N:M, N:, :M or N:+K"))?
+                    .to_string_lossy()
+                    .to_string(),
+            };
+            let range = LineRange::parse(&value).ok_or_else(|| {
+                format!("invalid {flag} value: {value} (use N, N:M, N:, :M or N:+K)")
+            })?;
+            if flag == "--line-range" {
+                opts.line_ranges.push(range);
+            } else {
+                opts.highlight_lines.push(range);
+            }
+            continue;
+        }
+        if s == "--language" || s == "-l" {
+            let Some(name) = it.next() else {
+                return Err(format!("{s} requires a language name"));
+            };
+            let name = name.to_string_lossy();
+            opts.language =
+                Some(syntax::by_name(&name).ok_or_else(|| format!("unknown language: {name}"))?);
+            continue;
+        }
 
         if s.starts_with('-') && s.len() > 1 {
             for ch in s[1..].chars() {
@@ -142,6 +345,12 @@ fn parse(args: &[OsString], default_mode: Mode) -> Result<Opts, String> {
                     's' => opts.squeeze_blank = true,
                     'E' => opts.show_ends = true,
                     'T' => opts.show_tabs = true,
+                    'v' => opts.show_nonprinting = true,
+                    'A' => {
+                        opts.show_nonprinting = true;
+                        opts.show_ends = true;
+                        opts.show_tabs = true;
+                    }
                     'p' => opts.mode = Mode::Plain,
                     _ => return Err(format!("unknown flag: -{ch}")),
                 }
@@ -186,6 +395,17 @@ fn print_help() {
     println!("  {} {}", opt("-s"), desc("Squeeze multiple blank lines"));
     println!("  {} {}", opt("-E"), desc("Display $ at end of each line"));
     println!("  {} {}", opt("-T"), desc("Display TAB as ^I"));
+    println!(
+        "  {} {}",
+        opt("-v"),
+        desc("Show nonprinting characters (^X and M- notation; glyphs in pretty mode)")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-A"),
+        opt("--show-all"),
+        desc("Same as -vET")
+    );
     println!();
     println!("{}", opt("PRETTY FLAGS"));
     println!(
@@ -210,6 +430,52 @@ fn print_help() {
         arg("<name>"),
         desc("Theme for pretty mode")
     );
+    println!(
+        "  {}, {} {} {}",
+        opt("--language"),
+        opt("-l"),
+        arg("<name>"),
+        desc("Highlight as <name> instead of detecting from name/shebang/modeline")
+    );
+    println!(
+        "  {}, {} {} {}",
+        opt("--line-range"),
+        opt("-r"),
+        arg("N:M"),
+        desc("Only print lines N..M (also N, N:, :M, N:+K; repeatable)")
+    );
+    println!(
+        "  {}, {} {} {}",
+        opt("--highlight-line"),
+        opt("-H"),
+        arg("N:M"),
+        desc("Highlight lines in reverse video (repeatable)")
+    );
+    println!(
+        "  {} {} {}",
+        opt("--encoding"),
+        arg("<name>"),
+        desc("Decode input as auto (default), utf-8, utf-16, utf-16le, utf-16be or latin1")
+    );
+    println!(
+        "  {} {} {}",
+        opt("--binary"),
+        arg("notice|hex|text"),
+        desc("Binary input: one-line notice (default), hex dump, or print anyway")
+    );
+    println!(
+        "  {} {}",
+        opt("--view"),
+        desc(
+            "Open the interactive viewer (/ ? search, n/N, : goto line, h/l scroll, Tab next file)"
+        )
+    );
+    println!(
+        "  {} {} {}",
+        opt("--paging"),
+        arg("auto|always|never"),
+        desc("Pipe pretty output through $DUSK_PAGER/$PAGER (default: less -RFX)")
+    );
     println!(
         "  {}, {} {}",
         opt("-h"),
@@ -218,6 +484,32 @@ fn print_help() {
     );
 }
 
+/// Bytes of `--binary hex` read at a time; a multiple of the 16-byte row.
+const HEX_CHUNK: u64 = 64 * 1024;
+
+/// `--binary hex`, a chunk at a time so huge files are never held whole.
+fn stream_hex<R: BufRead>(
+    reader: &mut R,
+    style: &Style,
+    theme: theme::Theme,
+) -> Result<(), String> {
+    let mut chunk = Vec::new();
+    let mut offset = 0;
+    loop {
+        chunk.clear();
+        let read = reader
+            .by_ref()
+            .take(HEX_CHUNK)
+            .read_to_end(&mut chunk)
+            .map_err(|err| format!("read error: {err}"))?;
+        if read == 0 {
+            return Ok(());
+        }
+        dump::print_hex(&chunk, offset, style, theme);
+        offset += read;
+    }
+}
+
 fn output_stream<R: BufRead>(
     path: Option<&Path>,
     reader: &mut R,
@@ -232,88 +524,173 @@ fn output_stream<R: BufRead>(
         && !opts.squeeze_blank
         && !opts.show_ends
         && !opts.show_tabs
+        && !opts.show_nonprinting
+        && opts.encoding.is_none()
+        && opts.line_ranges.is_empty()
     {
         let mut out = io::stdout().lock();
-        let mut raw = Vec::new();
-        reader
-            .read_to_end(&mut raw)
-            .map_err(|err| format!("read error: {err}"))?;
-        out.write_all(&raw)
-            .map_err(|err| format!("write error: {err}"))?;
+        io::copy(reader, &mut out).map_err(|err| format!("copy error: {err}"))?;
         return Ok(());
     }
 
-    if matches!(opts.mode, Mode::Pretty) {
-        let label = path
-            .map(|p| {
-                if p == Path::new("-") {
-                    "stdin".to_string()
-                } else {
-                    p.display().to_string()
-                }
-            })
-            .unwrap_or_else(|| "stdin".to_string());
-        let icon = path
-            .filter(|p| p.to_str() != Some("-"))
-            .map(devicons::file_icon)
-            .unwrap_or("");
-        let gap = if style.maybe_icon(icon).is_empty() {
-            ""
-        } else {
-            " "
+    // Pretty mode looks at the first read of the input (at most a buffer,
+    // and whatever a pipe has so far) to catch binaries, sniff the encoding
+    // and detect the grammar from the name, shebang or a modeline; lines are
+    // then decoded and painted as they arrive, so `tail -f log | dusk bat`
+    // works and large files are never held whole. Plain mode only decodes on
+    // `--encoding`.
+    let mut decoded;
+    let mut highlighter = None;
+    let reader: &mut dyn BufRead = if matches!(opts.mode, Mode::Pretty) {
+        let head = reader
+            .fill_buf()
+            .map_err(|err| format!("read error: {err}"))?
+            .to_vec();
+        let complete = path
+            .filter(|p| *p != Path::new("-"))
+            .and_then(|p| fs::metadata(p).ok())
+            .is_some_and(|md| md.is_file() && md.len() <= head.len() as u64);
+        let detected = opts
+            .encoding
+            .or_else(|| sniff::detect_head(&head, complete));
+        if opts.binary != Binary::Text && detected.is_none() {
+            print_header(path, None, style, &theme);
+            if opts.binary == Binary::Hex {
+                stream_hex(reader, style, theme)?;
+            } else {
+                let len = io::copy(reader, &mut io::sink())
+                    .map_err(|err| format!("read error: {err}"))?;
+                println!(
+                    "{}",
+                    style.paint(theme.warn, format!("binary file, {len} bytes"))
+                );
+            }
+            return Ok(());
+        }
+        let encoding = detected.unwrap_or(Encoding::Utf8);
+        let text = sniff::decode(&head, encoding);
+        let details = match sniff::line_endings(&text) {
+            Some(endings) => format!("{} · {endings}", encoding.label()),
+            None => encoding.label().to_string(),
         };
-        println!(
-            "{}",
-            style.paint(
-                theme.title,
-                format!("-- {}{}{} --", style.maybe_icon(icon), gap, label)
-            )
-        );
-    }
+        print_header(path, Some(&details), style, &theme);
+        let grammar = opts.language.or_else(|| syntax::detect(path, &text));
+        highlighter = grammar.map(Highlighter::new);
+        decoded = BufReader::new(sniff::Decoder::new(reader, encoding));
+        &mut decoded
+    } else if let Some(encoding) = opts.encoding {
+        decoded = BufReader::new(sniff::Decoder::new(reader, encoding));
+        &mut decoded
+    } else {
+        reader
+    };
+
+    let changes = match (opts.mode, path) {
+        (Mode::Pretty, Some(p)) if p != Path::new("-") => git_changes(p),
+        _ => None,
+    };
+    let highlight_on = style.color && !opts.highlight_lines.is_empty();
 
     let mut idx = 0usize;
+    let mut lineno = 0usize;
+    let mut last_shown: Option<usize> = None;
     let mut prev_blank = false;
-    let mut line = String::new();
+    let mut bytes = Vec::new();
     loop {
-        line.clear();
+        bytes.clear();
         let read = reader
-            .read_line(&mut line)
+            .read_until(b'\n', &mut bytes)
             .map_err(|err| format!("read error: {err}"))?;
         if read == 0 {
             break;
         }
 
-        let had_nl = line.ends_with('\n');
+        let had_nl = bytes.ends_with(b"\n");
         if had_nl {
-            line.pop();
-            if line.ends_with('\r') {
-                line.pop();
+            bytes.pop();
+            // `-v`/`-A` show the carriage return instead of dropping it.
+            if bytes.ends_with(b"\r") && !opts.show_nonprinting {
+                bytes.pop();
             }
         }
+        let line = String::from_utf8_lossy(&bytes);
 
-        let is_blank = line.is_empty();
+        lineno += 1;
+        if !opts.line_ranges.is_empty() && !opts.line_ranges.iter().any(|r| r.contains(lineno)) {
+            // Keep multi-line comment/string state right for later ranges.
+            if let Some(hl) = &mut highlighter {
+                hl.spans(&line);
+            }
+            continue;
+        }
+
+        let is_blank = bytes.is_empty();
         if opts.squeeze_blank && is_blank && prev_blank {
             continue;
         }
         prev_blank = is_blank;
 
-        let mut rendered = if opts.show_tabs {
-            line.replace('\t', "^I")
-        } else {
-            line.clone()
+        if matches!(opts.mode, Mode::Pretty) && last_shown.is_some_and(|last| lineno > last + 1) {
+            println!("{}", style.paint(theme.subtle, "  ⋯"));
+        }
+        last_shown = Some(lineno);
+
+        let mut rendered = match (opts.mode, opts.show_nonprinting) {
+            (Mode::Plain, true) => visible::caret(&bytes, opts.show_tabs),
+            (Mode::Pretty, true) => {
+                let spans = highlighter.as_mut().map(|hl| hl.spans(&line));
+                visible::glyphs(&line, spans.as_ref(), opts.show_tabs, style, &theme)
+            }
+            // Highlight the source text first so `^I`/`$` markers never
+            // change how a line tokenizes.
+            (_, false) => {
+                let painted = match &mut highlighter {
+                    Some(hl) => hl.paint(&line, style, &theme),
+                    None => line.to_string(),
+                };
+                if opts.show_tabs {
+                    painted.replace('\t', "^I")
+                } else {
+                    painted
+                }
+            }
         };
 
         if opts.show_ends {
-            rendered.push('$');
+            if matches!(opts.mode, Mode::Pretty) && opts.show_nonprinting {
+                rendered.push_str(&style.paint(theme.subtle, "␊"));
+            } else {
+                rendered.push('$');
+            }
         }
 
-        if matches!(opts.mode, Mode::Pretty) {
-            rendered = stylize_line(&rendered, path, style, theme);
+        if highlight_on && opts.highlight_lines.iter().any(|r| r.contains(lineno)) {
+            rendered = format!(
+                "\x1b[7m{}\x1b[0m",
+                rendered.replace("\x1b[0m", "\x1b[0m\x1b[7m")
+            );
+        }
+
+        if let Some(changes) = &changes {
+            let (mark, color) = match changes.get(&lineno) {
+                Some(LineChange::Added) => ("+", theme.diff_add),
+                Some(LineChange::Modified) => ("~", theme.warn),
+                Some(LineChange::RemovedBelow) => ("_", theme.diff_del),
+                Some(LineChange::RemovedAbove) => ("‾", theme.diff_del),
+                None => (" ", ""),
+            };
+            print!("{}", style.paint(color, mark));
         }
 
         if number && (!opts.number_nonblank || !is_blank) {
             idx += 1;
-            print!("{} ", style.paint(theme.number, format!("{:>5} |", idx)));
+            // Ranges skip lines, so show real line numbers there.
+            let shown = if opts.line_ranges.is_empty() {
+                idx
+            } else {
+                lineno
+            };
+            print!("{} ", style.paint(theme.number, format!("{:>5} |", shown)));
         }
 
         if had_nl {
@@ -326,141 +703,73 @@ fn output_stream<R: BufRead>(
     Ok(())
 }
 
-fn stylize_line(line: &str, path: Option<&Path>, style: &Style, theme: theme::Theme) -> String {
-    let ext = path
-        .and_then(|p| p.extension())
-        .and_then(|e| e.to_str())
-        .unwrap_or_default()
-        .to_ascii_lowercase();
-    let trimmed = line.trim_start();
-
-    if (trimmed.starts_with("# ") || trimmed.starts_with("##")) && ext == "md" {
-        return style.paint(theme.accent, line);
-    }
-
-    if trimmed.starts_with("//")
-        || trimmed.starts_with('#')
-        || trimmed.starts_with("/*")
-        || trimmed.starts_with('*')
-    {
-        return style.paint(theme.ok, line);
-    }
-
-    if line.contains("TODO") || line.contains("FIXME") {
-        return style.paint(theme.warn, line);
-    }
-
-    lexical_highlight(line, &ext, style, theme)
-}
-
-fn lexical_highlight(line: &str, ext: &str, style: &Style, theme: theme::Theme) -> String {
-    let keywords = match ext {
-        "rs" => &[
-            "fn", "let", "mut", "pub", "impl", "struct", "enum", "trait", "mod", "use", "match",
-            "if", "else", "for", "while", "loop", "return",
-        ][..],
-        "go" => &[
-            "func",
-            "var",
-            "const",
-            "type",
-            "struct",
-            "interface",
-            "package",
-            "import",
-            "if",
-            "else",
-            "for",
-            "range",
-            "return",
-            "switch",
-            "case",
-        ][..],
-        "py" => &[
-            "def", "class", "import", "from", "if", "elif", "else", "for", "while", "return",
-            "match", "case", "with", "as", "try", "except",
-        ][..],
-        "js" | "ts" | "jsx" | "tsx" => &[
-            "function",
-            "const",
-            "let",
-            "var",
-            "class",
-            "import",
-            "from",
-            "export",
-            "if",
-            "else",
-            "for",
-            "while",
-            "return",
-            "async",
-            "await",
-            "interface",
-            "type",
-        ][..],
-        "s" | "asm" => &[
-            "mov", "lea", "add", "sub", "mul", "div", "call", "ret", "jmp", "je", "jne", "cmp",
-            "push", "pop",
-        ][..],
-        _ => &[][..],
-    };
-
-    let mut out = String::new();
-    let mut token = String::new();
-    let mut in_string = false;
-    let mut string_delim = '\0';
-
-    let flush_token = |tok: &mut String, out: &mut String| {
-        if tok.is_empty() {
-            return;
-        }
-        let t = tok.as_str();
-        let painted = if keywords.iter().any(|k| *k == t) {
-            style.paint(theme.accent, t)
-        } else if t.starts_with("0x") || t.chars().all(|c| c.is_ascii_digit()) {
-            style.paint(theme.number, t)
-        } else if ext == "s" || ext == "asm" {
-            if t.starts_with('r') || ["eax", "ebx", "ecx", "edx"].contains(&t) {
-                style.paint(theme.ok, t)
+/// `-- icon name --`, plus the encoding and line endings when the input was
+/// decoded as text.
+fn print_header(path: Option<&Path>, details: Option<&str>, style: &Style, theme: &theme::Theme) {
+    let label = path
+        .map(|p| {
+            if p == Path::new("-") {
+                "stdin".to_string()
             } else {
-                style.paint(theme.subtle, t)
+                p.display().to_string()
             }
-        } else {
-            style.paint(theme.subtle, t)
-        };
-        out.push_str(&painted);
-        tok.clear();
+        })
+        .unwrap_or_else(|| "stdin".to_string());
+    let icon = path
+        .filter(|p| p.to_str() != Some("-"))
+        .map(devicons::file_icon)
+        .unwrap_or("");
+    let gap = if style.maybe_icon(icon).is_empty() {
+        ""
+    } else {
+        " "
     };
+    println!(
+        "{}{}",
+        style.paint(
+            theme.title,
+            format!("-- {}{}{} --", style.maybe_icon(icon), gap, label)
+        ),
+        details
+            .map(|d| format!(" {}", style.paint(theme.subtle, d)))
+            .unwrap_or_default()
+    );
+}
 
-    for ch in line.chars() {
-        if in_string {
-            token.push(ch);
-            if ch == string_delim {
-                out.push_str(&style.paint(theme.warn, token.clone()));
-                token.clear();
-                in_string = false;
-            }
-            continue;
-        }
-
-        if ch == '"' || ch == '\'' {
-            flush_token(&mut token, &mut out);
-            in_string = true;
-            string_delim = ch;
-            token.push(ch);
-            continue;
-        }
-
-        if ch.is_alphanumeric() || ch == '_' || ch == 'x' {
-            token.push(ch);
-            continue;
-        }
-
-        flush_token(&mut token, &mut out);
-        out.push_str(&style.paint(theme.info, ch.to_string()));
+/// Change markers for `path` against the git index, or `None` outside a work
+/// tree (or without git).
+fn git_changes(path: &Path) -> Option<BTreeMap<usize, LineChange>> {
+    if !process::command_exists("git") || !path.is_file() {
+        return None;
     }
-    flush_token(&mut token, &mut out);
+    let dir = path
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path.file_name()?.to_str()?;
+    let diff = process::run_capture_in(
+        dir,
+        "git",
+        &["diff", "--no-color", "--no-ext-diff", "-U0", "--", name],
+    )
+    .ok()??;
+    Some(diff::line_changes(&diff))
+}
 
-    out
+#[cfg(test)]
+mod tests {
+    use super::LineRange;
+
+    #[test]
+    fn parses_bat_line_range_forms() {
+        let r = |start, end| Some(LineRange { start, end });
+        assert_eq!(LineRange::parse("10:40"), r(10, 40));
+        assert_eq!(LineRange::parse("7"), r(7, 7));
+        assert_eq!(LineRange::parse(":3"), r(1, 3));
+        assert_eq!(LineRange::parse("5:"), r(5, usize::MAX));
+        assert_eq!(LineRange::parse("5:+2"), r(5, 7));
+        assert_eq!(LineRange::parse("0:2"), None);
+        assert_eq!(LineRange::parse("9:3"), None);
+        assert_eq!(LineRange::parse("x"), None);
+    }
 }
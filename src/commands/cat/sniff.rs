@@ -0,0 +1,369 @@
+use std::io::{self, Read};
+
+/// How much of the input is inspected before deciding it is binary.
+const SNIFF_LEN: usize = 8 * 1024;
+
+/// Text encodings pretty mode can decode to UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    /// Parses an `--encoding` value; bare `utf-16` is little endian, which
+    /// `decode` overrides when the input starts with a big-endian BOM.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(Self::Utf8),
+            "utf-16" | "utf16" | "utf-16le" | "utf16le" => Some(Self::Utf16Le),
+            "utf-16be" | "utf16be" => Some(Self::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" | "iso8859-1" => Some(Self::Latin1),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf8Bom => "UTF-8 BOM",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+}
+
+/// Guesses the encoding of `data`, or `None` when it looks binary.
+///
+/// A BOM wins. Without one, NULs falling mostly on odd (or even) byte
+/// positions mean UTF-16 LE (BE); valid UTF-8 is UTF-8; anything else
+/// without NULs or a pile of control bytes is read as Latin-1.
+pub fn detect(data: &[u8]) -> Option<Encoding> {
+    detect_head(data, true)
+}
+
+/// `detect` for the start of an input that may go on (a pipe, or the first
+/// read of a file): unless `complete` says `head` is everything, a multi-byte
+/// character cut off at its end does not make it Latin-1.
+pub fn detect_head(data: &[u8], complete: bool) -> Option<Encoding> {
+    if data.starts_with(b"\xef\xbb\xbf") {
+        return Some(Encoding::Utf8Bom);
+    }
+    if data.starts_with(b"\xff\xfe") {
+        return Some(Encoding::Utf16Le);
+    }
+    if data.starts_with(b"\xfe\xff") {
+        return Some(Encoding::Utf16Be);
+    }
+
+    let head = &data[..data.len().min(SNIFF_LEN)];
+    if let Some(utf16) = utf16_without_bom(head) {
+        return Some(utf16);
+    }
+    if head.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => return Some(Encoding::Utf8),
+        // A multi-byte character cut off by the sample boundary.
+        Err(err) if err.error_len().is_none() && (!complete || data.len() > SNIFF_LEN) => {
+            return Some(Encoding::Utf8);
+        }
+        Err(_) => {}
+    }
+    let controls = head
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    (controls * 10 < head.len()).then_some(Encoding::Latin1)
+}
+
+/// ASCII-heavy UTF-16 has a NUL in every other byte; require most pairs to
+/// look like that and the other half to be almost NUL-free.
+fn utf16_without_bom(head: &[u8]) -> Option<Encoding> {
+    let pairs = head.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let even = head.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd = head.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if odd * 10 >= pairs * 4 && even * 10 < pairs {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= pairs * 4 && odd * 10 < pairs {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Decodes `data` to UTF-8, dropping a leading BOM (for UTF-16 the BOM also
+/// decides the byte order). Invalid sequences become U+FFFD.
+pub fn decode(data: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => {
+            let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+            String::from_utf8_lossy(data).into_owned()
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let (data, little) = if let Some(rest) = data.strip_prefix(b"\xff\xfe") {
+                (rest, true)
+            } else if let Some(rest) = data.strip_prefix(b"\xfe\xff") {
+                (rest, false)
+            } else {
+                (data, encoding == Encoding::Utf16Le)
+            };
+            let units = data.chunks(2).map(|pair| match *pair {
+                [a, b] if little => u16::from_le_bytes([a, b]),
+                [a, b] => u16::from_be_bytes([a, b]),
+                _ => 0xfffd,
+            });
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+        Encoding::Latin1 => data.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Decodes a byte stream to UTF-8 as it is read, by the same rules as
+/// `decode`, so input of any size (or a pipe that never ends) is shown as it
+/// arrives. UTF-8 passes through untouched apart from the BOM; invalid bytes
+/// are left for the reader to replace.
+pub struct Decoder<R> {
+    inner: R,
+    encoding: Encoding,
+    /// UTF-16 byte order; a BOM overrides the encoding's.
+    little: bool,
+    /// Whether the start has been checked for a BOM.
+    started: bool,
+    /// Bytes read but not decoded yet: the start while a BOM may still be
+    /// arriving, half a UTF-16 unit, or a high surrogate awaiting its pair.
+    pending: Vec<u8>,
+    /// Decoded bytes not handed out yet, from `at` on.
+    out: Vec<u8>,
+    at: usize,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(inner: R, encoding: Encoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            little: encoding != Encoding::Utf16Be,
+            started: false,
+            pending: Vec::new(),
+            out: Vec::new(),
+            at: 0,
+        }
+    }
+
+    fn decode_pending(&mut self, eof: bool) {
+        let utf16 = matches!(self.encoding, Encoding::Utf16Le | Encoding::Utf16Be);
+        if !self.started {
+            let bom_len = match self.encoding {
+                Encoding::Latin1 => 0,
+                _ if utf16 => 2,
+                _ => 3,
+            };
+            if self.pending.len() < bom_len && !eof {
+                return;
+            }
+            self.started = true;
+            let bom = if utf16 {
+                match self.pending.get(..2) {
+                    Some(b"\xff\xfe") => Some(true),
+                    Some(b"\xfe\xff") => Some(false),
+                    _ => None,
+                }
+                .map(|little| (little, 2))
+            } else {
+                (bom_len == 3 && self.pending.starts_with(b"\xef\xbb\xbf"))
+                    .then_some((self.little, 3))
+            };
+            if let Some((little, len)) = bom {
+                self.little = little;
+                self.pending.drain(..len);
+            }
+        }
+
+        match self.encoding {
+            Encoding::Utf8 | Encoding::Utf8Bom => self.out.append(&mut self.pending),
+            Encoding::Latin1 => {
+                let text = self.pending.drain(..).map(char::from).collect::<String>();
+                self.out.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let little = self.little;
+                let mut units = self
+                    .pending
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        let pair = [pair[0], pair[1]];
+                        if little {
+                            u16::from_le_bytes(pair)
+                        } else {
+                            u16::from_be_bytes(pair)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let mut used = units.len() * 2;
+                if eof {
+                    // A stray last byte is an invalid unit, as in `decode`.
+                    if used < self.pending.len() {
+                        units.push(0xfffd);
+                        used += 1;
+                    }
+                } else if units.last().is_some_and(|u| (0xd800..0xdc00).contains(u)) {
+                    units.pop();
+                    used -= 2;
+                }
+                let text = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect::<String>();
+                self.out.extend_from_slice(text.as_bytes());
+                self.pending.drain(..used);
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0u8; 8192];
+        let mut eof = false;
+        while self.at == self.out.len() && !eof {
+            self.out.clear();
+            self.at = 0;
+            let n = self.inner.read(&mut chunk)?;
+            eof = n == 0;
+            self.pending.extend_from_slice(&chunk[..n]);
+            self.decode_pending(eof);
+        }
+        let n = buf.len().min(self.out.len() - self.at);
+        buf[..n].copy_from_slice(&self.out[self.at..self.at + n]);
+        self.at += n;
+        Ok(n)
+    }
+}
+
+/// Line-ending style reported in the pretty-mode header.
+pub fn line_endings(text: &str) -> Option<&'static str> {
+    let total = text.matches('\n').count();
+    let crlf = text.matches("\r\n").count();
+    match (total, crlf) {
+        (0, _) => None,
+        (_, 0) => Some("LF"),
+        (t, c) if t == c => Some("CRLF"),
+        _ => Some("mixed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::{Decoder, Encoding, SNIFF_LEN, decode, detect, detect_head, line_endings};
+
+    #[test]
+    fn flags_nul_bytes_and_control_heavy_data() {
+        assert_eq!(detect(b"plain text\n"), Some(Encoding::Utf8));
+        assert_eq!(detect("héllo → wörld".as_bytes()), Some(Encoding::Utf8));
+        assert_eq!(detect(b"\x7fELF\x02\x01\x00\x00"), None);
+        assert_eq!(detect(b"\x01\x02\x03\x04\xff\x05"), None);
+    }
+
+    #[test]
+    fn ignores_a_character_split_by_the_sample_boundary() {
+        let mut data = vec![b'a'; SNIFF_LEN - 1];
+        data.extend_from_slice("é tail".as_bytes());
+        assert_eq!(detect(&data), Some(Encoding::Utf8));
+    }
+
+    #[test]
+    fn a_cut_off_character_in_an_open_ended_head_stays_utf8() {
+        let head = "ab é".as_bytes();
+        let cut = &head[..head.len() - 1];
+        assert_eq!(detect_head(cut, false), Some(Encoding::Utf8));
+        assert_eq!(detect_head(cut, true), Some(Encoding::Latin1));
+    }
+
+    #[test]
+    fn decoder_matches_decode_however_the_input_is_split() {
+        /// Hands out at most `step` bytes per read.
+        struct Trickle<'a>(&'a [u8], usize);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.1.min(buf.len()).min(self.0.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+        let utf16 = |s: &str, little: bool| {
+            s.encode_utf16()
+                .flat_map(|u| {
+                    if little {
+                        u.to_le_bytes()
+                    } else {
+                        u.to_be_bytes()
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut le_bom = b"\xff\xfe".to_vec();
+        le_bom.extend(utf16("a 🦀\nb\n", true));
+        let mut odd = utf16("ok", false);
+        odd.push(b'x');
+        let cases = [
+            (b"\xef\xbb\xbfplain\n".to_vec(), Encoding::Utf8Bom),
+            ("héllo\nwörld".as_bytes().to_vec(), Encoding::Utf8),
+            (b"caf\xe9\n".to_vec(), Encoding::Latin1),
+            (le_bom.clone(), Encoding::Utf16Le),
+            (le_bom, Encoding::Utf16Be),
+            (utf16("🦀 be", false), Encoding::Utf16Be),
+            (odd, Encoding::Utf16Be),
+        ];
+        for (data, encoding) in cases {
+            for step in [1, 2, 3, 64] {
+                let mut text = String::new();
+                Decoder::new(Trickle(&data, step), encoding)
+                    .read_to_string(&mut text)
+                    .expect("decode");
+                assert_eq!(text, decode(&data, encoding), "{encoding:?} by {step}");
+            }
+        }
+    }
+
+    #[test]
+    fn detects_boms_utf16_and_latin1() {
+        assert_eq!(detect(b"\xef\xbb\xbfid;name\n"), Some(Encoding::Utf8Bom));
+        assert_eq!(detect(b"\xff\xfei\x00d\x00"), Some(Encoding::Utf16Le));
+        assert_eq!(detect(b"\x00i\x00d\x00;\x00n"), Some(Encoding::Utf16Be));
+        assert_eq!(detect(b"i\x00d\x00;\x00n\x00"), Some(Encoding::Utf16Le));
+        assert_eq!(detect(b"caf\xe9;cr\xe8me\r\n"), Some(Encoding::Latin1));
+    }
+
+    #[test]
+    fn decodes_to_utf8_without_the_bom() {
+        assert_eq!(decode(b"\xef\xbb\xbfok", Encoding::Utf8Bom), "ok");
+        assert_eq!(
+            decode(b"\xff\xfec\x00a\x00f\x00\xe9\x00", Encoding::Utf16Le),
+            "café"
+        );
+        assert_eq!(decode(b"\x00h\x00i", Encoding::Utf16Be), "hi");
+        assert_eq!(decode(b"\xfe\xff\x00h\x00i", Encoding::Utf16Le), "hi");
+        assert_eq!(decode(b"caf\xe9", Encoding::Latin1), "café");
+    }
+
+    #[test]
+    fn reports_line_ending_style() {
+        assert_eq!(line_endings("a\nb\n"), Some("LF"));
+        assert_eq!(line_endings("a\r\nb\r\n"), Some("CRLF"));
+        assert_eq!(line_endings("a\r\nb\n"), Some("mixed"));
+        assert_eq!(line_endings("a"), None);
+    }
+}
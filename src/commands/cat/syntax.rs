@@ -0,0 +1,1627 @@
+use std::path::Path;
+
+/// Which lexer drives a grammar. `Code` covers C-like and scripting languages
+/// and is configured entirely by the `Grammar` fields; the others add
+/// format-specific structure on top of the same fields.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    Code,
+    Yaml,
+    Html,
+    Css,
+    Markdown,
+    Diff,
+}
+
+pub struct Quote {
+    pub open: &'static str,
+    pub close: &'static str,
+    pub escapes: bool,
+    pub multiline: bool,
+}
+
+const fn quote(open: &'static str, close: &'static str, escapes: bool, multiline: bool) -> Quote {
+    Quote {
+        open,
+        close,
+        escapes,
+        multiline,
+    }
+}
+
+const DQ: Quote = quote("\"", "\"", true, false);
+const SQ: Quote = quote("'", "'", true, false);
+const SQ_RAW: Quote = quote("'", "'", false, false);
+
+pub struct Grammar {
+    pub name: &'static str,
+    /// Display name (`C++`), for `xtree --loc` language rows.
+    pub title: &'static str,
+    /// Extra names accepted by `--language`, modelines and Markdown fences.
+    pub aliases: &'static [&'static str],
+    pub extensions: &'static [&'static str],
+    pub filenames: &'static [&'static str],
+    /// Shebang interpreters, without version suffixes (`python3.11` → `python`).
+    pub interpreters: &'static [&'static str],
+    pub flavor: Flavor,
+    /// Comment syntax. Flavors other than `Code` lex their comments
+    /// themselves, but still list them here for `xtree --loc`.
+    pub line_comments: &'static [&'static str],
+    pub block_comment: Option<(&'static str, &'static str)>,
+    pub nested_comments: bool,
+    /// Quotes whose strings are documentation (Python docstrings); they are
+    /// highlighted as strings but counted as comments by `xtree --loc`.
+    pub docstrings: &'static [(&'static str, &'static str)],
+    /// Line comments only start at line start or after whitespace (`$#` in
+    /// shell is not a comment).
+    pub comment_after_space: bool,
+    /// Longest delimiters first.
+    pub quotes: &'static [Quote],
+    pub keywords: &'static [&'static str],
+    pub types: &'static [&'static str],
+    pub constants: &'static [&'static str],
+    pub builtins: &'static [&'static str],
+    pub ignore_case: bool,
+    /// Capitalized identifiers are types (Rust, Java, ...).
+    pub caps_are_types: bool,
+    /// Lines starting with `#` are preprocessor directives (C/C++).
+    pub preprocessor: bool,
+    /// `#[...]` / `#![...]` attributes and `name!` macros (Rust).
+    pub rust_extras: bool,
+    /// `'a'` char literals vs `'a` lifetimes, plus `r#"..."#` raw strings.
+    pub rust_literals: bool,
+    /// `@Annotation` / `@decorator`.
+    pub annotations: bool,
+    /// `$var`, `${var}` and `<<EOF` heredocs.
+    pub shell_extras: bool,
+    /// A bare identifier or quoted string followed by this byte is a key
+    /// (`=` for TOML/INI, `:` for JSON).
+    pub key_before: Option<u8>,
+    /// Lines starting with `[` are section headers (TOML/INI).
+    pub section_headers: bool,
+    /// Extra bytes allowed inside number literals (TOML dates use `-` and `:`).
+    pub number_extra: &'static str,
+}
+
+const BASE: Grammar = Grammar {
+    name: "",
+    title: "",
+    aliases: &[],
+    extensions: &[],
+    filenames: &[],
+    interpreters: &[],
+    flavor: Flavor::Code,
+    line_comments: &[],
+    block_comment: None,
+    nested_comments: false,
+    docstrings: &[],
+    comment_after_space: false,
+    quotes: &[DQ],
+    keywords: &[],
+    types: &[],
+    constants: &[],
+    builtins: &[],
+    ignore_case: false,
+    caps_are_types: false,
+    preprocessor: false,
+    rust_extras: false,
+    rust_literals: false,
+    annotations: false,
+    shell_extras: false,
+    key_before: None,
+    section_headers: false,
+    number_extra: "",
+};
+
+const C_TYPES: &[&str] = &[
+    "void", "char", "short", "int", "long", "float", "double", "signed", "unsigned", "bool",
+    "size_t", "ssize_t", "int8_t", "int16_t", "int32_t", "int64_t", "uint8_t", "uint16_t",
+    "uint32_t", "uint64_t", "FILE",
+];
+
+const C_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "const", "continue", "default", "do", "else", "enum", "extern", "for",
+    "goto", "if", "inline", "register", "restrict", "return", "sizeof", "static", "struct",
+    "switch", "typedef", "union", "volatile", "while",
+];
+
+pub static GRAMMARS: &[Grammar] = &[
+    Grammar {
+        name: "rust",
+        title: "Rust",
+        aliases: &["rs"],
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &[quote("\"", "\"", true, true)],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+            "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+            "type", "unsafe", "use", "where", "while", "yield",
+        ],
+        types: &[
+            "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+            "f32", "f64", "bool", "char", "str",
+        ],
+        constants: &["true", "false", "None", "Some", "Ok", "Err"],
+        caps_are_types: true,
+        rust_extras: true,
+        rust_literals: true,
+        ..BASE
+    },
+    Grammar {
+        name: "c",
+        title: "C",
+        aliases: &["h"],
+        extensions: &["c", "h"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        keywords: C_KEYWORDS,
+        types: C_TYPES,
+        constants: &["NULL", "true", "false", "EOF"],
+        preprocessor: true,
+        ..BASE
+    },
+    Grammar {
+        name: "cpp",
+        title: "C++",
+        aliases: &["c++", "cxx", "hpp"],
+        extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx", "ino"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        keywords: &[
+            "alignas",
+            "alignof",
+            "auto",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "co_await",
+            "co_return",
+            "co_yield",
+            "concept",
+            "const",
+            "consteval",
+            "constexpr",
+            "constinit",
+            "const_cast",
+            "continue",
+            "decltype",
+            "default",
+            "delete",
+            "do",
+            "dynamic_cast",
+            "else",
+            "enum",
+            "explicit",
+            "export",
+            "extern",
+            "final",
+            "for",
+            "friend",
+            "goto",
+            "if",
+            "inline",
+            "mutable",
+            "namespace",
+            "new",
+            "noexcept",
+            "operator",
+            "override",
+            "private",
+            "protected",
+            "public",
+            "reinterpret_cast",
+            "requires",
+            "return",
+            "sizeof",
+            "static",
+            "static_assert",
+            "static_cast",
+            "struct",
+            "switch",
+            "template",
+            "this",
+            "throw",
+            "try",
+            "typedef",
+            "typeid",
+            "typename",
+            "union",
+            "using",
+            "virtual",
+            "volatile",
+            "while",
+        ],
+        types: C_TYPES,
+        constants: &["nullptr", "NULL", "true", "false"],
+        preprocessor: true,
+        ..BASE
+    },
+    Grammar {
+        name: "go",
+        title: "Go",
+        aliases: &["golang"],
+        extensions: &["go"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ, quote("`", "`", false, true)],
+        keywords: &[
+            "break",
+            "case",
+            "chan",
+            "const",
+            "continue",
+            "default",
+            "defer",
+            "else",
+            "fallthrough",
+            "for",
+            "func",
+            "go",
+            "goto",
+            "if",
+            "import",
+            "interface",
+            "map",
+            "package",
+            "range",
+            "return",
+            "select",
+            "struct",
+            "switch",
+            "type",
+            "var",
+        ],
+        types: &[
+            "bool",
+            "byte",
+            "complex64",
+            "complex128",
+            "error",
+            "float32",
+            "float64",
+            "int",
+            "int8",
+            "int16",
+            "int32",
+            "int64",
+            "rune",
+            "string",
+            "uint",
+            "uint8",
+            "uint16",
+            "uint32",
+            "uint64",
+            "uintptr",
+            "any",
+        ],
+        constants: &["true", "false", "nil", "iota"],
+        builtins: &[
+            "append", "cap", "close", "copy", "delete", "len", "make", "new", "panic", "print",
+            "println", "recover",
+        ],
+        ..BASE
+    },
+    Grammar {
+        name: "python",
+        title: "Python",
+        aliases: &["py", "python3"],
+        extensions: &["py", "pyi", "pyw"],
+        filenames: &["SConstruct", "SConscript"],
+        interpreters: &["python"],
+        line_comments: &["#"],
+        docstrings: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+        quotes: &[
+            quote("\"\"\"", "\"\"\"", true, true),
+            quote("'''", "'''", true, true),
+            DQ,
+            SQ,
+        ],
+        keywords: &[
+            "and", "as", "assert", "async", "await", "break", "case", "class", "continue", "def",
+            "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+            "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise", "return",
+            "try", "while", "with", "yield", "self",
+        ],
+        types: &[
+            "int", "float", "str", "bytes", "bool", "list", "dict", "set", "tuple", "object",
+        ],
+        constants: &["True", "False", "None"],
+        builtins: &[
+            "print",
+            "len",
+            "range",
+            "enumerate",
+            "zip",
+            "open",
+            "isinstance",
+            "super",
+            "map",
+            "filter",
+            "sorted",
+            "min",
+            "max",
+            "sum",
+            "any",
+            "all",
+            "repr",
+            "type",
+        ],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "javascript",
+        title: "JavaScript",
+        aliases: &["js", "node", "jsx", "mjs"],
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        interpreters: &["node", "nodejs", "deno", "bun"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ, quote("`", "`", true, true)],
+        keywords: &[
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "debugger",
+            "default",
+            "delete",
+            "do",
+            "else",
+            "export",
+            "extends",
+            "finally",
+            "for",
+            "from",
+            "function",
+            "if",
+            "import",
+            "in",
+            "instanceof",
+            "let",
+            "new",
+            "of",
+            "return",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "try",
+            "typeof",
+            "var",
+            "void",
+            "while",
+            "with",
+            "yield",
+        ],
+        constants: &["true", "false", "null", "undefined", "NaN", "Infinity"],
+        builtins: &[
+            "console", "require", "module", "exports", "window", "document",
+        ],
+        caps_are_types: true,
+        ..BASE
+    },
+    Grammar {
+        name: "typescript",
+        title: "TypeScript",
+        aliases: &["ts", "tsx"],
+        extensions: &["ts", "tsx", "mts", "cts"],
+        interpreters: &["ts-node", "tsx"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ, quote("`", "`", true, true)],
+        keywords: &[
+            "abstract",
+            "as",
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "declare",
+            "default",
+            "delete",
+            "do",
+            "else",
+            "enum",
+            "export",
+            "extends",
+            "finally",
+            "for",
+            "from",
+            "function",
+            "if",
+            "implements",
+            "import",
+            "in",
+            "instanceof",
+            "interface",
+            "keyof",
+            "let",
+            "namespace",
+            "new",
+            "of",
+            "private",
+            "protected",
+            "public",
+            "readonly",
+            "return",
+            "satisfies",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "try",
+            "type",
+            "typeof",
+            "var",
+            "void",
+            "while",
+            "yield",
+        ],
+        types: &[
+            "any", "boolean", "never", "number", "object", "string", "symbol", "unknown", "bigint",
+        ],
+        constants: &["true", "false", "null", "undefined", "NaN", "Infinity"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "java",
+        title: "Java",
+        extensions: &["java"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[quote("\"\"\"", "\"\"\"", true, true), DQ, SQ],
+        keywords: &[
+            "abstract",
+            "assert",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "do",
+            "else",
+            "enum",
+            "extends",
+            "final",
+            "finally",
+            "for",
+            "goto",
+            "if",
+            "implements",
+            "import",
+            "instanceof",
+            "interface",
+            "native",
+            "new",
+            "package",
+            "permits",
+            "private",
+            "protected",
+            "public",
+            "record",
+            "return",
+            "sealed",
+            "static",
+            "strictfp",
+            "super",
+            "switch",
+            "synchronized",
+            "this",
+            "throw",
+            "throws",
+            "transient",
+            "try",
+            "var",
+            "void",
+            "volatile",
+            "while",
+            "yield",
+        ],
+        types: &[
+            "boolean", "byte", "char", "double", "float", "int", "long", "short",
+        ],
+        constants: &["true", "false", "null"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "csharp",
+        title: "C#",
+        aliases: &["cs", "c#"],
+        extensions: &["cs"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        keywords: &[
+            "abstract",
+            "as",
+            "async",
+            "await",
+            "base",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "delegate",
+            "do",
+            "else",
+            "enum",
+            "event",
+            "explicit",
+            "extern",
+            "finally",
+            "fixed",
+            "for",
+            "foreach",
+            "get",
+            "goto",
+            "if",
+            "implicit",
+            "in",
+            "interface",
+            "internal",
+            "is",
+            "lock",
+            "namespace",
+            "new",
+            "operator",
+            "out",
+            "override",
+            "params",
+            "private",
+            "protected",
+            "public",
+            "readonly",
+            "record",
+            "ref",
+            "return",
+            "sealed",
+            "set",
+            "sizeof",
+            "static",
+            "struct",
+            "switch",
+            "this",
+            "throw",
+            "try",
+            "typeof",
+            "using",
+            "var",
+            "virtual",
+            "void",
+            "when",
+            "where",
+            "while",
+            "yield",
+        ],
+        types: &[
+            "bool", "byte", "char", "decimal", "double", "float", "int", "long", "object", "sbyte",
+            "short", "string", "uint", "ulong", "ushort", "dynamic",
+        ],
+        constants: &["true", "false", "null"],
+        caps_are_types: true,
+        preprocessor: true,
+        ..BASE
+    },
+    Grammar {
+        name: "kotlin",
+        title: "Kotlin",
+        aliases: &["kt"],
+        extensions: &["kt", "kts"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &[quote("\"\"\"", "\"\"\"", false, true), DQ, SQ],
+        keywords: &[
+            "as",
+            "break",
+            "by",
+            "class",
+            "companion",
+            "const",
+            "continue",
+            "data",
+            "do",
+            "else",
+            "enum",
+            "for",
+            "fun",
+            "if",
+            "import",
+            "in",
+            "init",
+            "interface",
+            "internal",
+            "is",
+            "lateinit",
+            "object",
+            "open",
+            "override",
+            "package",
+            "private",
+            "protected",
+            "public",
+            "return",
+            "sealed",
+            "super",
+            "suspend",
+            "this",
+            "throw",
+            "try",
+            "typealias",
+            "val",
+            "var",
+            "when",
+            "while",
+        ],
+        constants: &["true", "false", "null"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "ruby",
+        title: "Ruby",
+        aliases: &["rb"],
+        extensions: &["rb", "rake", "gemspec"],
+        filenames: &["Rakefile", "Gemfile", "Vagrantfile"],
+        interpreters: &["ruby"],
+        line_comments: &["#"],
+        block_comment: Some(("=begin", "=end")),
+        quotes: &[DQ, SQ_RAW],
+        keywords: &[
+            "alias",
+            "and",
+            "begin",
+            "break",
+            "case",
+            "class",
+            "def",
+            "do",
+            "else",
+            "elsif",
+            "end",
+            "ensure",
+            "for",
+            "if",
+            "in",
+            "module",
+            "next",
+            "not",
+            "or",
+            "redo",
+            "rescue",
+            "retry",
+            "return",
+            "self",
+            "super",
+            "then",
+            "undef",
+            "unless",
+            "until",
+            "when",
+            "while",
+            "yield",
+            "require",
+            "attr_reader",
+            "attr_accessor",
+        ],
+        constants: &["true", "false", "nil"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "lua",
+        title: "Lua",
+        extensions: &["lua"],
+        interpreters: &["lua", "luajit"],
+        line_comments: &["--"],
+        block_comment: Some(("--[[", "]]")),
+        quotes: &[quote("[[", "]]", false, true), DQ, SQ],
+        keywords: &[
+            "and", "break", "do", "else", "elseif", "end", "for", "function", "goto", "if", "in",
+            "local", "not", "or", "repeat", "return", "then", "until", "while",
+        ],
+        constants: &["true", "false", "nil"],
+        builtins: &[
+            "print",
+            "pairs",
+            "ipairs",
+            "require",
+            "type",
+            "tostring",
+            "tonumber",
+            "error",
+            "pcall",
+            "setmetatable",
+            "getmetatable",
+        ],
+        ..BASE
+    },
+    Grammar {
+        name: "shell",
+        title: "Shell",
+        aliases: &["sh", "bash", "zsh", "ksh", "shellscript", "console"],
+        extensions: &["sh", "bash", "zsh", "ksh", "fish"],
+        filenames: &[
+            ".bashrc",
+            ".bash_profile",
+            ".bash_aliases",
+            ".profile",
+            ".zshrc",
+            ".zprofile",
+            ".zshenv",
+            "PKGBUILD",
+        ],
+        interpreters: &["sh", "bash", "zsh", "ksh", "dash", "ash", "fish"],
+        line_comments: &["#"],
+        comment_after_space: true,
+        quotes: &[DQ, SQ_RAW, quote("`", "`", true, false)],
+        keywords: &[
+            "if", "then", "else", "elif", "fi", "case", "esac", "for", "while", "until", "do",
+            "done", "in", "function", "select", "time", "return", "local", "export", "readonly",
+            "declare", "typeset", "unset", "shift", "break", "continue", "exit",
+        ],
+        builtins: &[
+            "echo", "printf", "cd", "pwd", "read", "source", "eval", "exec", "set", "test", "trap",
+            "wait", "alias", "command", "type", "getopts", "true", "false",
+        ],
+        shell_extras: true,
+        ..BASE
+    },
+    Grammar {
+        name: "sql",
+        title: "SQL",
+        aliases: &["mysql", "postgres", "postgresql", "sqlite", "psql"],
+        extensions: &["sql", "ddl"],
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[SQ_RAW, quote("\"", "\"", false, false)],
+        keywords: &[
+            "add",
+            "all",
+            "alter",
+            "and",
+            "as",
+            "asc",
+            "begin",
+            "between",
+            "by",
+            "case",
+            "check",
+            "column",
+            "commit",
+            "constraint",
+            "create",
+            "cross",
+            "database",
+            "default",
+            "delete",
+            "desc",
+            "distinct",
+            "drop",
+            "else",
+            "end",
+            "exists",
+            "foreign",
+            "from",
+            "full",
+            "group",
+            "having",
+            "if",
+            "in",
+            "index",
+            "inner",
+            "insert",
+            "into",
+            "is",
+            "join",
+            "key",
+            "left",
+            "like",
+            "limit",
+            "not",
+            "null",
+            "offset",
+            "on",
+            "or",
+            "order",
+            "outer",
+            "primary",
+            "references",
+            "returning",
+            "right",
+            "rollback",
+            "select",
+            "set",
+            "table",
+            "then",
+            "transaction",
+            "trigger",
+            "union",
+            "unique",
+            "update",
+            "using",
+            "values",
+            "view",
+            "when",
+            "where",
+            "with",
+        ],
+        types: &[
+            "bigint",
+            "blob",
+            "boolean",
+            "char",
+            "date",
+            "datetime",
+            "decimal",
+            "double",
+            "float",
+            "int",
+            "integer",
+            "json",
+            "jsonb",
+            "numeric",
+            "real",
+            "serial",
+            "smallint",
+            "text",
+            "time",
+            "timestamp",
+            "uuid",
+            "varchar",
+        ],
+        constants: &["true", "false"],
+        builtins: &[
+            "count", "sum", "avg", "min", "max", "coalesce", "now", "lower", "upper",
+        ],
+        ignore_case: true,
+        ..BASE
+    },
+    Grammar {
+        name: "toml",
+        title: "TOML",
+        extensions: &["toml"],
+        filenames: &["Cargo.lock", "Pipfile", "poetry.lock"],
+        line_comments: &["#"],
+        quotes: &[
+            quote("\"\"\"", "\"\"\"", true, true),
+            quote("'''", "'''", false, true),
+            DQ,
+            SQ_RAW,
+        ],
+        constants: &["true", "false", "inf", "nan"],
+        key_before: Some(b'='),
+        section_headers: true,
+        number_extra: "-:+",
+        ..BASE
+    },
+    Grammar {
+        name: "ini",
+        title: "INI",
+        aliases: &["dosini", "cfg"],
+        extensions: &["ini", "cfg", "conf", "desktop", "service"],
+        filenames: &[".editorconfig", ".gitconfig", ".npmrc"],
+        line_comments: &["#", ";"],
+        quotes: &[DQ],
+        constants: &["true", "false", "yes", "no", "on", "off"],
+        key_before: Some(b'='),
+        section_headers: true,
+        ..BASE
+    },
+    Grammar {
+        name: "json",
+        title: "JSON",
+        aliases: &["jsonc", "json5"],
+        extensions: &["json", "jsonc", "json5", "jsonl", "geojson"],
+        filenames: &[".babelrc", ".eslintrc", "composer.lock", "flake.lock"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        constants: &["true", "false", "null"],
+        key_before: Some(b':'),
+        ..BASE
+    },
+    Grammar {
+        name: "yaml",
+        title: "YAML",
+        aliases: &["yml"],
+        extensions: &["yaml", "yml"],
+        filenames: &[".clang-format", ".clang-tidy"],
+        flavor: Flavor::Yaml,
+        line_comments: &["#"],
+        constants: &[
+            "true", "false", "null", "yes", "no", "on", "off", "~", ".inf", "-.inf", ".nan",
+        ],
+        ignore_case: true,
+        ..BASE
+    },
+    Grammar {
+        name: "html",
+        title: "HTML",
+        aliases: &["htm", "xhtml", "vue"],
+        extensions: &["html", "htm", "xhtml", "vue"],
+        flavor: Flavor::Html,
+        block_comment: Some(("<!--", "-->")),
+        ..BASE
+    },
+    Grammar {
+        name: "xml",
+        title: "XML",
+        aliases: &["svg", "plist"],
+        extensions: &["xml", "svg", "xsd", "xsl", "plist", "csproj"],
+        flavor: Flavor::Html,
+        block_comment: Some(("<!--", "-->")),
+        ..BASE
+    },
+    Grammar {
+        name: "css",
+        title: "CSS",
+        extensions: &["css"],
+        flavor: Flavor::Css,
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        ..BASE
+    },
+    Grammar {
+        name: "scss",
+        title: "SCSS",
+        aliases: &["sass", "less"],
+        extensions: &["scss", "sass", "less"],
+        flavor: Flavor::Css,
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        ..BASE
+    },
+    Grammar {
+        name: "markdown",
+        title: "Markdown",
+        aliases: &["md", "mkd"],
+        extensions: &["md", "markdown", "mdx", "mkd"],
+        filenames: &["README", "CHANGELOG"],
+        flavor: Flavor::Markdown,
+        block_comment: Some(("<!--", "-->")),
+        ..BASE
+    },
+    Grammar {
+        name: "diff",
+        title: "Diff",
+        aliases: &["patch", "udiff"],
+        extensions: &["diff", "patch", "rej"],
+        flavor: Flavor::Diff,
+        ..BASE
+    },
+    Grammar {
+        name: "dockerfile",
+        title: "Dockerfile",
+        aliases: &["docker"],
+        extensions: &["dockerfile"],
+        filenames: &["Dockerfile", "Containerfile"],
+        line_comments: &["#"],
+        comment_after_space: true,
+        quotes: &[DQ, SQ_RAW],
+        keywords: &[
+            "from",
+            "as",
+            "run",
+            "cmd",
+            "label",
+            "expose",
+            "env",
+            "add",
+            "copy",
+            "entrypoint",
+            "volume",
+            "user",
+            "workdir",
+            "arg",
+            "onbuild",
+            "stopsignal",
+            "healthcheck",
+            "shell",
+            "maintainer",
+        ],
+        ignore_case: true,
+        shell_extras: true,
+        ..BASE
+    },
+    Grammar {
+        name: "asm",
+        title: "Assembly",
+        aliases: &["assembly", "nasm", "gas", "s"],
+        extensions: &["s", "asm", "nasm"],
+        line_comments: &[";", "#", "//"],
+        comment_after_space: true,
+        keywords: &[
+            "mov", "movzx", "movsx", "lea", "add", "sub", "mul", "imul", "div", "idiv", "inc",
+            "dec", "and", "or", "xor", "not", "neg", "shl", "shr", "sar", "call", "ret", "jmp",
+            "je", "jne", "jz", "jnz", "jg", "jge", "jl", "jle", "ja", "jb", "cmp", "test", "push",
+            "pop", "nop", "syscall", "int", "ldr", "str", "bl", "b", "cbz", "cbnz",
+        ],
+        types: &[
+            "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11",
+            "r12", "r13", "r14", "r15", "eax", "ebx", "ecx", "edx", "esi", "edi", "ebp", "esp",
+            "ax", "bx", "cx", "dx", "al", "bl", "cl", "dl", "rip", "sp", "lr", "pc", "xzr",
+        ],
+        ignore_case: true,
+        ..BASE
+    },
+    Grammar {
+        name: "scala",
+        title: "Scala",
+        aliases: &["sc"],
+        extensions: &["scala", "sc"],
+        interpreters: &["scala"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &[quote("\"\"\"", "\"\"\"", false, true), DQ, SQ],
+        keywords: &[
+            "abstract",
+            "case",
+            "catch",
+            "class",
+            "def",
+            "do",
+            "else",
+            "extends",
+            "final",
+            "finally",
+            "for",
+            "given",
+            "if",
+            "implicit",
+            "import",
+            "lazy",
+            "match",
+            "new",
+            "object",
+            "override",
+            "package",
+            "private",
+            "protected",
+            "return",
+            "sealed",
+            "then",
+            "throw",
+            "trait",
+            "try",
+            "type",
+            "using",
+            "val",
+            "var",
+            "while",
+            "with",
+            "yield",
+        ],
+        constants: &["true", "false", "null", "None", "Nil"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "swift",
+        title: "Swift",
+        extensions: &["swift"],
+        interpreters: &["swift"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &[quote("\"\"\"", "\"\"\"", true, true), DQ],
+        keywords: &[
+            "actor",
+            "as",
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "continue",
+            "defer",
+            "do",
+            "else",
+            "enum",
+            "extension",
+            "fileprivate",
+            "for",
+            "func",
+            "guard",
+            "if",
+            "import",
+            "in",
+            "init",
+            "internal",
+            "is",
+            "let",
+            "private",
+            "protocol",
+            "public",
+            "repeat",
+            "return",
+            "self",
+            "static",
+            "struct",
+            "switch",
+            "throw",
+            "throws",
+            "try",
+            "var",
+            "where",
+            "while",
+        ],
+        constants: &["true", "false", "nil"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "dart",
+        title: "Dart",
+        extensions: &["dart"],
+        interpreters: &["dart"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &[
+            quote("\"\"\"", "\"\"\"", true, true),
+            quote("'''", "'''", true, true),
+            DQ,
+            SQ,
+        ],
+        keywords: &[
+            "abstract",
+            "as",
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "do",
+            "else",
+            "enum",
+            "extends",
+            "final",
+            "finally",
+            "for",
+            "if",
+            "implements",
+            "import",
+            "in",
+            "is",
+            "late",
+            "library",
+            "mixin",
+            "new",
+            "return",
+            "required",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "try",
+            "var",
+            "void",
+            "while",
+            "with",
+            "yield",
+        ],
+        types: &[
+            "int", "double", "num", "bool", "String", "List", "Map", "Set", "dynamic",
+        ],
+        constants: &["true", "false", "null"],
+        caps_are_types: true,
+        annotations: true,
+        ..BASE
+    },
+    Grammar {
+        name: "zig",
+        title: "Zig",
+        extensions: &["zig"],
+        line_comments: &["//"],
+        quotes: &[DQ, SQ],
+        keywords: &[
+            "and",
+            "break",
+            "catch",
+            "comptime",
+            "const",
+            "continue",
+            "defer",
+            "else",
+            "enum",
+            "errdefer",
+            "error",
+            "export",
+            "extern",
+            "fn",
+            "for",
+            "if",
+            "inline",
+            "or",
+            "orelse",
+            "packed",
+            "pub",
+            "return",
+            "struct",
+            "switch",
+            "test",
+            "try",
+            "union",
+            "unreachable",
+            "usingnamespace",
+            "var",
+            "while",
+        ],
+        types: &[
+            "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "isize", "usize", "f32", "f64",
+            "bool", "void", "anyerror", "type",
+        ],
+        constants: &["true", "false", "null", "undefined"],
+        ..BASE
+    },
+    Grammar {
+        name: "php",
+        title: "PHP",
+        extensions: &["php", "phtml"],
+        interpreters: &["php"],
+        line_comments: &["//", "#"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &[DQ, SQ],
+        keywords: &[
+            "abstract",
+            "as",
+            "break",
+            "case",
+            "catch",
+            "class",
+            "const",
+            "continue",
+            "default",
+            "do",
+            "echo",
+            "else",
+            "elseif",
+            "extends",
+            "final",
+            "finally",
+            "fn",
+            "for",
+            "foreach",
+            "function",
+            "if",
+            "implements",
+            "interface",
+            "match",
+            "namespace",
+            "new",
+            "private",
+            "protected",
+            "public",
+            "require",
+            "return",
+            "static",
+            "switch",
+            "throw",
+            "trait",
+            "try",
+            "use",
+            "while",
+        ],
+        constants: &["true", "false", "null", "TRUE", "FALSE", "NULL"],
+        shell_extras: true,
+        ..BASE
+    },
+    Grammar {
+        name: "perl",
+        title: "Perl",
+        aliases: &["pl"],
+        extensions: &["pl", "pm"],
+        interpreters: &["perl"],
+        line_comments: &["#"],
+        comment_after_space: true,
+        quotes: &[DQ, SQ_RAW],
+        keywords: &[
+            "my", "our", "local", "sub", "if", "elsif", "else", "unless", "while", "until", "for",
+            "foreach", "last", "next", "return", "use", "package", "require", "do", "and", "or",
+            "not",
+        ],
+        builtins: &[
+            "print", "printf", "open", "close", "die", "warn", "push", "shift", "split",
+        ],
+        shell_extras: true,
+        ..BASE
+    },
+    Grammar {
+        name: "r",
+        title: "R",
+        extensions: &["r"],
+        interpreters: &["Rscript"],
+        line_comments: &["#"],
+        quotes: &[DQ, SQ],
+        keywords: &[
+            "if", "else", "repeat", "while", "function", "for", "in", "next", "break", "return",
+            "library",
+        ],
+        constants: &["TRUE", "FALSE", "NULL", "NA", "Inf", "NaN"],
+        ..BASE
+    },
+    Grammar {
+        name: "haskell",
+        title: "Haskell",
+        aliases: &["hs"],
+        extensions: &["hs"],
+        interpreters: &["runhaskell"],
+        line_comments: &["--"],
+        block_comment: Some(("{-", "-}")),
+        nested_comments: true,
+        keywords: &[
+            "case", "class", "data", "deriving", "do", "else", "if", "import", "in", "instance",
+            "let", "module", "newtype", "of", "then", "type", "where",
+        ],
+        constants: &["True", "False", "Nothing", "Just"],
+        caps_are_types: true,
+        ..BASE
+    },
+    Grammar {
+        name: "elixir",
+        title: "Elixir",
+        aliases: &["ex"],
+        extensions: &["ex", "exs"],
+        interpreters: &["elixir"],
+        line_comments: &["#"],
+        quotes: &[quote("\"\"\"", "\"\"\"", true, true), DQ, SQ],
+        keywords: &[
+            "after",
+            "case",
+            "catch",
+            "cond",
+            "def",
+            "defmodule",
+            "defp",
+            "defstruct",
+            "do",
+            "else",
+            "end",
+            "fn",
+            "for",
+            "if",
+            "import",
+            "quote",
+            "receive",
+            "rescue",
+            "try",
+            "unless",
+            "use",
+            "when",
+            "with",
+        ],
+        constants: &["true", "false", "nil"],
+        caps_are_types: true,
+        ..BASE
+    },
+    Grammar {
+        name: "clojure",
+        title: "Clojure",
+        aliases: &["clj"],
+        extensions: &["clj", "cljs", "cljc", "edn"],
+        line_comments: &[";"],
+        keywords: &[
+            "def", "defn", "defmacro", "fn", "let", "if", "do", "when", "cond", "loop", "recur",
+            "ns", "require",
+        ],
+        constants: &["true", "false", "nil"],
+        ..BASE
+    },
+    Grammar {
+        name: "makefile",
+        title: "Makefile",
+        aliases: &["make"],
+        extensions: &["mk"],
+        filenames: &["Makefile", "makefile", "GNUmakefile"],
+        line_comments: &["#"],
+        quotes: &[],
+        keywords: &[
+            "ifeq", "ifneq", "ifdef", "ifndef", "else", "endif", "include", "define", "endef",
+            "export", "override",
+        ],
+        shell_extras: true,
+        ..BASE
+    },
+];
+
+impl Grammar {
+    pub fn is_word(&self, list: &[&str], word: &str) -> bool {
+        if self.ignore_case {
+            list.iter().any(|w| w.eq_ignore_ascii_case(word))
+        } else {
+            list.contains(&word)
+        }
+    }
+}
+
+/// Looks up a grammar by name, alias or extension (case-insensitive).
+pub fn by_name(name: &str) -> Option<&'static Grammar> {
+    let name = name.trim().to_ascii_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    GRAMMARS.iter().find(|g| {
+        g.name == name
+            || g.aliases.contains(&name.as_str())
+            || g.extensions.contains(&name.as_str())
+    })
+}
+
+/// Picks a grammar for `path` with the given content: an editor modeline
+/// wins, then the exact file name, the extension, and finally the shebang.
+pub fn detect(path: Option<&Path>, text: &str) -> Option<&'static Grammar> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let head = lines.iter().take(5);
+    let tail = lines.iter().skip(lines.len().saturating_sub(5).max(5));
+    if let Some(grammar) = head.chain(tail).find_map(|line| modeline(line)) {
+        return Some(grammar);
+    }
+
+    if let Some(grammar) = path.and_then(for_path) {
+        return Some(grammar);
+    }
+
+    lines.first().and_then(|line| shebang(line))
+}
+
+/// The grammar for `path` by exact file name, then extension.
+pub fn for_path(path: &Path) -> Option<&'static Grammar> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if let Some(grammar) = GRAMMARS.iter().find(|g| g.filenames.contains(&file_name)) {
+        return Some(grammar);
+    }
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if let Some(grammar) = GRAMMARS
+        .iter()
+        .find(|g| g.extensions.contains(&ext.as_str()))
+    {
+        return Some(grammar);
+    }
+    let lower = file_name.to_ascii_lowercase();
+    if lower.starts_with("dockerfile") {
+        return by_name("dockerfile");
+    }
+    None
+}
+
+fn shebang(line: &str) -> Option<&'static Grammar> {
+    let rest = line.strip_prefix("#!")?;
+    let mut words = rest.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    GRAMMARS.iter().find(|g| g.interpreters.contains(&name))
+}
+
+/// Vim (`vim: set ft=rust:`, `vi: filetype=sh`) and Emacs (`-*- mode: python
+/// -*-`, `-*- ruby -*-`) modelines.
+fn modeline(line: &str) -> Option<&'static Grammar> {
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let body = &rest[..rest.find("-*-")?];
+        let value = body
+            .split(';')
+            .find_map(|part| {
+                let (key, value) = part.split_once(':')?;
+                key.trim().eq_ignore_ascii_case("mode").then_some(value)
+            })
+            .unwrap_or(body);
+        return by_name(value);
+    }
+
+    for marker in ["vim:", "vi:", "ex:"] {
+        let Some(pos) = line.find(marker) else {
+            continue;
+        };
+        if pos > 0 && !line[..pos].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let settings = &line[pos + marker.len()..];
+        for item in settings.split(|c: char| c == ':' || c.is_whitespace()) {
+            if let Some((key, value)) = item.split_once('=')
+                && matches!(key, "ft" | "filetype" | "syntax" | "syn")
+            {
+                return by_name(value);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{by_name, detect};
+    use std::path::Path;
+
+    fn name(path: &str, text: &str) -> Option<&'static str> {
+        detect(Some(Path::new(path)), text).map(|g| g.name)
+    }
+
+    #[test]
+    fn detects_by_extension_and_file_name() {
+        assert_eq!(name("src/main.rs", ""), Some("rust"));
+        assert_eq!(name("Cargo.lock", ""), Some("toml"));
+        assert_eq!(name("Dockerfile.dev", ""), Some("dockerfile"));
+        assert_eq!(name("notes.txt", ""), None);
+    }
+
+    #[test]
+    fn detects_by_shebang() {
+        assert_eq!(
+            name("run", "#!/usr/bin/env python3\nprint(1)\n"),
+            Some("python")
+        );
+        assert_eq!(name("build", "#!/bin/bash -e\n"), Some("shell"));
+        assert_eq!(
+            name("tool", "#!/usr/bin/env -S node --flag\n"),
+            Some("javascript")
+        );
+    }
+
+    #[test]
+    fn modeline_overrides_extension() {
+        assert_eq!(
+            name("config.txt", "# vim: set ft=yaml :\na: 1\n"),
+            Some("yaml")
+        );
+        assert_eq!(name("x.conf", "# -*- mode: sh -*-\n"), Some("shell"));
+        let tail = format!("{}// vim: ft=cpp\n", "x\n".repeat(20));
+        assert_eq!(name("legacy.h", &tail), Some("cpp"));
+    }
+
+    #[test]
+    fn looks_up_aliases() {
+        assert_eq!(by_name("YML").map(|g| g.name), Some("yaml"));
+        assert_eq!(by_name("c++").map(|g| g.name), Some("cpp"));
+        assert!(by_name("klingon").is_none());
+    }
+}
@@ -0,0 +1,710 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use crossterm::cursor::MoveTo;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEventKind};
+use crossterm::queue;
+use crossterm::style::Print;
+use crossterm::terminal::{self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate};
+use unicode_width::UnicodeWidthChar;
+
+use super::highlight::{Class, Highlighter, Spans};
+use super::sniff::{self, Encoding};
+use super::syntax::{self, Grammar};
+use crate::core::style::Style;
+use crate::core::terminal::TerminalGuard;
+use crate::core::theme::Theme;
+
+const TAB_WIDTH: usize = 4;
+const H_STEP: usize = 8;
+const REVERSE: &str = "\x1b[7m";
+
+/// One file loaded into the viewer: tab-expanded lines plus their highlight
+/// spans, computed once up front so scrolling never re-lexes.
+struct Doc {
+    label: String,
+    language: Option<&'static str>,
+    lines: Vec<String>,
+    spans: Vec<Spans>,
+}
+
+impl Doc {
+    fn new(label: String, text: &str, grammar: Option<&'static Grammar>) -> Self {
+        let mut highlighter = grammar.map(Highlighter::new);
+        let mut lines = Vec::new();
+        let mut spans = Vec::new();
+        for raw in text.lines() {
+            let line = expand_tabs(raw);
+            spans.push(match &mut highlighter {
+                Some(hl) => hl.spans(&line),
+                None => vec![(Class::Plain, 0..line.len())],
+            });
+            lines.push(line);
+        }
+        Self {
+            label,
+            language: grammar.map(|g| g.name),
+            lines,
+            spans,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Search {
+    pattern: String,
+    forward: bool,
+}
+
+enum Prompt {
+    Search {
+        forward: bool,
+        input: String,
+        origin: usize,
+    },
+    Goto {
+        input: String,
+    },
+}
+
+struct Viewer {
+    docs: Vec<Doc>,
+    current: usize,
+    top: usize,
+    left: usize,
+    search: Option<Search>,
+    prompt: Option<Prompt>,
+    message: Option<String>,
+    body_rows: usize,
+}
+
+/// `dusk bat --view`: full-screen viewer over every file on the command line
+/// (stdin when none are given).
+pub(super) fn run(
+    files: &[String],
+    language: Option<&'static Grammar>,
+    encoding: Option<Encoding>,
+    style: &Style,
+    theme: &Theme,
+) -> Result<(), String> {
+    if !atty::is(atty::Stream::Stdout) {
+        return Err("--view needs an interactive terminal".to_string());
+    }
+
+    let mut docs = Vec::new();
+    if files.is_empty() || files.iter().any(|f| f == "-") {
+        let mut raw = Vec::new();
+        io::stdin()
+            .read_to_end(&mut raw)
+            .map_err(|err| format!("read error: {err}"))?;
+        let text = decode(&raw, encoding);
+        let grammar = language.or_else(|| syntax::detect(None, &text));
+        docs.push(Doc::new("stdin".to_string(), &text, grammar));
+    }
+    for file in files.iter().filter(|f| *f != "-") {
+        let raw = fs::read(file).map_err(|err| format!("{file}: {err}"))?;
+        let path = std::path::Path::new(file);
+        let text = decode(&raw, encoding);
+        let grammar = language.or_else(|| syntax::detect(Some(path), &text));
+        docs.push(Doc::new(file.clone(), &text, grammar));
+    }
+
+    let mut viewer = Viewer {
+        docs,
+        current: 0,
+        top: 0,
+        left: 0,
+        search: None,
+        prompt: None,
+        message: None,
+        body_rows: 1,
+    };
+
+    let _guard = TerminalGuard::enter()?;
+    let mut dirty = true;
+    loop {
+        if dirty {
+            viewer.render(style, theme)?;
+            dirty = false;
+        }
+        if !event::poll(Duration::from_millis(120)).map_err(|e| e.to_string())? {
+            continue;
+        }
+        match event::read().map_err(|e| e.to_string())? {
+            Event::Key(key) => {
+                if !viewer.handle_key(key) {
+                    break;
+                }
+                dirty = true;
+            }
+            Event::Mouse(m) => match m.kind {
+                MouseEventKind::ScrollUp => {
+                    viewer.scroll_by(-3);
+                    dirty = true;
+                }
+                MouseEventKind::ScrollDown => {
+                    viewer.scroll_by(3);
+                    dirty = true;
+                }
+                _ => {}
+            },
+            Event::Resize(_, _) => dirty = true,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+impl Viewer {
+    fn doc(&self) -> &Doc {
+        &self.docs[self.current]
+    }
+
+    fn max_top(&self) -> usize {
+        self.doc().lines.len().saturating_sub(self.body_rows)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.top = self.top.saturating_add_signed(delta).min(self.max_top());
+    }
+
+    fn switch_to(&mut self, index: usize) {
+        self.current = index;
+        self.top = 0;
+        self.left = 0;
+        self.message = Some(format!(
+            "{} ({}/{})",
+            self.doc().label,
+            index + 1,
+            self.docs.len()
+        ));
+    }
+
+    /// Active pattern: the one being typed, else the last confirmed search.
+    fn pattern(&self) -> Option<&str> {
+        match &self.prompt {
+            Some(Prompt::Search { input, .. }) if !input.is_empty() => Some(input),
+            Some(Prompt::Search { .. }) => None,
+            _ => self.search.as_ref().map(|s| s.pattern.as_str()),
+        }
+    }
+
+    /// Returns `false` when the viewer should close.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.prompt.is_some() {
+            self.handle_prompt_key(key);
+            return true;
+        }
+        self.message = None;
+        let page = self.body_rows.max(1) as isize;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if ctrl => return false,
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Enter => self.scroll_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Char(' ') | KeyCode::PageDown | KeyCode::Char('f') => self.scroll_by(page),
+            KeyCode::Char('b') | KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::Char('d') => self.scroll_by(page / 2),
+            KeyCode::Char('u') => self.scroll_by(-page / 2),
+            KeyCode::Char('g') | KeyCode::Home => self.top = 0,
+            KeyCode::Char('G') | KeyCode::End => self.top = self.max_top(),
+            KeyCode::Char('l') | KeyCode::Right => self.left += H_STEP,
+            KeyCode::Char('h') | KeyCode::Left => self.left = self.left.saturating_sub(H_STEP),
+            KeyCode::Char('0') => self.left = 0,
+            KeyCode::Char('/') | KeyCode::Char('?') => {
+                self.prompt = Some(Prompt::Search {
+                    forward: key.code == KeyCode::Char('/'),
+                    input: String::new(),
+                    origin: self.top,
+                });
+            }
+            KeyCode::Char(':') => {
+                self.prompt = Some(Prompt::Goto {
+                    input: String::new(),
+                })
+            }
+            KeyCode::Char('n') => self.repeat_search(false),
+            KeyCode::Char('N') => self.repeat_search(true),
+            KeyCode::Tab | KeyCode::Char(']') if self.docs.len() > 1 => {
+                self.switch_to((self.current + 1) % self.docs.len());
+            }
+            KeyCode::BackTab | KeyCode::Char('[') if self.docs.len() > 1 => {
+                self.switch_to((self.current + self.docs.len() - 1) % self.docs.len());
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_prompt_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+        match prompt {
+            Prompt::Search {
+                forward,
+                mut input,
+                origin,
+            } => match key.code {
+                KeyCode::Esc => self.top = origin,
+                KeyCode::Enter => {
+                    if input.is_empty() {
+                        // Empty input re-runs the previous pattern in the new
+                        // direction, like less.
+                        if let Some(search) = &mut self.search {
+                            search.forward = forward;
+                            self.repeat_search(false);
+                        }
+                    } else {
+                        if find_line(&self.doc().lines, &input, origin, forward).is_none() {
+                            self.message = Some("Pattern not found".to_string());
+                        }
+                        self.search = Some(Search {
+                            pattern: input,
+                            forward,
+                        });
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.top = origin;
+                    self.incremental(&input, forward, origin);
+                    self.prompt = Some(Prompt::Search {
+                        forward,
+                        input,
+                        origin,
+                    });
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    self.incremental(&input, forward, origin);
+                    self.prompt = Some(Prompt::Search {
+                        forward,
+                        input,
+                        origin,
+                    });
+                }
+                _ => {
+                    self.prompt = Some(Prompt::Search {
+                        forward,
+                        input,
+                        origin,
+                    })
+                }
+            },
+            Prompt::Goto { mut input } => match key.code {
+                KeyCode::Esc => {}
+                KeyCode::Enter => {
+                    if let Ok(line) = input.parse::<usize>() {
+                        self.top = line.saturating_sub(1).min(self.max_top());
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.prompt = Some(Prompt::Goto { input });
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    input.push(c);
+                    self.prompt = Some(Prompt::Goto { input });
+                }
+                _ => self.prompt = Some(Prompt::Goto { input }),
+            },
+        }
+    }
+
+    /// Moves to the first match at or after (before, for `?`) the line the
+    /// search started from.
+    fn incremental(&mut self, pattern: &str, forward: bool, origin: usize) {
+        if pattern.is_empty() {
+            self.top = origin;
+            return;
+        }
+        if let Some(line) = find_line(&self.doc().lines, pattern, origin, forward) {
+            self.reveal(line, pattern);
+        }
+    }
+
+    fn repeat_search(&mut self, reverse: bool) {
+        let Some(search) = self.search.clone() else {
+            self.message = Some("No previous search".to_string());
+            return;
+        };
+        let forward = search.forward != reverse;
+        let start = if forward { self.top + 1 } else { self.top };
+        match find_line(&self.doc().lines, &search.pattern, start, forward) {
+            Some(line) => {
+                if forward && line < start || !forward && line >= start {
+                    self.message = Some("Search wrapped".to_string());
+                }
+                self.reveal(line, &search.pattern);
+            }
+            None => self.message = Some("Pattern not found".to_string()),
+        }
+    }
+
+    /// Scrolls so `line` is the top row and its first match is visible.
+    fn reveal(&mut self, line: usize, pattern: &str) {
+        self.top = line.min(self.max_top());
+        let text = &self.doc().lines[line];
+        if let Some(first) = find_matches(text, pattern).first() {
+            let col = display_width(&text[..first.start]);
+            let width = terminal::size().map_or(80, |(w, _)| w as usize);
+            if col < self.left || col >= self.left + width.saturating_sub(12) {
+                self.left = col.saturating_sub(H_STEP) / H_STEP * H_STEP;
+            }
+        }
+    }
+
+    fn render(&mut self, style: &Style, theme: &Theme) -> Result<(), String> {
+        let (w, h) = terminal::size().map_err(|e| e.to_string())?;
+        let w = w as usize;
+        let h = h as usize;
+        self.body_rows = h.saturating_sub(2).max(1);
+        self.top = self.top.min(self.max_top());
+
+        let doc = self.doc();
+        let total = doc.lines.len();
+        let num_w = total.max(1).to_string().len().max(4);
+        let gutter = num_w + 3;
+        let text_w = w.saturating_sub(gutter);
+        let pattern = self.pattern();
+
+        let mut out = io::stdout();
+        queue!(
+            out,
+            BeginSynchronizedUpdate,
+            MoveTo(0, 0),
+            Clear(ClearType::All)
+        )
+        .map_err(|e| e.to_string())?;
+
+        let files = if self.docs.len() > 1 {
+            format!(" [{}/{}]", self.current + 1, self.docs.len())
+        } else {
+            String::new()
+        };
+        let language = doc.language.map(|l| format!("  {l}")).unwrap_or_default();
+        draw_line(
+            &mut out,
+            0,
+            &style.paint(
+                theme.title,
+                pad(&format!("-- {}{files}{language} --", doc.label), w),
+            ),
+        )?;
+
+        for row in 0..self.body_rows {
+            let idx = self.top + row;
+            let Some(line) = doc.lines.get(idx) else {
+                draw_line(&mut out, (row + 1) as u16, &style.paint(theme.subtle, "~"))?;
+                continue;
+            };
+            let matches = pattern.map(|p| find_matches(line, p)).unwrap_or_default();
+            let number = style.paint(theme.number, format!("{:>num_w$} │ ", idx + 1));
+            let text = render_line(
+                line,
+                &doc.spans[idx],
+                &matches,
+                self.left,
+                text_w,
+                style,
+                theme,
+            );
+            draw_line(&mut out, (row + 1) as u16, &format!("{number}{text}"))?;
+        }
+
+        let status = match &self.prompt {
+            Some(Prompt::Search { forward, input, .. }) => {
+                format!("{}{input}", if *forward { '/' } else { '?' })
+            }
+            Some(Prompt::Goto { input }) => format!(":{input}"),
+            None => match &self.message {
+                Some(message) => message.clone(),
+                None => {
+                    let last = (self.top + self.body_rows).min(total);
+                    let pct = (last * 100).checked_div(total).unwrap_or(100);
+                    let col = if self.left > 0 {
+                        format!("  col {}", self.left + 1)
+                    } else {
+                        String::new()
+                    };
+                    let switch = if self.docs.len() > 1 {
+                        "  Tab/[ ] file"
+                    } else {
+                        ""
+                    };
+                    format!(
+                        "{}-{last}/{total} {pct}%{col}   / ? search  n/N next  : line  h/l scroll{switch}  q quit",
+                        (self.top + 1).min(total)
+                    )
+                }
+            },
+        };
+        let status_color = if self.prompt.is_some() {
+            theme.accent
+        } else {
+            theme.subtle
+        };
+        draw_line(
+            &mut out,
+            (h.saturating_sub(1)) as u16,
+            &style.paint(status_color, pad(&status, w)),
+        )?;
+
+        queue!(out, EndSynchronizedUpdate).map_err(|e| e.to_string())?;
+        out.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// How much of a file [`preview`] reads.
+const PREVIEW_BYTES: u64 = 256 * 1024;
+
+/// `rows` lines of `path` starting at line `top`, highlighted the way
+/// `--view` shows them and clipped to `width` columns. Binary files and read
+/// errors become a one-line notice.
+pub(crate) fn preview(
+    path: &Path,
+    top: usize,
+    rows: usize,
+    width: usize,
+    style: &Style,
+    theme: &Theme,
+) -> Vec<String> {
+    let mut raw = Vec::new();
+    let read = fs::File::open(path).and_then(|file| file.take(PREVIEW_BYTES).read_to_end(&mut raw));
+    if let Err(err) = read {
+        return vec![style.paint(theme.warn, err.to_string())];
+    }
+    let Some(encoding) = sniff::detect(&raw) else {
+        let size = fs::metadata(path).map_or(raw.len() as u64, |md| md.len());
+        return vec![style.paint(theme.warn, format!("binary file, {size} bytes"))];
+    };
+    let text = sniff::decode(&raw, encoding);
+    // Highlighting state carries across lines, so lex from the top.
+    let head = text.lines().take(top + rows).collect::<Vec<_>>().join("\n");
+    let doc = Doc::new(String::new(), &head, syntax::detect(Some(path), &text));
+    doc.lines
+        .iter()
+        .zip(&doc.spans)
+        .skip(top)
+        .map(|(line, spans)| render_line(line, spans, &[], 0, width, style, theme))
+        .collect()
+}
+
+/// Index of the next line containing `pattern`, wrapping around the file:
+/// forward searches start at `start`, backward ones at the line before it.
+fn find_line(lines: &[String], pattern: &str, start: usize, forward: bool) -> Option<usize> {
+    let n = lines.len();
+    if pattern.is_empty() {
+        return None;
+    }
+    let start = start.min(n);
+    let hit = |i: &usize| !find_matches(&lines[*i], pattern).is_empty();
+    if forward {
+        (start..n).chain(0..start).find(hit)
+    } else {
+        (0..start).rev().chain((start..n).rev()).find(hit)
+    }
+}
+
+/// Byte ranges of `pattern` in `line`. Smart case: an all-lowercase pattern
+/// matches case-insensitively.
+fn find_matches(line: &str, pattern: &str) -> Vec<Range<usize>> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    let fold = !pattern.chars().any(char::is_uppercase);
+    let (hay, needle) = if fold {
+        (line.to_ascii_lowercase(), pattern.to_ascii_lowercase())
+    } else {
+        (line.to_string(), pattern.to_string())
+    };
+    hay.match_indices(&needle)
+        .map(|(at, m)| at..at + m.len())
+        .collect()
+}
+
+/// Paints the visible window `[left, left + width)` of a line, keeping
+/// highlight classes and reversing search matches.
+fn render_line(
+    line: &str,
+    spans: &Spans,
+    matches: &[Range<usize>],
+    left: usize,
+    width: usize,
+    style: &Style,
+    theme: &Theme,
+) -> String {
+    let mut out = String::new();
+    let mut segment = String::new();
+    let mut current: Option<(Class, bool)> = None;
+    let mut col = 0usize;
+    let mut span = spans.iter().peekable();
+
+    let flush = |out: &mut String, segment: &mut String, key: Option<(Class, bool)>| {
+        if segment.is_empty() {
+            return;
+        }
+        let (class, matched) = key.unwrap_or((Class::Plain, false));
+        let color = class.color(theme);
+        if style.color && (matched || !color.is_empty()) {
+            let reverse = if matched { REVERSE } else { "" };
+            out.push_str(&format!("{reverse}{color}{segment}\x1b[0m"));
+        } else {
+            out.push_str(segment);
+        }
+        segment.clear();
+    };
+
+    for (at, ch) in line.char_indices() {
+        let w = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if col < left {
+            col += w;
+            continue;
+        }
+        if col + w > left + width {
+            break;
+        }
+        col += w;
+        while span.peek().is_some_and(|(_, r)| r.end <= at) {
+            span.next();
+        }
+        let class = span
+            .peek()
+            .filter(|(_, r)| r.contains(&at))
+            .map_or(Class::Plain, |(c, _)| *c);
+        let matched = matches.iter().any(|m| m.contains(&at));
+        let key = Some((class, matched));
+        if key != current {
+            flush(&mut out, &mut segment, current);
+            current = key;
+        }
+        segment.push(ch);
+    }
+    flush(&mut out, &mut segment, current);
+    out
+}
+
+/// Forced or sniffed encoding; binary input falls back to lossy UTF-8.
+fn decode(raw: &[u8], encoding: Option<Encoding>) -> String {
+    let encoding = encoding
+        .or_else(|| sniff::detect(raw))
+        .unwrap_or(Encoding::Utf8);
+    sniff::decode(raw, encoding)
+}
+
+fn expand_tabs(line: &str) -> String {
+    if !line.contains('\t') {
+        return line.to_string();
+    }
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut col = 0;
+    for ch in line.chars() {
+        if ch == '\t' {
+            let n = TAB_WIDTH - col % TAB_WIDTH;
+            out.extend(std::iter::repeat_n(' ', n));
+            col += n;
+        } else {
+            out.push(ch);
+            col += UnicodeWidthChar::width(ch).unwrap_or(0);
+        }
+    }
+    out
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
+        .sum()
+}
+
+fn draw_line(out: &mut io::Stdout, y: u16, line: &str) -> Result<(), String> {
+    queue!(
+        out,
+        MoveTo(0, y),
+        Clear(ClearType::CurrentLine),
+        Print(line)
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn pad(s: &str, width: usize) -> String {
+    let truncated = if s.chars().count() > width {
+        let mut out = String::new();
+        for (i, ch) in s.chars().enumerate() {
+            if i + 1 >= width {
+                out.push('…');
+                break;
+            }
+            out.push(ch);
+        }
+        out
+    } else {
+        s.to_string()
+    };
+
+    if truncated.chars().count() >= width {
+        truncated
+    } else {
+        format!("{truncated:<width$}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_tabs, find_line, find_matches, render_line};
+    use crate::commands::cat::highlight::Class;
+    use crate::core::color::ColorDepth;
+    use crate::core::style::Style;
+    use crate::core::theme;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn search_is_smart_case() {
+        assert_eq!(find_matches("Foo foo FOO", "foo"), vec![0..3, 4..7, 8..11]);
+        assert_eq!(find_matches("Foo foo FOO", "Foo"), vec![0..3]);
+    }
+
+    #[test]
+    fn line_search_wraps_in_both_directions() {
+        let doc = lines("alpha\nbeta\nalpha again\ngamma");
+        assert_eq!(find_line(&doc, "alpha", 1, true), Some(2));
+        assert_eq!(find_line(&doc, "alpha", 3, true), Some(0));
+        assert_eq!(find_line(&doc, "alpha", 2, false), Some(0));
+        assert_eq!(find_line(&doc, "gamma", 0, false), Some(3));
+        assert_eq!(find_line(&doc, "delta", 0, true), None);
+    }
+
+    #[test]
+    fn renders_the_horizontal_window_with_matches_reversed() {
+        let plain = Style {
+            color: false,
+            icons: false,
+            depth: ColorDepth::Ansi16,
+        };
+        let theme = theme::plain();
+        let line = "0123456789abcdef";
+        let spans = vec![(Class::Plain, 0..line.len())];
+        assert_eq!(render_line(line, &spans, &[], 8, 4, &plain, &theme), "89ab");
+
+        let color = Style {
+            color: true,
+            ..plain
+        };
+        let matches = find_matches(line, "9a");
+        let out = render_line(line, &spans, &matches, 8, 4, &color, &theme);
+        assert_eq!(out, "8\x1b[7m9a\x1b[0mb");
+    }
+
+    #[test]
+    fn expands_tabs_to_stops() {
+        assert_eq!(expand_tabs("a\tb\t\tc"), "a   b       c");
+    }
+}
@@ -0,0 +1,129 @@
+use super::highlight::{Class, Spans};
+use crate::core::style::Style;
+use crate::core::theme::Theme;
+
+/// GNU `cat -v` notation: `^X` for control bytes, `^?` for DEL and `M-` for
+/// bytes with the high bit set. Tabs stay literal unless `show_tabs`.
+pub fn caret(bytes: &[u8], show_tabs: bool) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &b in bytes {
+        let (meta, low) = if b >= 0x80 {
+            (true, b - 0x80)
+        } else {
+            (false, b)
+        };
+        if meta {
+            out.push_str("M-");
+        }
+        match low {
+            b'\t' if !meta && !show_tabs => out.push('\t'),
+            0x00..=0x1f => {
+                out.push('^');
+                out.push((low + 0x40) as char);
+            }
+            0x7f => out.push_str("^?"),
+            _ => out.push(low as char),
+        }
+    }
+    out
+}
+
+/// Glyph for a character that would otherwise be invisible, if any.
+fn glyph(c: char, show_tabs: bool) -> Option<String> {
+    Some(match c {
+        '\t' if show_tabs => "→".to_string(),
+        '\t' => return None,
+        // Control pictures block: ␀ ␁ ... ␟.
+        '\0'..='\x1f' => char::from_u32(0x2400 + c as u32)?.to_string(),
+        '\x7f' => "␡".to_string(),
+        '\u{a0}' => "⍽".to_string(),
+        '\u{80}'..='\u{9f}' | '\u{200b}'..='\u{200f}' | '\u{2028}' | '\u{2029}' | '\u{feff}' => {
+            format!("<U+{:04X}>", c as u32)
+        }
+        _ => return None,
+    })
+}
+
+/// Pretty-mode `-A`: paints `line` with its highlight spans, swapping
+/// control characters, non-breaking spaces and other invisible characters
+/// for glyphs (subtle) and marking trailing whitespace (warn).
+pub fn glyphs(
+    line: &str,
+    spans: Option<&Spans>,
+    show_tabs: bool,
+    style: &Style,
+    theme: &Theme,
+) -> String {
+    let trailing = line.trim_end_matches([' ', '\t']).len();
+    let mut out = String::with_capacity(line.len() + 16);
+    let mut segment = String::new();
+    let mut current = "";
+    let mut span = spans.map(|s| s.iter().peekable());
+
+    for (at, c) in line.char_indices() {
+        let (color, text) = if at >= trailing {
+            let text = if c == '\t' { "→" } else { "·" };
+            (theme.warn, text.to_string())
+        } else if let Some(text) = glyph(c, show_tabs) {
+            (theme.subtle, text)
+        } else {
+            let class = span.as_mut().map_or(Class::Plain, |it| {
+                while it.peek().is_some_and(|(_, r)| r.end <= at) {
+                    it.next();
+                }
+                it.peek()
+                    .filter(|(_, r)| r.contains(&at))
+                    .map_or(Class::Plain, |(c, _)| *c)
+            });
+            (class.color(theme), c.to_string())
+        };
+        if color != current {
+            push_segment(&mut out, &segment, current, style);
+            segment.clear();
+            current = color;
+        }
+        segment.push_str(&text);
+    }
+    push_segment(&mut out, &segment, current, style);
+    out
+}
+
+fn push_segment(out: &mut String, segment: &str, color: &str, style: &Style) {
+    if segment.is_empty() {
+        return;
+    }
+    if color.is_empty() {
+        out.push_str(segment);
+    } else {
+        out.push_str(&style.paint(color, segment));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{caret, glyphs};
+    use crate::core::color::ColorDepth;
+    use crate::core::style::Style;
+    use crate::core::theme;
+
+    #[test]
+    fn caret_notation_matches_gnu_cat() {
+        assert_eq!(caret(b"a\tb\x01\x7f\r", false), "a\tb^A^?^M");
+        assert_eq!(caret(b"\t\xe9\x80", true), "^IM-iM-^@");
+    }
+
+    #[test]
+    fn glyphs_mark_invisible_characters_and_trailing_whitespace() {
+        let style = Style {
+            color: false,
+            icons: false,
+            depth: ColorDepth::Ansi16,
+        };
+        let theme = theme::plain();
+        assert_eq!(
+            glyphs("a\u{a0}b\x1b\tc  \t", None, true, &style, &theme),
+            "a⍽b␛→c··→"
+        );
+        assert_eq!(glyphs("\tx\r", None, false, &style, &theme), "\tx␍");
+    }
+}
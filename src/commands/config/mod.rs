@@ -0,0 +1,132 @@
+use std::env;
+
+use crate::core::settings::{self, Kind, SECTIONS, Settings};
+use crate::core::style::Style;
+use crate::core::theme;
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        None | Some("show") => show(),
+        Some("path") => {
+            match settings::config_path() {
+                Some(path) => println!("{}", path.display()),
+                None => return Err("unable to resolve config directory".to_string()),
+            }
+            Ok(())
+        }
+        Some("-h" | "--help") => {
+            print_help();
+            Ok(())
+        }
+        Some(other) => Err(format!("config supports: show | path (got `{other}`)")),
+    }
+}
+
+fn show() -> Result<(), String> {
+    settings::check()?;
+    let settings = settings::current();
+    let style = Style::for_stdout();
+    let t = theme::active(None);
+
+    let file_label = match (settings.path(), settings::config_path()) {
+        (Some(path), _) => path.display().to_string(),
+        (None, Some(path)) => format!("{} (not found)", path.display()),
+        (None, None) => "(no config directory)".to_string(),
+    };
+    println!(
+        "{} {}",
+        style.paint(t.title, "# config file:"),
+        style.paint(t.info, file_label)
+    );
+
+    for (section, keys) in SECTIONS {
+        println!();
+        println!("{}", style.paint(t.title, format!("[{section}]")));
+        let rows = keys
+            .iter()
+            .map(|key| {
+                let (value, source) = effective(settings, section, key.name).unwrap_or_else(|| {
+                    (
+                        display_default(key.kind, key.default),
+                        "default".to_string(),
+                    )
+                });
+                (key.name, value, source)
+            })
+            .collect::<Vec<_>>();
+        let key_w = rows.iter().map(|r| r.0.len()).max().unwrap_or(0);
+        let value_w = rows.iter().map(|r| r.1.chars().count()).max().unwrap_or(0);
+        for (name, value, source) in rows {
+            println!(
+                "{} = {}  {}",
+                style.paint(t.accent, format!("{name:key_w$}")),
+                style.paint(t.ok, format!("{value:value_w$}")),
+                style.paint(t.subtle, format!("# {source}"))
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Value and origin for keys set by the environment or config.toml; `None`
+/// means the built-in default applies.
+fn effective(settings: &Settings, section: &str, key: &str) -> Option<(String, String)> {
+    let env_override = match (section, key) {
+        ("global", "theme") => env_value("DUSK_THEME"),
+        ("global", "color") => env_value("DUSK_COLOR"),
+        ("global", "color-depth") => env_value("DUSK_COLOR_DEPTH"),
+        ("global", "pager") => env_value("DUSK_PAGER"),
+        _ => None,
+    };
+    if let Some((var, value)) = env_override {
+        return Some((format!("\"{value}\""), format!("env {var}")));
+    }
+    settings
+        .get(section, key)
+        .map(|v| (v.to_string(), "config file".to_string()))
+}
+
+fn env_value(var: &str) -> Option<(String, String)> {
+    env::var(var)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| (var.to_string(), v))
+}
+
+fn display_default(kind: Kind, default: &str) -> String {
+    match kind {
+        Kind::Str if default != "-" => format!("\"{default}\""),
+        _ => default.to_string(),
+    }
+}
+
+fn print_help() {
+    let style = Style::for_stdout();
+    let theme = theme::active(None);
+    let cmd = |s: &str| style.paint(theme.title, s);
+    let opt = |s: &str| style.paint(theme.accent, s);
+    let desc = |s: &str| style.paint(theme.info, s);
+    println!("{}", cmd("dusk config (global configuration file)"));
+    println!();
+    println!("{}", opt("USAGE"));
+    println!(
+        "  {} {} {}",
+        opt("dusk"),
+        cmd("config show"),
+        desc("# Effective settings and where each value came from")
+    );
+    println!(
+        "  {} {} {}",
+        opt("dusk"),
+        cmd("config path"),
+        desc("# Location of config.toml")
+    );
+    println!();
+    println!("{}", opt("PRECEDENCE"));
+    println!(
+        "  {}",
+        desc(
+            "CLI flags > environment (DUSK_THEME, DUSK_COLOR, DUSK_COLOR_DEPTH, DUSK_PAGER) > config.toml > built-in defaults",
+        )
+    );
+}
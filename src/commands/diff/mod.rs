@@ -1,12 +1,15 @@
+use std::collections::BTreeMap;
 use std::env;
 
 use crate::core::icons;
+use crate::core::pager;
 use crate::core::process;
 use crate::core::style::Style;
 use crate::core::theme;
 
 pub fn run(args: &[String]) -> Result<(), String> {
     process::ensure_command_exists("git", "dusk diff")?;
+    let (args, paging) = pager::take_flag(args)?;
 
     if args.iter().any(|a| a == "-h" || a == "--help") {
         let theme = theme::active(None);
@@ -25,11 +28,17 @@ pub fn run(args: &[String]) -> Result<(), String> {
             "  {} {} {}",
             opt("dusk"),
             cmd("diff"),
-            arg("[theme] [--staged]")
+            arg("[theme] [--staged] [--paging auto|always|never]")
         );
         println!();
         println!("{}", opt("FLAGS"));
         println!("  {} {}", opt("--staged"), desc("Show staged changes"));
+        println!(
+            "  {} {} {}",
+            opt("--paging"),
+            arg("auto|always|never"),
+            desc("Pipe output through $DUSK_PAGER/$PAGER (default: less -RFX)")
+        );
         println!(
             "  {}, {} {}",
             opt("-h"),
@@ -42,7 +51,7 @@ pub fn run(args: &[String]) -> Result<(), String> {
     let mut theme_name: Option<&str> = None;
     let mut staged = false;
 
-    for arg in args {
+    for arg in &args {
         match arg.as_str() {
             "--staged" => staged = true,
             other if !other.starts_with('-') => theme_name = Some(other),
@@ -52,6 +61,7 @@ pub fn run(args: &[String]) -> Result<(), String> {
 
     let theme = theme::active(theme_name);
     let style = Style::for_stdout();
+    let _pager = pager::start(paging);
 
     println!(
         "{}",
@@ -97,7 +107,7 @@ fn render_diff(output: &str, style: &Style, theme: theme::Theme) {
 
         if let Some(rest) = line.strip_prefix("diff --git ") {
             println!();
-            println!("{}", style.paint(theme.accent, format!("{rest}")));
+            println!("{}", style.paint(theme.accent, rest));
             i += 1;
             continue;
         }
@@ -136,8 +146,8 @@ fn render_diff(output: &str, style: &Style, theme: theme::Theme) {
                         left,
                         right,
                         content_w,
-                        theme.warn,
-                        theme.ok,
+                        theme.diff_del,
+                        theme.diff_add,
                     );
                     old_ln += 1;
                     new_ln += 1;
@@ -154,7 +164,7 @@ fn render_diff(output: &str, style: &Style, theme: theme::Theme) {
                         left,
                         "",
                         content_w,
-                        theme.warn,
+                        theme.diff_del,
                         theme.info,
                     );
                     old_ln += 1;
@@ -172,7 +182,7 @@ fn render_diff(output: &str, style: &Style, theme: theme::Theme) {
                         right,
                         content_w,
                         theme.info,
-                        theme.ok,
+                        theme.diff_add,
                     );
                     new_ln += 1;
                     i += 1;
@@ -207,28 +217,72 @@ fn render_diff(output: &str, style: &Style, theme: theme::Theme) {
 }
 
 fn parse_hunk_header(line: &str) -> (usize, usize) {
-    let mut old_ln = 1usize;
-    let mut new_ln = 1usize;
-
-    let parts = line.split_whitespace().collect::<Vec<_>>();
-    if parts.len() >= 3 {
-        if let Some(old) = parts[1].strip_prefix('-') {
-            old_ln = old
-                .split(',')
-                .next()
-                .and_then(|n| n.parse::<usize>().ok())
-                .unwrap_or(1);
+    parse_hunk(line).map_or((1, 1), |h| (h.old_start, h.new_start))
+}
+
+/// Line numbers and lengths from a `@@ -a,b +c,d @@` header; an omitted
+/// length means one line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+}
+
+pub(crate) fn parse_hunk(line: &str) -> Option<Hunk> {
+    let mut parts = line.strip_prefix("@@ ")?.split_whitespace();
+    let range = |part: Option<&str>, sign: char| -> Option<(usize, usize)> {
+        let mut nums = part?.strip_prefix(sign)?.split(',');
+        let start = nums.next()?.parse().ok()?;
+        let len = nums.next().map_or(Some(1), |n| n.parse().ok())?;
+        Some((start, len))
+    };
+    let (old_start, old_len) = range(parts.next(), '-')?;
+    let (new_start, new_len) = range(parts.next(), '+')?;
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+    })
+}
+
+/// How a line of the new file differs from the old one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LineChange {
+    Added,
+    Modified,
+    /// Lines were removed right after this one.
+    RemovedBelow,
+    /// Lines were removed before the first line.
+    RemovedAbove,
+}
+
+/// Per-line change markers (1-based new-file line numbers) from the hunk
+/// headers of a unified diff; `-U0` output gives exact ranges.
+pub(crate) fn line_changes(diff: &str) -> BTreeMap<usize, LineChange> {
+    let mut changes = BTreeMap::new();
+    for hunk in diff.lines().filter_map(parse_hunk) {
+        if hunk.new_len == 0 {
+            let (line, change) = if hunk.new_start == 0 {
+                (1, LineChange::RemovedAbove)
+            } else {
+                (hunk.new_start, LineChange::RemovedBelow)
+            };
+            changes.entry(line).or_insert(change);
+            continue;
         }
-        if let Some(new) = parts[2].strip_prefix('+') {
-            new_ln = new
-                .split(',')
-                .next()
-                .and_then(|n| n.parse::<usize>().ok())
-                .unwrap_or(1);
+        let kind = if hunk.old_len == 0 {
+            LineChange::Added
+        } else {
+            LineChange::Modified
+        };
+        for line in hunk.new_start..hunk.new_start + hunk.new_len {
+            changes.insert(line, kind);
         }
     }
-
-    (old_ln, new_ln)
+    changes
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -272,14 +326,42 @@ fn print_side(
 
 fn truncate(s: &str, width: usize) -> String {
     let mut out = String::new();
-    let mut count = 0usize;
-    for ch in s.chars() {
+    for (count, ch) in s.chars().enumerate() {
         if count >= width.saturating_sub(1) {
             out.push('…');
             return out;
         }
         out.push(ch);
-        count += 1;
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Hunk, LineChange, line_changes, parse_hunk};
+
+    #[test]
+    fn parses_hunk_headers_with_implicit_lengths() {
+        assert_eq!(
+            parse_hunk("@@ -3 +4,2 @@ fn main() {"),
+            Some(Hunk {
+                old_start: 3,
+                old_len: 1,
+                new_start: 4,
+                new_len: 2,
+            })
+        );
+        assert_eq!(parse_hunk("diff --git a/x b/x"), None);
+    }
+
+    #[test]
+    fn maps_zero_context_hunks_to_line_changes() {
+        let diff = "@@ -0,0 +1,2 @@\n+a\n+b\n@@ -5 +7 @@\n-x\n+y\n@@ -9,2 +10,0 @@\n-c\n-d\n";
+        let changes = line_changes(diff);
+        assert_eq!(changes.get(&1), Some(&LineChange::Added));
+        assert_eq!(changes.get(&2), Some(&LineChange::Added));
+        assert_eq!(changes.get(&7), Some(&LineChange::Modified));
+        assert_eq!(changes.get(&10), Some(&LineChange::RemovedBelow));
+        assert_eq!(changes.len(), 4);
+    }
+}
@@ -146,9 +146,16 @@ fn print_help() {
 fn render_hex(path: &Path, style: &Style, theme: theme::Theme) -> Result<(), String> {
     let data = fs::read(path).map_err(|e| format!("failed reading {}: {e}", path.display()))?;
     println!("{}", style.paint(theme.accent, "-- HEX --"));
+    print_hex(&data, 0, style, theme);
+    Ok(())
+}
 
+/// Offset, 16 hex columns and an ASCII gutter per row; also used by `bat
+/// --binary hex`, which passes the input a chunk at a time with `start` as
+/// the chunk's offset.
+pub(crate) fn print_hex(data: &[u8], start: usize, style: &Style, theme: theme::Theme) {
     for (row, chunk) in data.chunks(16).enumerate() {
-        let offset = row * 16;
+        let offset = start + row * 16;
         let mut hex_cols = Vec::new();
         let mut ascii = String::new();
 
@@ -181,8 +188,6 @@ fn render_hex(path: &Path, style: &Style, theme: theme::Theme) -> Result<(), Str
             style.paint(theme.subtle, ascii),
         );
     }
-
-    Ok(())
 }
 
 fn render_asm(path: &Path, style: &Style, theme: theme::Theme) -> Result<(), String> {
@@ -211,7 +216,7 @@ fn color_asm_line(line: &str, style: &Style, theme: theme::Theme) -> String {
     }
 
     if let Some((addr, rest)) = line.split_once(':') {
-        let mut parts = rest.trim_start().split_whitespace().collect::<Vec<_>>();
+        let mut parts = rest.split_whitespace().collect::<Vec<_>>();
         if parts.is_empty() {
             return style.paint(theme.number, line);
         }
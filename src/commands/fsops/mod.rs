@@ -96,10 +96,9 @@ pub fn run(kind: OpKind, args: &[OsString], supports_recursive: bool) -> Result<
         if should_offer_sudo(&parsed.positional)
             && process::command_exists("sudo")
             && !is_effective_root()
+            && confirm("Some paths are not owned by current user. Retry with sudo? [y/N] ")?
         {
-            if confirm("Some paths are not owned by current user. Retry with sudo? [y/N] ")? {
-                return run_with_sudo(kind.bin(), args);
-            }
+            return run_with_sudo(kind.bin(), args);
         }
     }
 
@@ -296,10 +295,10 @@ fn is_owned_path(path: &Path) -> bool {
         return meta.uid() == uid;
     }
 
-    if let Some(parent) = path.parent() {
-        if let Ok(meta) = fs::symlink_metadata(parent) {
-            return meta.uid() == uid;
-        }
+    if let Some(parent) = path.parent()
+        && let Ok(meta) = fs::symlink_metadata(parent)
+    {
+        return meta.uid() == uid;
     }
 
     true
@@ -1,47 +1,19 @@
 use std::io::{self, Write};
 use std::time::Duration;
 
-use crossterm::cursor::{Hide, MoveTo, Show};
-use crossterm::event::{
-    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseEventKind,
-};
-use crossterm::execute;
+use crossterm::cursor::MoveTo;
+use crossterm::event::{self, Event, KeyCode, MouseEventKind};
 use crossterm::queue;
 use crossterm::style::Print;
-use crossterm::terminal::{
-    self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate, EnterAlternateScreen,
-    LeaveAlternateScreen,
-};
+use crossterm::terminal::{self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate};
 
 use crate::core::process;
 use crate::core::style::Style;
+use crate::core::terminal::TerminalGuard;
 use crate::core::theme;
 
 use super::diffview;
 
-struct Guard;
-
-impl Guard {
-    fn enter() -> Result<Self, String> {
-        terminal::enable_raw_mode().map_err(|e| format!("failed to enable raw mode: {e}"))?;
-        execute!(io::stdout(), EnterAlternateScreen, Hide, EnableMouseCapture)
-            .map_err(|e| format!("failed to enter alternate screen: {e}"))?;
-        Ok(Self)
-    }
-}
-
-impl Drop for Guard {
-    fn drop(&mut self) {
-        let _ = execute!(
-            io::stdout(),
-            Show,
-            DisableMouseCapture,
-            LeaveAlternateScreen
-        );
-        let _ = terminal::disable_raw_mode();
-    }
-}
-
 pub(super) fn run(theme_name: Option<&str>, staged: bool) -> Result<(), String> {
     let style = Style::for_stdout();
     let theme = theme::active(theme_name);
@@ -54,7 +26,7 @@ pub(super) fn run(theme_name: Option<&str>, staged: bool) -> Result<(), String>
     let diff =
         process::run_capture("git", &args).map_err(|e| format!("failed to run git diff: {e}"))?;
 
-    let _guard = Guard::enter()?;
+    let _guard = TerminalGuard::enter()?;
 
     loop {
         let (w, h) = terminal::size().map_err(|e| e.to_string())?;
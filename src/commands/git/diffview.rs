@@ -136,6 +136,7 @@ pub(crate) fn render_side_by_side(
     rows
 }
 
+#[allow(clippy::too_many_arguments)]
 fn format_diff_row(
     style: &Style,
     theme: theme::Theme,
@@ -322,8 +323,8 @@ fn is_keyword(ext: &str, token: &str) -> bool {
 fn kind_color(kind: SideKind, theme: theme::Theme) -> &'static str {
     match kind {
         SideKind::Context => theme.info,
-        SideKind::Added => theme.ok,
-        SideKind::Removed => theme.warn,
+        SideKind::Added => theme.diff_add,
+        SideKind::Removed => theme.diff_del,
     }
 }
 
@@ -368,9 +369,7 @@ fn parse_hunk_header(line: &str) -> (usize, usize) {
 }
 
 fn parse_ext_from_diff_header(line: &str) -> Option<String> {
-    let Some(idx) = line.rfind(" b/") else {
-        return None;
-    };
+    let idx = line.rfind(" b/")?;
     let path = &line[idx + 3..];
     Path::new(path)
         .extension()
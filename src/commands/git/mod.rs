@@ -1,14 +1,18 @@
 mod diff_tui;
 mod diffview;
+pub(crate) mod status;
 mod tui;
 
 use crate::core::icons;
+use crate::core::pager::{self, Paging};
 use crate::core::process;
 use crate::core::style::Style;
 use crate::core::theme;
 
 pub fn run(args: &[String]) -> Result<(), String> {
     process::ensure_command_exists("git", "dusk git")?;
+    let (args, paging) = pager::take_flag(args)?;
+    let args = args.as_slice();
 
     if args.is_empty() || matches!(args.first().map(String::as_str), Some("-h" | "--help")) {
         let t = theme::active(None);
@@ -20,13 +24,18 @@ pub fn run(args: &[String]) -> Result<(), String> {
         println!("{}", cmd("dusk git (enhanced git visualizer)"));
         println!();
         println!("{}", opt("USAGE"));
-        println!("  {} {} {}", opt("dusk"), cmd("git log"), arg("[theme]"));
+        println!(
+            "  {} {} {}",
+            opt("dusk"),
+            cmd("git log"),
+            arg("[theme] [--paging auto|always|never]")
+        );
         println!("  {} {} {}", opt("dusk"), cmd("git status"), arg("[theme]"));
         println!(
             "  {} {} {}",
             opt("dusk"),
             cmd("git diff"),
-            arg("[theme] [--staged] [--tui]")
+            arg("[theme] [--staged] [--tui] [--paging auto|always|never]")
         );
         println!("  {} {} {}", opt("dusk"), cmd("git tui"), arg("[theme]"));
         println!();
@@ -65,9 +74,9 @@ pub fn run(args: &[String]) -> Result<(), String> {
     let sub = args.first().map(|s| s.as_str()).unwrap_or("log");
 
     match sub {
-        "log" => log_graph(args.get(1).map(String::as_str)),
+        "log" => log_graph(args.get(1).map(String::as_str), paging),
         "status" => status_panel(args.get(1).map(String::as_str)),
-        "diff" => git_diff(&args[1..]),
+        "diff" => git_diff(&args[1..], paging),
         "tui" | "interactive" => tui::run(args.get(1).map(String::as_str)),
         "graph" => {
             Err("`dusk git graph` was removed as redundant. Use `dusk git log`.".to_string())
@@ -77,9 +86,10 @@ pub fn run(args: &[String]) -> Result<(), String> {
     }
 }
 
-fn log_graph(theme_name: Option<&str>) -> Result<(), String> {
+fn log_graph(theme_name: Option<&str>, paging: Option<Paging>) -> Result<(), String> {
     let theme = theme::active(theme_name);
     let style = Style::for_stdout();
+    let _pager = pager::start(paging);
 
     println!(
         "{}",
@@ -115,7 +125,7 @@ fn log_graph(theme_name: Option<&str>) -> Result<(), String> {
     Ok(())
 }
 
-fn git_diff(args: &[String]) -> Result<(), String> {
+fn git_diff(args: &[String], paging: Option<Paging>) -> Result<(), String> {
     if args.iter().any(|a| a == "-h" || a == "--help") {
         let t = theme::active(None);
         let s = Style::for_stdout();
@@ -137,6 +147,12 @@ fn git_diff(args: &[String]) -> Result<(), String> {
         println!("  {} {}", opt("--staged"), desc("Show staged changes"));
         println!("  {} {}", opt("--tui"), desc("Open isolated diff TUI"));
         println!("  {} {}", opt("--no-tui"), desc("Force terminal output"));
+        println!(
+            "  {} {} {}",
+            opt("--paging"),
+            arg("auto|always|never"),
+            desc("Pipe terminal output through $DUSK_PAGER/$PAGER")
+        );
         return Ok(());
     }
 
@@ -159,6 +175,7 @@ fn git_diff(args: &[String]) -> Result<(), String> {
 
     let theme = theme::active(theme_name);
     let style = Style::for_stdout();
+    let _pager = pager::start(paging);
     let mut diff_args = vec!["diff", "--no-color", "--unified=3"];
     if staged {
         diff_args.push("--staged");
@@ -277,10 +294,11 @@ fn format_graph_lines(
 
 fn split_graph_hash(s: &str) -> (&str, &str) {
     for tok in s.split_whitespace() {
-        if tok.len() >= 7 && tok.chars().all(|c| c.is_ascii_hexdigit()) {
-            if let Some(idx) = s.find(tok) {
-                return (&s[..idx], tok);
-            }
+        if tok.len() >= 7
+            && tok.chars().all(|c| c.is_ascii_hexdigit())
+            && let Some(idx) = s.find(tok)
+        {
+            return (&s[..idx], tok);
         }
     }
     (s, "")
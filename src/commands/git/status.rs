@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::process;
+
+/// One entry of `git status --porcelain=v2`. `x`/`y` are the index and
+/// work-tree status letters, with v2's `.` (unchanged) turned back into a
+/// space; untracked entries are `??`, ignored ones `!!` and conflicts carry
+/// git's unmerged letters (`UU`, `AA`, ...).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FileStatus {
+    pub(crate) x: char,
+    pub(crate) y: char,
+    pub(crate) display_path: String,
+    /// Path relative to the directory git ran in (the new name for renames).
+    /// Callers pass `-c status.relativePaths=true`, so a user's config cannot
+    /// turn these into root-relative paths.
+    pub(crate) git_path: String,
+}
+
+impl FileStatus {
+    pub(crate) fn tag(&self) -> &'static str {
+        match (self.x, self.y) {
+            ('?', '?') => "??",
+            ('A', _) => "A ",
+            ('M', _) => "M ",
+            (' ', 'M') => " M",
+            ('R', _) => "R ",
+            ('D', _) => "D ",
+            ('U', _) | (_, 'U') => "U ",
+            _ => "  ",
+        }
+    }
+
+    pub(crate) fn is_untracked(&self) -> bool {
+        self.x == '?'
+    }
+
+    pub(crate) fn is_ignored(&self) -> bool {
+        self.x == '!'
+    }
+
+    pub(crate) fn is_conflicted(&self) -> bool {
+        matches!(
+            (self.x, self.y),
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D')
+        )
+    }
+
+    pub(crate) fn is_deleted(&self) -> bool {
+        self.x == 'D' || self.y == 'D'
+    }
+
+    pub(crate) fn is_modified(&self) -> bool {
+        self.x == 'M' || self.y == 'M'
+    }
+
+    pub(crate) fn is_tracked_change(&self) -> bool {
+        !self.is_untracked() && (self.is_modified() || self.is_deleted())
+    }
+}
+
+/// Parses `git status --porcelain=v2` output. Header (`#`) lines are
+/// skipped; renames are shown as `old => new`.
+pub(crate) fn parse_porcelain(s: &str) -> Vec<FileStatus> {
+    let mut out = Vec::new();
+    for line in s.lines() {
+        let Some((kind, rest)) = line.split_once(' ') else {
+            continue;
+        };
+        // Fields before the path: XY sub mH mI mW hH hI for ordinary
+        // entries, plus a score for renames, and three stages for conflicts.
+        let (fields, xy) = match kind {
+            "1" => (7, rest.get(..2)),
+            "2" => (8, rest.get(..2)),
+            "u" => (9, rest.get(..2)),
+            "?" => (0, Some("??")),
+            "!" => (0, Some("!!")),
+            _ => continue,
+        };
+        let Some(xy) = xy else {
+            continue;
+        };
+        let Some(path) = rest.splitn(fields + 1, ' ').nth(fields) else {
+            continue;
+        };
+
+        let mut letters = xy.chars().map(|c| if c == '.' { ' ' } else { c });
+        let x = letters.next().unwrap_or(' ');
+        let y = letters.next().unwrap_or(' ');
+        let (git_path, display_path) = match path.split_once('\t') {
+            Some((new, old)) => (new.to_string(), format!("{old} => {new}")),
+            None => (path.to_string(), path.to_string()),
+        };
+        out.push(FileStatus {
+            x,
+            y,
+            display_path,
+            git_path,
+        });
+    }
+    out
+}
+
+/// An eza-style two-letter git marker: index (staged) then work tree.
+/// `-` is unchanged, `N` new, `M` modified, `D` deleted, `R` renamed,
+/// `T` type change, `I` ignored and `U` conflicted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Mark {
+    pub(crate) staged: char,
+    pub(crate) unstaged: char,
+}
+
+impl Mark {
+    const CLEAN: Mark = Mark {
+        staged: '-',
+        unstaged: '-',
+    };
+
+    fn from_status(file: &FileStatus) -> Self {
+        if file.is_conflicted() {
+            return Mark {
+                staged: 'U',
+                unstaged: 'U',
+            };
+        }
+        if file.is_untracked() {
+            return Mark {
+                staged: '-',
+                unstaged: 'N',
+            };
+        }
+        if file.is_ignored() {
+            return Mark {
+                staged: '-',
+                unstaged: 'I',
+            };
+        }
+        Mark {
+            staged: letter(file.x),
+            unstaged: letter(file.y),
+        }
+    }
+
+    /// Keeps the more important letter of each column, so a directory shows
+    /// the worst state found below it.
+    fn merge(self, other: Mark) -> Self {
+        let pick = |a: char, b: char| if rank(b) > rank(a) { b } else { a };
+        Mark {
+            staged: pick(self.staged, other.staged),
+            unstaged: pick(self.unstaged, other.unstaged),
+        }
+    }
+}
+
+fn letter(code: char) -> char {
+    match code {
+        ' ' => '-',
+        'A' | 'C' => 'N',
+        'M' | 'D' | 'R' | 'T' => code,
+        _ => 'M',
+    }
+}
+
+fn rank(c: char) -> u8 {
+    match c {
+        'U' => 7,
+        'D' => 6,
+        'M' => 5,
+        'R' => 4,
+        'T' => 3,
+        'N' => 2,
+        'I' => 1,
+        _ => 0,
+    }
+}
+
+/// The result of one `git status --porcelain=v2` below a directory, for
+/// xtree's `--git-status` and ls's `--git`, keyed by path.
+pub(crate) struct GitStatus {
+    root: PathBuf,
+    files: HashMap<PathBuf, Mark>,
+    /// Rolled-up marks for every directory holding a change.
+    dirs: HashMap<PathBuf, Mark>,
+}
+
+impl GitStatus {
+    /// Runs `git status` for `root`; `None` outside a repository. Ignored
+    /// entries are only asked for when gitignored files are shown at all.
+    /// Keys are `root` joined with git's paths, so a relative `root` matches
+    /// paths listed from it.
+    pub(crate) fn load(
+        root: &Path,
+        with_ignored: bool,
+        used_for: &str,
+    ) -> Result<Option<Self>, String> {
+        let abs = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        if !abs.ancestors().any(|dir| dir.join(".git").exists()) {
+            return Ok(None);
+        }
+        process::ensure_command_exists("git", used_for)?;
+
+        // Paths come back relative to `root`, limited to it by the `.` pathspec.
+        let mut args = vec![
+            "-c",
+            "status.relativePaths=true",
+            "status",
+            "--porcelain=v2",
+        ];
+        if with_ignored {
+            args.push("--ignored=matching");
+        }
+        args.extend(["--", "."]);
+        let output = process::run_capture_in(root, "git", &args)
+            .map_err(|err| format!("git status failed: {err}"))?
+            .ok_or_else(|| format!("git status failed in {}", root.display()))?;
+        Ok(Some(Self::from_porcelain(root, &output)))
+    }
+
+    fn from_porcelain(root: &Path, output: &str) -> Self {
+        let mut files = HashMap::new();
+        let mut dirs: HashMap<PathBuf, Mark> = HashMap::new();
+        for file in parse_porcelain(output) {
+            // Untracked or ignored directories come back whole, as `dir/`.
+            let path = root.join(file.git_path.trim_end_matches('/'));
+            let mark = Mark::from_status(&file);
+            if !file.is_ignored() {
+                for dir in path.ancestors().skip(1) {
+                    let rolled = dirs.entry(dir.to_path_buf()).or_insert(Mark::CLEAN);
+                    *rolled = rolled.merge(mark);
+                    if dir == root {
+                        break;
+                    }
+                }
+            }
+            files.insert(path, mark);
+        }
+        Self {
+            root: root.to_path_buf(),
+            files,
+            dirs,
+        }
+    }
+
+    /// The marker for `path`: its own status, the rollup for a directory,
+    /// or that of an untracked/ignored directory it sits in.
+    pub(crate) fn mark(&self, path: &Path, is_dir: bool) -> Option<Mark> {
+        if !path.starts_with(&self.root) {
+            return None;
+        }
+        if let Some(mark) = self.files.get(path) {
+            return Some(*mark);
+        }
+        if is_dir && let Some(mark) = self.dirs.get(path) {
+            return Some(*mark);
+        }
+        let inherited = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .find_map(|dir| self.files.get(dir));
+        Some(inherited.copied().unwrap_or(Mark::CLEAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GitStatus, Mark, parse_porcelain};
+    use std::path::Path;
+
+    #[test]
+    fn parses_porcelain_v2_entries() {
+        let out = "# branch.oid abc\n\
+                   1 .M N... 100644 100644 100644 aaa bbb src/main.rs\n\
+                   1 A. N... 000000 100644 100644 000 ccc new file.rs\n\
+                   2 R. N... 100644 100644 100644 ddd ddd R100 lib.rs\told.rs\n\
+                   u UU N... 100644 100644 100644 100644 e1 e2 e3 both.rs\n\
+                   ? notes.txt\n\
+                   ! target/\n";
+        let files = parse_porcelain(out);
+        let summary = files
+            .iter()
+            .map(|f| (f.x, f.y, f.git_path.as_str(), f.display_path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (' ', 'M', "src/main.rs", "src/main.rs"),
+                ('A', ' ', "new file.rs", "new file.rs"),
+                ('R', ' ', "lib.rs", "old.rs => lib.rs"),
+                ('U', 'U', "both.rs", "both.rs"),
+                ('?', '?', "notes.txt", "notes.txt"),
+                ('!', '!', "target/", "target/"),
+            ]
+        );
+        assert!(files[3].is_conflicted());
+        assert!(files[5].is_ignored());
+    }
+
+    fn mark(s: &str) -> Option<Mark> {
+        let mut chars = s.chars();
+        Some(Mark {
+            staged: chars.next().unwrap(),
+            unstaged: chars.next().unwrap(),
+        })
+    }
+
+    #[test]
+    fn marks_files_and_rolls_changes_up_to_directories() {
+        let output = "1 .M N... 100644 100644 100644 a b src/lib.rs\n\
+                      1 A. N... 000000 100644 100644 0 c src/new.rs\n\
+                      1 D. N... 100644 000000 000000 d 0 docs/gone.md\n\
+                      ? scratch/\n\
+                      ! target/\n";
+        let status = GitStatus::from_porcelain(Path::new("/repo"), output);
+        let at = |p: &str, dir: bool| status.mark(Path::new(p), dir);
+
+        assert_eq!(at("/repo/src/lib.rs", false), mark("-M"));
+        assert_eq!(at("/repo/src/new.rs", false), mark("N-"));
+        assert_eq!(at("/repo/src/main.rs", false), mark("--"));
+        assert_eq!(at("/repo/src", true), mark("NM"));
+        assert_eq!(at("/repo/docs", true), mark("D-"));
+        assert_eq!(at("/repo/scratch/notes.txt", false), mark("-N"));
+        assert_eq!(at("/repo/target/debug", true), mark("-I"));
+        assert_eq!(at("/elsewhere/file", false), None);
+    }
+}
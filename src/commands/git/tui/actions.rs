@@ -3,6 +3,7 @@ use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::commands::git::status::parse_porcelain;
 use crate::core::process;
 
 use super::*;
@@ -58,7 +59,12 @@ impl App {
             .ok()
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
-        let porcelain = git_capture(&["status", "--porcelain=1"])?;
+        let porcelain = git_capture(&[
+            "-c",
+            "status.relativePaths=true",
+            "status",
+            "--porcelain=v2",
+        ])?;
         self.files = parse_porcelain(&porcelain);
 
         if self.selected >= self.files.len() {
@@ -606,7 +612,11 @@ impl App {
             "themes" => {
                 self.status_msg = format!(
                     "Themes: {}",
-                    THEMES.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+                    theme::all()
+                        .iter()
+                        .map(|t| t.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
                 );
             }
             "theme" => {
@@ -637,17 +647,18 @@ impl App {
     }
 
     pub(super) fn cycle_theme(&mut self) {
-        let idx = THEMES
+        let themes = theme::all();
+        let idx = themes
             .iter()
             .position(|t| t.name == self.theme.name)
             .unwrap_or(0);
-        self.theme = THEMES[(idx + 1) % THEMES.len()];
+        self.theme = themes[(idx + 1) % themes.len()];
         self.status_msg = format!("Theme: {}", self.theme.name);
     }
 
     pub(super) fn apply_theme(&mut self, name: &str) -> bool {
-        if let Some(found) = THEMES.iter().find(|t| t.name.eq_ignore_ascii_case(name)) {
-            self.theme = *found;
+        if let Some(found) = theme::find(name) {
+            self.theme = found;
             true
         } else {
             false
@@ -718,7 +729,7 @@ impl App {
             },
         ];
 
-        for t in THEMES {
+        for t in theme::all() {
             entries.push(PaletteEntry {
                 label: format!("Theme: {}", t.name),
                 action: PaletteAction::Theme(t.name),
@@ -906,33 +917,6 @@ pub(super) fn command_mode_help() -> String {
     "Cmds: help|cmdhelp|refresh|stage|unstage|stage-all|unstage-all|commit <msg>|push|push-remote <remote>/<branch>|branch <name>|switch <name>|workspace|graph-tab|commitdiff|diff|file-diff|repo-diff|toggle-diff|theme <name>|themes|palette|quit".to_string()
 }
 
-fn parse_porcelain(s: &str) -> Vec<FileStatus> {
-    let mut out = Vec::new();
-    for line in s.lines() {
-        if line.len() < 4 {
-            continue;
-        }
-
-        let x = line.chars().next().unwrap_or(' ');
-        let y = line.chars().nth(1).unwrap_or(' ');
-        let mut display = line[3..].to_string();
-        let mut git_path = display.clone();
-
-        if let Some((_, new)) = display.rsplit_once(" -> ") {
-            git_path = new.to_string();
-            display = format!("{} => {}", display.split(" -> ").next().unwrap_or(""), new);
-        }
-
-        out.push(FileStatus {
-            x,
-            y,
-            display_path: display,
-            git_path,
-        });
-    }
-    out
-}
-
 pub(super) fn git_capture(args: &[&str]) -> Result<String, String> {
     process::ensure_command_exists("git", "dusk git tui")?;
     let output = Command::new("git")
@@ -30,23 +30,17 @@ impl App {
                 self.open_palette();
                 changed = true;
             }
-            KeyCode::Char('1') => {
-                if self.tab != Tab::Workspace {
-                    self.tab = Tab::Workspace;
-                    changed = true;
-                }
+            KeyCode::Char('1') if self.tab != Tab::Workspace => {
+                self.tab = Tab::Workspace;
+                changed = true;
             }
-            KeyCode::Char('2') => {
-                if self.tab != Tab::Graph {
-                    self.tab = Tab::Graph;
-                    changed = true;
-                }
+            KeyCode::Char('2') if self.tab != Tab::Graph => {
+                self.tab = Tab::Graph;
+                changed = true;
             }
-            KeyCode::Char('3') => {
-                if self.tab != Tab::CommitDiff {
-                    self.tab = Tab::CommitDiff;
-                    changed = true;
-                }
+            KeyCode::Char('3') if self.tab != Tab::CommitDiff => {
+                self.tab = Tab::CommitDiff;
+                changed = true;
             }
             KeyCode::Char('r') => {
                 self.refresh()?;
@@ -239,12 +233,10 @@ impl App {
                     self.select_next_palette();
                     changed = true;
                 }
-                KeyCode::Char(ch) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.palette_query.push(ch);
-                        self.clamp_palette_selected();
-                        changed = true;
-                    }
+                KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.palette_query.push(ch);
+                    self.clamp_palette_selected();
+                    changed = true;
                 }
                 _ => {}
             },
@@ -324,11 +316,9 @@ impl App {
                 self.input.clear();
                 changed = true;
             }
-            KeyCode::Char(ch) => {
-                if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                    self.input.push(ch);
-                    changed = true;
-                }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(ch);
+                changed = true;
             }
             _ => {}
         }
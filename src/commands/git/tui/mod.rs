@@ -1,14 +1,12 @@
-use std::io;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crossterm::cursor::{Hide, Show};
-use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseEventKind};
-use crossterm::execute;
-use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::event::{self, Event, MouseEventKind};
 
+use super::status::FileStatus;
 use crate::core::icons;
 use crate::core::style::Style;
-use crate::core::theme::{self, THEMES, Theme};
+use crate::core::terminal::TerminalGuard;
+use crate::core::theme::{self, Theme};
 
 mod actions;
 mod input;
@@ -69,45 +67,6 @@ struct PaletteEntry {
     action: PaletteAction,
 }
 
-#[derive(Clone, Debug)]
-struct FileStatus {
-    x: char,
-    y: char,
-    display_path: String,
-    git_path: String,
-}
-
-impl FileStatus {
-    fn tag(&self) -> &'static str {
-        match (self.x, self.y) {
-            ('?', '?') => "??",
-            ('A', _) => "A ",
-            ('M', ' ') | ('M', _) => "M ",
-            (' ', 'M') => " M",
-            ('R', _) => "R ",
-            ('D', _) => "D ",
-            ('U', _) | (_, 'U') => "U ",
-            _ => "  ",
-        }
-    }
-
-    fn is_untracked(&self) -> bool {
-        self.x == '?'
-    }
-
-    fn is_deleted(&self) -> bool {
-        self.x == 'D' || self.y == 'D'
-    }
-
-    fn is_modified(&self) -> bool {
-        self.x == 'M' || self.y == 'M'
-    }
-
-    fn is_tracked_change(&self) -> bool {
-        !self.is_untracked() && (self.is_modified() || self.is_deleted())
-    }
-}
-
 enum StatusRow {
     Header(&'static str),
     Spacer,
@@ -148,29 +107,6 @@ struct App {
     push_overlay_ok: Option<bool>,
 }
 
-struct TerminalGuard;
-
-impl TerminalGuard {
-    fn enter() -> Result<Self, String> {
-        terminal::enable_raw_mode().map_err(|e| format!("failed to enable raw mode: {e}"))?;
-        execute!(io::stdout(), EnterAlternateScreen, Hide, EnableMouseCapture)
-            .map_err(|e| format!("failed to enter alternate screen: {e}"))?;
-        Ok(Self)
-    }
-}
-
-impl Drop for TerminalGuard {
-    fn drop(&mut self) {
-        let _ = execute!(
-            io::stdout(),
-            Show,
-            DisableMouseCapture,
-            LeaveAlternateScreen
-        );
-        let _ = terminal::disable_raw_mode();
-    }
-}
-
 pub fn run(theme_name: Option<&str>) -> Result<(), String> {
     let mut app = App::new(theme::active(theme_name), Style::for_stdout());
     app.refresh()?;
@@ -261,7 +197,7 @@ fn blink_phase() -> bool {
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_millis())
         .unwrap_or(0);
-    (ms / 500) % 2 == 0
+    (ms / 500).is_multiple_of(2)
 }
 
 fn is_diff_scroll_context(app: &App) -> bool {
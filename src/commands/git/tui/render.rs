@@ -604,7 +604,7 @@ impl App {
                 &format!(
                     "{}{}{}",
                     self.style.paint(self.theme.accent, "│"),
-                    self.style.paint(*color, pad_display(line, inner_w)),
+                    self.style.paint(color, pad_display(line, inner_w)),
                     self.style.paint(self.theme.accent, "│")
                 ),
             )?;
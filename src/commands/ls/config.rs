@@ -1,6 +1,8 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
 
+use crate::core::settings::Settings;
+
 #[derive(Clone, Copy)]
 pub(super) enum SortMode {
     Name,
@@ -12,6 +14,19 @@ pub(super) enum SortMode {
     Ext,
 }
 
+/// How entries are laid out without `-l`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Layout {
+    /// Columns on a terminal, one per line otherwise.
+    Auto,
+    /// `-1`
+    Lines,
+    /// `-C`: columns filled top to bottom.
+    Columns,
+    /// `-x`: rows filled left to right.
+    Across,
+}
+
 #[derive(Clone, Copy)]
 pub(super) enum ColorMode {
     Auto,
@@ -28,9 +43,30 @@ pub(super) struct Opts {
     pub basic: bool,
     pub file_type: bool,
     pub show_author: bool,
+    /// `--group`, `-i/--inode`, `-s/--blocks` and `--links`: extra long
+    /// columns, in the order `ls -l` prints them.
+    pub show_group: bool,
+    pub show_inode: bool,
+    pub show_blocks: bool,
+    pub show_links: bool,
+    /// `-L/--dereference`: show what symlinks point to instead of the links.
+    pub dereference: bool,
     pub sort: SortMode,
     pub reverse: bool,
     pub human: bool,
+    pub layout: Layout,
+    /// `-R`: list subdirectories too, each under its own header.
+    pub recurse: bool,
+    /// `-T/--tree`: one listing with branches instead of headers.
+    pub tree: bool,
+    /// `--level`: how deep `-R` and `--tree` go.
+    pub level: Option<usize>,
+    /// `--git-ignore`: hide what git ignores.
+    pub git_ignore: bool,
+    /// `--git`: the index/work-tree status column in long format.
+    pub git: bool,
+    /// `--width`: grid width instead of `COLUMNS` or the terminal's.
+    pub width: Option<usize>,
     pub color: ColorMode,
     pub theme: Option<String>,
     pub paths: Vec<PathBuf>,
@@ -47,9 +83,21 @@ impl Default for Opts {
             basic: false,
             file_type: false,
             show_author: false,
+            show_group: false,
+            show_inode: false,
+            show_blocks: false,
+            show_links: false,
+            dereference: false,
             sort: SortMode::Name,
             reverse: false,
             human: false,
+            layout: Layout::Auto,
+            recurse: false,
+            tree: false,
+            level: None,
+            git_ignore: false,
+            git: false,
+            width: None,
             color: ColorMode::Auto,
             theme: None,
             paths: vec![PathBuf::from(".")],
@@ -57,9 +105,10 @@ impl Default for Opts {
     }
 }
 
-pub(super) fn parse(args: &[OsString]) -> Result<Opts, String> {
+pub(super) fn parse(args: &[OsString], settings: &Settings) -> Result<Opts, String> {
     let mut opts = Opts::default();
     opts.paths.clear();
+    apply_settings(&mut opts, settings)?;
 
     let mut it = args.iter().peekable();
     while let Some(arg) = it.next() {
@@ -90,6 +139,44 @@ pub(super) fn parse(args: &[OsString]) -> Result<Opts, String> {
             continue;
         }
 
+        if s == "--width" || s.starts_with("--width=") {
+            let value = match s.strip_prefix("--width=") {
+                Some(v) => v.to_string(),
+                None => it
+                    .next()
+                    .ok_or_else(|| "--width requires a column count".to_string())?
+                    .to_string_lossy()
+                    .to_string(),
+            };
+            opts.width = Some(
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|w| *w > 0)
+                    .ok_or_else(|| format!("invalid --width: {value}"))?,
+            );
+            continue;
+        }
+
+        if s == "--level" || s.starts_with("--level=") {
+            let value = match s.strip_prefix("--level=") {
+                Some(v) => v.to_string(),
+                None => it
+                    .next()
+                    .ok_or_else(|| "--level requires a depth".to_string())?
+                    .to_string_lossy()
+                    .to_string(),
+            };
+            opts.level = Some(
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|l| *l > 0)
+                    .ok_or_else(|| format!("invalid --level: {value}"))?,
+            );
+            continue;
+        }
+
         if s == "--theme" {
             let Some(name) = it.next() else {
                 return Err("--theme requires a theme name".to_string());
@@ -111,7 +198,16 @@ pub(super) fn parse(args: &[OsString]) -> Result<Opts, String> {
             "--icons" => opts.icons = true,
             "--no-icons" => opts.icons = false,
             "--file-type" => opts.file_type = true,
+            "--recurse" => opts.recurse = true,
+            "--tree" => opts.tree = true,
+            "--git-ignore" => opts.git_ignore = true,
+            "--git" => opts.git = true,
             "--author" => opts.show_author = true,
+            "--group" => opts.show_group = true,
+            "--inode" => opts.show_inode = true,
+            "--blocks" => opts.show_blocks = true,
+            "--links" => opts.show_links = true,
+            "--dereference" => opts.dereference = true,
             "--basic" => {
                 opts.basic = true;
                 opts.icons = false;
@@ -132,7 +228,14 @@ pub(super) fn parse(args: &[OsString]) -> Result<Opts, String> {
                         'S' => opts.sort = SortMode::Size,
                         'h' => opts.human = true,
                         '?' => return Err("__SHOW_HELP__".to_string()),
-                        '1' => {}
+                        '1' => opts.layout = Layout::Lines,
+                        'C' => opts.layout = Layout::Columns,
+                        'x' => opts.layout = Layout::Across,
+                        'R' => opts.recurse = true,
+                        'T' => opts.tree = true,
+                        'i' => opts.show_inode = true,
+                        's' => opts.show_blocks = true,
+                        'L' => opts.dereference = true,
                         _ => return Err(format!("unknown flag: -{ch}")),
                     }
                 }
@@ -148,6 +251,49 @@ pub(super) fn parse(args: &[OsString]) -> Result<Opts, String> {
     Ok(opts)
 }
 
+/// Applies `[ls]` from config.toml; CLI flags parsed afterwards win.
+fn apply_settings(opts: &mut Opts, settings: &Settings) -> Result<(), String> {
+    let flag = |key: &str| settings.get_bool("ls", key);
+    if let Some(v) = flag("all") {
+        opts.show_hidden = v;
+    }
+    if let Some(v) = flag("almost-all") {
+        opts.almost_all = v;
+        opts.show_hidden |= v;
+    }
+    if let Some(v) = flag("long") {
+        opts.long = v;
+    }
+    if let Some(v) = flag("headers") {
+        opts.headers = v;
+    }
+    if let Some(v) = flag("icons") {
+        opts.icons = v;
+    }
+    if let Some(v) = flag("reverse") {
+        opts.reverse = v;
+    }
+    if let Some(v) = flag("human-readable") {
+        opts.human = v;
+    }
+    if let Some(v) = flag("file-type") {
+        opts.file_type = v;
+    }
+    if let Some(v) = flag("author") {
+        opts.show_author = v;
+    }
+    if let Some(v) = settings.get_str("ls", "sort") {
+        opts.sort = parse_sort(v).map_err(|err| format!("config [ls] sort: {err}"))?;
+    }
+    // `DUSK_THEME` beats the file; only `--theme` beats the variable.
+    if std::env::var_os("DUSK_THEME").is_none()
+        && let Some(v) = settings.get_str("ls", "theme")
+    {
+        opts.theme = Some(v.to_string());
+    }
+    Ok(())
+}
+
 fn parse_sort(v: &str) -> Result<SortMode, String> {
     match v {
         "name" => Ok(SortMode::Name),
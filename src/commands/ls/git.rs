@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::core::process;
+
+/// Paths git ignores below one listed directory, for `--git-ignore`.
+pub(super) struct GitIgnored {
+    paths: HashSet<PathBuf>,
+}
+
+impl GitIgnored {
+    /// One `git ls-files` for `dir` and everything below it; `None` outside
+    /// a repository or when git is unavailable.
+    pub(super) fn load(dir: &Path) -> Option<Self> {
+        let abs = dir.canonicalize().ok()?;
+        if !abs.is_dir() || !abs.ancestors().any(|d| d.join(".git").exists()) {
+            return None;
+        }
+        // Ignored directories come back whole, as `dir/`; paths are relative
+        // to `dir`, so joining keeps them comparable with listed entries.
+        let output = process::run_capture_in(
+            &abs,
+            "git",
+            &[
+                "ls-files",
+                "-z",
+                "--others",
+                "--ignored",
+                "--exclude-standard",
+                "--directory",
+                "--",
+                ".",
+            ],
+        )
+        .ok()??;
+        let paths = output
+            .split('\0')
+            .filter(|line| !line.is_empty())
+            .map(|line| dir.join(line.trim_end_matches('/')))
+            .collect();
+        Some(Self { paths })
+    }
+
+    /// Whether `path`, or a directory it sits in, is ignored.
+    pub(super) fn contains(&self, path: &Path) -> bool {
+        path.ancestors().any(|p| self.paths.contains(p))
+    }
+}
@@ -3,19 +3,23 @@ use std::fs;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
+use crate::commands::git::status::GitStatus;
+use crate::core::settings;
 use crate::core::style::Style;
 use crate::core::theme;
 
 mod config;
+mod git;
 mod output;
 mod row;
 
 use config::{ColorMode, Opts, parse};
+use git::GitIgnored;
 use output::{print_rows, sort_rows};
-use row::build_row;
+use row::{EntryKind, Row, build_row};
 
 pub fn run(args: &[OsString]) -> Result<(), String> {
-    let opts = match parse(args) {
+    let opts = match parse(args, settings::current()) {
         Ok(o) => o,
         Err(e) if e == "__SHOW_HELP__" => {
             print_help();
@@ -40,42 +44,178 @@ pub fn run(args: &[OsString]) -> Result<(), String> {
         theme::plain()
     };
 
-    for (idx, path) in opts.paths.iter().enumerate() {
-        if opts.paths.len() > 1 {
-            if idx > 0 {
+    let mut listing = Listing {
+        opts: &opts,
+        style: &style,
+        theme,
+        ignored: None,
+        git: None,
+        printed: false,
+    };
+    for path in &opts.paths {
+        listing.ignored = if opts.git_ignore {
+            GitIgnored::load(path)
+        } else {
+            None
+        };
+        if opts.tree {
+            listing.tree(path)?;
+        } else {
+            listing.list(path, 1)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// State shared while printing every path given on the command line.
+struct Listing<'a> {
+    opts: &'a Opts,
+    style: &'a Style,
+    theme: theme::Theme,
+    /// `--git-ignore` paths for the path being listed.
+    ignored: Option<GitIgnored>,
+    /// `--git` statuses for the directory (or tree) being listed.
+    git: Option<GitStatus>,
+    /// Whether a listing was printed yet, to separate the next one.
+    printed: bool,
+}
+
+impl Listing<'_> {
+    /// One directory (or file), under a `path:` header when several paths
+    /// are listed or `-R` is on, then its subdirectories with `-R`.
+    fn list(&mut self, path: &Path, depth: usize) -> Result<(), String> {
+        let opts = self.opts;
+        if opts.paths.len() > 1 || opts.recurse {
+            if self.printed {
                 println!();
             }
             println!(
                 "{}",
-                style.paint(theme.title, format!("{}:", path.display()))
+                self.style
+                    .paint(self.theme.title, format!("{}:", path.display()))
             );
         }
+        self.printed = true;
+
+        self.git = self.load_git(path)?;
+        let rows = self.rows(path, true)?;
+        print_rows(&rows, opts, self.style, self.theme);
 
-        let entries = collect_entries(path, &opts)?;
+        if opts.recurse && opts.level.is_none_or(|level| depth < level) {
+            for row in rows.iter().filter(|row| is_subdir(row)) {
+                self.list(&row.path, depth + 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `--tree`: the path and everything below it as one listing, so long
+    /// columns line up across levels.
+    fn tree(&mut self, root: &Path) -> Result<(), String> {
+        if self.printed {
+            println!();
+        }
+        self.printed = true;
+        self.git = self.load_git(root)?;
+        let mut rows = vec![self.row(root)?];
+        if root.is_dir() {
+            self.tree_rows(root, "", 1, &mut rows)?;
+        }
+        print_rows(&rows, self.opts, self.style, self.theme);
+        Ok(())
+    }
+
+    fn tree_rows(
+        &self,
+        dir: &Path,
+        prefix: &str,
+        depth: usize,
+        out: &mut Vec<Row>,
+    ) -> Result<(), String> {
+        if self.opts.level.is_some_and(|level| depth > level) {
+            return Ok(());
+        }
+        let rows = self.rows(dir, false)?;
+        let total = rows.len();
+        for (idx, mut row) in rows.into_iter().enumerate() {
+            let last = idx + 1 == total;
+            row.tree_prefix = format!("{prefix}{}", if last { "└── " } else { "├── " });
+            let child = is_subdir(&row).then(|| row.path.clone());
+            out.push(row);
+            if let Some(child) = child {
+                let next = format!("{prefix}{}", if last { "    " } else { "│   " });
+                self.tree_rows(&child, &next, depth + 1, out)?;
+            }
+        }
+        Ok(())
+    }
 
+    /// Sorted rows for the entries of `path`; `implied` adds `.` and `..`
+    /// under `-a`.
+    fn rows(&self, path: &Path, implied: bool) -> Result<Vec<Row>, String> {
+        let entries = collect_entries(path, self.opts, implied)?;
         let mut rows = Vec::new();
         for entry in &entries {
-            match build_row(entry, &opts, &style) {
+            if self
+                .ignored
+                .as_ref()
+                .is_some_and(|ignored| ignored.contains(entry))
+            {
+                continue;
+            }
+            match self.row(entry) {
                 Ok(row) => rows.push(row),
                 Err(err) if err.contains("Permission denied") => continue,
                 Err(err) => return Err(err),
             }
         }
+        sort_rows(&mut rows, self.opts.sort, self.opts.reverse);
+        Ok(rows)
+    }
 
-        sort_rows(&mut rows, opts.sort, opts.reverse);
-        print_rows(&rows, &opts, &style, theme);
+    fn row(&self, path: &Path) -> Result<Row, String> {
+        let mut row = build_row(path, self.opts, self.style)?;
+        // `..` lies outside the listed directory, so git says nothing on it.
+        if row.sort_name != ".." {
+            row.git = self
+                .git
+                .as_ref()
+                .and_then(|git| git.mark(path, row.kind == EntryKind::Dir));
+        }
+        Ok(row)
     }
 
-    Ok(())
+    /// One `git status` per listed directory, only for `-l --git`. Ignored
+    /// entries are marked unless `--git-ignore` hides them anyway.
+    fn load_git(&self, dir: &Path) -> Result<Option<GitStatus>, String> {
+        if self.opts.git && self.opts.long && dir.is_dir() {
+            GitStatus::load(dir, !self.opts.git_ignore, "dusk ls --git")
+        } else {
+            Ok(None)
+        }
+    }
 }
 
-fn collect_entries(path: &Path, opts: &Opts) -> Result<Vec<PathBuf>, String> {
-    if path.is_file() {
+/// A real directory to descend into: not a symlink (even with `-L`), `.`
+/// or `..`.
+fn is_subdir(row: &Row) -> bool {
+    row.kind == EntryKind::Dir
+        && row.link.is_none()
+        && !matches!(row.sort_name.as_str(), "." | "..")
+}
+
+fn collect_entries(path: &Path, opts: &Opts, implied: bool) -> Result<Vec<PathBuf>, String> {
+    // Like `ls -l`, a symlink named on the command line is shown rather
+    // than opened, unless `-L` asks for the target.
+    let link_itself = opts.long && !opts.dereference && path.is_symlink();
+    if !path.is_dir() || link_itself {
+        // Files, devices, pipes and sockets list as themselves.
+        if fs::symlink_metadata(path).is_err() {
+            return Err(format!("no such file or directory: {}", path.display()));
+        }
         return Ok(vec![path.to_path_buf()]);
     }
-    if !path.is_dir() {
-        return Err(format!("no such file or directory: {}", path.display()));
-    }
 
     let read_dir = match fs::read_dir(path) {
         Ok(rd) => rd,
@@ -98,7 +238,7 @@ fn collect_entries(path: &Path, opts: &Opts) -> Result<Vec<PathBuf>, String> {
         })
         .collect::<Vec<_>>();
 
-    if opts.show_hidden && !opts.almost_all {
+    if implied && opts.show_hidden && !opts.almost_all {
         // GNU ls -a behavior: include implied . and .. first
         let mut with_implied = vec![path.join("."), path.join("..")];
         with_implied.append(&mut items);
@@ -151,6 +291,55 @@ fn print_help() {
         opt("--reverse"),
         desc("Reverse sort order")
     );
+    println!(
+        "  {} {}",
+        opt("-C"),
+        desc("Names in columns, top to bottom (default on a terminal)")
+    );
+    println!(
+        "  {} {}",
+        opt("-x"),
+        desc("Names in columns, left to right")
+    );
+    println!(
+        "  {} {}",
+        opt("-1"),
+        desc("One name per line (default when piped)")
+    );
+    println!(
+        "  {} {} {}",
+        opt("--width"),
+        arg("<cols>"),
+        desc("Grid width (default COLUMNS, then the terminal)")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-R"),
+        opt("--recurse"),
+        desc("List subdirectories recursively, each under a header")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-T"),
+        opt("--tree"),
+        desc("Tree view; with -l, columns line up beside the branches")
+    );
+    println!(
+        "  {} {} {}",
+        opt("--level"),
+        arg("<n>"),
+        desc("Limit -R / --tree depth")
+    );
+    println!(
+        "  {} {}",
+        opt("--git"),
+        desc("With -l, show index/work-tree git status (rolled up on directories)")
+    );
+    println!(
+        "  {} {}",
+        opt("--git-ignore"),
+        desc("Hide files git ignores (.gitignore, info/exclude, core.excludesFile)")
+    );
     println!("  {} {}", opt("-t"), desc("Sort by modification time"));
     println!("  {} {}", opt("-S"), desc("Sort by file size"));
     println!(
@@ -169,6 +358,34 @@ fn print_help() {
         opt("--author"),
         desc("With -l, print author column")
     );
+    println!(
+        "  {} {}",
+        opt("--group"),
+        desc("With -l, print group column")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-i"),
+        opt("--inode"),
+        desc("With -l, print inode numbers")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-s"),
+        opt("--blocks"),
+        desc("With -l, print allocated 1K blocks (human-readable with -h)")
+    );
+    println!(
+        "  {} {}",
+        opt("--links"),
+        desc("With -l, print hard-link counts")
+    );
+    println!(
+        "  {}, {} {}",
+        opt("-L"),
+        opt("--dereference"),
+        desc("Show what symlinks point to instead of the links")
+    );
     println!(
         "  {} {} {}",
         opt("--sort"),
@@ -1,9 +1,14 @@
 use std::cmp::Ordering;
+use std::env;
 
+use atty::Stream;
+use unicode_width::UnicodeWidthStr;
+
+use crate::commands::git::status::Mark;
 use crate::core::style::Style;
 use crate::core::theme;
 
-use super::config::{Opts, SortMode};
+use super::config::{Layout, Opts, SortMode};
 use super::row::{EntryKind, Row};
 
 pub(super) fn sort_rows(rows: &mut [Row], mode: SortMode, reverse: bool) {
@@ -34,67 +39,272 @@ fn sort_cmp(a: &Row, b: &Row, mode: SortMode) -> Ordering {
     }
 }
 
+/// One metadata column of the long format.
+struct Column {
+    header: &'static str,
+    value: fn(&Row) -> &str,
+    /// Numbers are right-aligned.
+    right: bool,
+    color: &'static str,
+}
+
+/// The long-format columns `opts` asks for, in `ls -l` order.
+fn columns(opts: &Opts, theme: theme::Theme) -> Vec<Column> {
+    let column = |header, value, right, color| Column {
+        header,
+        value,
+        right,
+        color,
+    };
+    let mut cols = Vec::new();
+    if opts.show_inode {
+        cols.push(column("INODE", |r: &Row| &r.inode, true, theme.number));
+    }
+    if opts.show_blocks {
+        cols.push(column("BLOCKS", |r: &Row| &r.blocks, true, theme.size));
+    }
+    cols.push(column("PERMS", |r: &Row| &r.perms, false, theme.info));
+    if opts.show_links {
+        cols.push(column("LINKS", |r: &Row| &r.links, true, theme.number));
+    }
+    cols.push(column("OWNER", |r: &Row| &r.owner, false, theme.subtle));
+    if opts.show_group {
+        cols.push(column("GROUP", |r: &Row| &r.group, false, theme.subtle));
+    }
+    if opts.show_author {
+        cols.push(column("AUTHOR", |r: &Row| &r.author, false, theme.subtle));
+    }
+    cols.push(column("SIZE", |r: &Row| &r.size, true, theme.size));
+    cols.push(column("MODIFIED", |r: &Row| &r.modified, false, theme.date));
+    cols
+}
+
 pub(super) fn print_rows(rows: &[Row], opts: &Opts, style: &Style, theme: theme::Theme) {
-    let (perm_w, owner_w, author_w, size_w, mod_w) =
-        rows.iter()
-            .fold((0usize, 0usize, 0usize, 0usize, 0usize), |acc, r| {
-                (
-                    acc.0.max(r.perms.len()),
-                    acc.1.max(r.owner.len()),
-                    acc.2.max(r.author.len()),
-                    acc.3.max(r.size.len()),
-                    acc.4.max(r.modified.len()),
-                )
-            });
+    if !opts.long {
+        if opts.headers {
+            println!("{}", style.paint(theme.title, "NAME"));
+        }
+        print_short(rows, opts, style, theme);
+        return;
+    }
 
-    if opts.headers {
-        if opts.long {
-            let mut cols = vec![
-                format!("{:perm_w$}", "PERMS", perm_w = perm_w),
-                format!("{:owner_w$}", "OWNER", owner_w = owner_w),
-            ];
-            if opts.show_author {
-                cols.push(format!("{:author_w$}", "AUTHOR", author_w = author_w));
-            }
-            cols.push(format!("{:>size_w$}", "SIZE", size_w = size_w));
-            cols.push(format!("{:mod_w$}", "MODIFIED", mod_w = mod_w));
-            cols.push("NAME".to_string());
-            println!("{}", style.paint(theme.title, cols.join(" ")));
+    let cols = columns(opts, theme);
+    let widths = cols
+        .iter()
+        .map(|col| {
+            let header = if opts.headers { col.header.len() } else { 0 };
+            rows.iter()
+                .map(|r| (col.value)(r).len())
+                .fold(header, usize::max)
+        })
+        .collect::<Vec<_>>();
+    let cell = |col: &Column, width: usize, text: &str| {
+        if col.right {
+            format!("{text:>width$}")
         } else {
-            println!("{}", style.paint(theme.title, "NAME"));
+            format!("{text:width$}")
         }
+    };
+    // No column outside a repository.
+    let git = opts.git && rows.iter().any(|r| r.git.is_some());
+
+    if opts.headers {
+        let mut header = cols
+            .iter()
+            .zip(&widths)
+            .map(|(col, &width)| cell(col, width, col.header))
+            .collect::<Vec<_>>();
+        if git {
+            header.push("GIT".to_string());
+        }
+        header.push("NAME".to_string());
+        println!("{}", style.paint(theme.title, header.join(" ")));
     }
 
     for row in rows {
-        let body_color = match row.kind {
-            EntryKind::Dir => theme.accent,
-            EntryKind::Exec => theme.ok,
-            EntryKind::Link => theme.warn,
-            EntryKind::File => theme.info,
+        let mut left_parts = cols
+            .iter()
+            .zip(&widths)
+            .map(|(col, &width)| style.paint(col.color, cell(col, width, (col.value)(row))))
+            .collect::<Vec<_>>();
+        if git {
+            let pad = if opts.headers { " " } else { "" };
+            left_parts.push(format!("{}{pad}", git_mark(row.git, style, theme)));
+        }
+
+        let left = left_parts.join(" ");
+        println!(
+            "{left} {}{}{}",
+            branches(row, style, theme),
+            style.paint(kind_color(row.kind, theme), &row.display),
+            link_target(row, style, theme)
+        );
+    }
+}
+
+/// ` -> target` for a symlink, colored by what it points to; a dangling
+/// link's target is painted in the warn color. Empty for everything else,
+/// including links `-L` already resolved.
+fn link_target(row: &Row, style: &Style, theme: theme::Theme) -> String {
+    let Some(link) = row.link.as_ref().filter(|_| row.kind == EntryKind::Link) else {
+        return String::new();
+    };
+    let color = link.kind.map_or(theme.warn, |kind| kind_color(kind, theme));
+    format!(
+        " {} {}",
+        style.paint(theme.subtle, "->"),
+        style.paint(color, link.path.display().to_string())
+    )
+}
+
+/// The two `--git` letters, new in the ok color, changes in warn and
+/// unchanged or ignored in subtle; blank for entries git has no say on.
+fn git_mark(mark: Option<Mark>, style: &Style, theme: theme::Theme) -> String {
+    let Some(mark) = mark else {
+        return "  ".to_string();
+    };
+    let paint = |c: char| {
+        let color = match c {
+            'N' => theme.ok,
+            '-' | 'I' => theme.subtle,
+            _ => theme.warn,
         };
+        style.paint(color, c.to_string())
+    };
+    format!("{}{}", paint(mark.staged), paint(mark.unstaged))
+}
 
-        if !opts.long {
-            println!("{}", style.paint(body_color, &row.display));
-            continue;
+fn kind_color(kind: EntryKind, theme: theme::Theme) -> &'static str {
+    match kind {
+        EntryKind::Dir => theme.directory,
+        EntryKind::Exec => theme.executable,
+        EntryKind::Link => theme.symlink,
+        EntryKind::File => theme.file,
+    }
+}
+
+fn branches(row: &Row, style: &Style, theme: theme::Theme) -> String {
+    if row.tree_prefix.is_empty() {
+        String::new()
+    } else {
+        style.paint(theme.subtle, &row.tree_prefix)
+    }
+}
+
+/// Spaces between grid columns.
+const GRID_GAP: usize = 2;
+
+/// Names only: a grid like `ls -C`/`-x` on a terminal, one per line when
+/// piped or with `-1`.
+fn print_short(rows: &[Row], opts: &Opts, style: &Style, theme: theme::Theme) {
+    let across = match opts.layout {
+        _ if opts.tree => None,
+        Layout::Lines => None,
+        Layout::Columns => Some(false),
+        Layout::Across => Some(true),
+        Layout::Auto => atty::is(Stream::Stdout).then_some(false),
+    };
+    let widths = rows
+        .iter()
+        .map(|row| UnicodeWidthStr::width(row.display.as_str()))
+        .collect::<Vec<_>>();
+    let Some((across, col_widths)) =
+        across.and_then(|across| Some((across, fit_grid(&widths, grid_width(opts), across)?)))
+    else {
+        for row in rows {
+            println!(
+                "{}{}",
+                branches(row, style, theme),
+                style.paint(kind_color(row.kind, theme), &row.display)
+            );
+        }
+        return;
+    };
+
+    let cols = col_widths.len();
+    let lines = rows.len().div_ceil(cols);
+    let at = |line: usize, col: usize| {
+        let idx = if across {
+            line * cols + col
+        } else {
+            col * lines + line
+        };
+        (idx < rows.len()).then_some(idx)
+    };
+    for line in 0..lines {
+        let mut out = String::new();
+        for (col, col_width) in col_widths.iter().enumerate() {
+            let Some(idx) = at(line, col) else {
+                break;
+            };
+            let row = &rows[idx];
+            out.push_str(&style.paint(kind_color(row.kind, theme), &row.display));
+            if col + 1 < cols && at(line, col + 1).is_some() {
+                out.push_str(&" ".repeat(col_width - widths[idx] + GRID_GAP));
+            }
         }
+        println!("{out}");
+    }
+}
 
-        let perms = format!("{:perm_w$}", row.perms, perm_w = perm_w);
-        let owner = format!("{:owner_w$}", row.owner, owner_w = owner_w);
-        let author = format!("{:author_w$}", row.author, author_w = author_w);
-        let size = format!("{:>size_w$}", row.size, size_w = size_w);
-        let modified = format!("{:mod_w$}", row.modified, mod_w = mod_w);
-
-        let mut left_parts = vec![
-            style.paint(theme.info, perms),
-            style.paint(theme.subtle, owner),
-        ];
-        if opts.show_author {
-            left_parts.push(style.paint(theme.subtle, author));
+/// `--width`, then `COLUMNS`, then the terminal's width.
+fn grid_width(opts: &Opts) -> usize {
+    opts.width
+        .or_else(|| {
+            env::var("COLUMNS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|w| *w > 0)
+        })
+        .or_else(|| {
+            crossterm::terminal::size()
+                .ok()
+                .map(|(w, _)| usize::from(w))
+        })
+        .unwrap_or(80)
+}
+
+/// Column widths of the grid with the most columns that fits in `width`,
+/// filled top to bottom (or left to right when `across`). `None` when only
+/// one column fits.
+fn fit_grid(widths: &[usize], width: usize, across: bool) -> Option<Vec<usize>> {
+    // Every column is at least as wide as the shortest name, which caps the
+    // count worth trying (as GNU ls does) and keeps huge listings linear.
+    let narrowest = widths.iter().copied().min()?;
+    let most = widths.len().min(width / (narrowest + GRID_GAP) + 1);
+    for cols in (2..=most).rev() {
+        let lines = widths.len().div_ceil(cols);
+        // Top-to-bottom fill can leave the last column empty (4 names in 3
+        // columns take 2 lines); that layout is the same as one fewer column.
+        if !across && (cols - 1) * lines >= widths.len() {
+            continue;
         }
-        left_parts.push(style.paint(theme.accent, size));
-        left_parts.push(style.paint(theme.number, modified));
+        let mut col_widths = vec![0; cols];
+        for (idx, w) in widths.iter().enumerate() {
+            let col = if across { idx % cols } else { idx / lines };
+            col_widths[col] = col_widths[col].max(*w);
+        }
+        if col_widths.iter().sum::<usize>() + GRID_GAP * (cols - 1) <= width {
+            return Some(col_widths);
+        }
+    }
+    None
+}
 
-        let left = left_parts.join(" ");
-        println!("{left} {}", style.paint(body_color, &row.display));
+#[cfg(test)]
+mod tests {
+    use super::fit_grid;
+
+    #[test]
+    fn fits_the_most_columns_into_the_width() {
+        let widths = [5, 1, 9, 3, 4];
+        // Down: [5, 1] [9, 3] [4] needs 5 + 2 + 9 + 2 + 4 = 22.
+        assert_eq!(fit_grid(&widths, 22, false), Some(vec![5, 9, 4]));
+        // One short: two columns [5, 1, 9] [3, 4].
+        assert_eq!(fit_grid(&widths, 21, false), Some(vec![9, 4]));
+        // Across: rows [5, 1, 9] [3, 4] give columns 5, 4, 9.
+        assert_eq!(fit_grid(&widths, 22, true), Some(vec![5, 4, 9]));
+        assert_eq!(fit_grid(&widths, 8, false), None);
+        assert_eq!(fit_grid(&[3], 80, false), None);
     }
 }
@@ -1,17 +1,33 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Local};
 
+use crate::commands::git::status::Mark;
 use crate::core::devicons;
+use crate::core::format::human_size;
 use crate::core::style::Style;
 
 use super::config::Opts;
 
 #[derive(Clone)]
 pub(super) struct Row {
+    pub path: PathBuf,
+    /// `--tree` branches drawn before the name.
+    pub tree_prefix: String,
+    /// `--git` status, rolled up for directories.
+    pub git: Option<Mark>,
+    /// `-i/--inode`
+    pub inode: String,
+    /// `-s/--blocks`: allocated size, in 1024-byte blocks like `ls -s`.
+    pub blocks: String,
+    /// Type character and permission bits, as `ls -l` prints them.
     pub perms: String,
+    /// `--links`: hard-link count.
+    pub links: String,
     pub owner: String,
+    /// `--group`
+    pub group: String,
     pub author: String,
     pub size: String,
     pub size_bytes: u64,
@@ -19,6 +35,8 @@ pub(super) struct Row {
     pub mtime_epoch: i128,
     pub display: String,
     pub kind: EntryKind,
+    /// Set for symlinks, even when `-L` shows the target's metadata.
+    pub link: Option<LinkTarget>,
     pub sort_name: String,
     pub sort_ext: String,
 }
@@ -31,9 +49,32 @@ pub(super) enum EntryKind {
     File,
 }
 
+/// Where a symlink points.
+#[derive(Clone)]
+pub(super) struct LinkTarget {
+    /// The target as stored in the link, not resolved.
+    pub path: PathBuf,
+    /// The kind of what it resolves to; `None` for a dangling link.
+    pub kind: Option<EntryKind>,
+}
+
 pub(super) fn build_row(path: &Path, opts: &Opts, style: &Style) -> Result<Row, String> {
-    let md = fs::symlink_metadata(path)
+    let mut md = fs::symlink_metadata(path)
         .map_err(|err| format!("failed metadata {}: {err}", path.display()))?;
+    let mut link = None;
+    if md.file_type().is_symlink() {
+        let target = fs::metadata(path).ok();
+        link = Some(LinkTarget {
+            path: fs::read_link(path).unwrap_or_default(),
+            kind: target.as_ref().map(entry_kind),
+        });
+        // `-L` describes the target; dangling links stay links.
+        if opts.dereference
+            && let Some(target) = target
+        {
+            md = target;
+        }
+    }
     let name = special_name(path).unwrap_or_else(|| {
         path.file_name()
             .and_then(|n| n.to_str())
@@ -41,15 +82,7 @@ pub(super) fn build_row(path: &Path, opts: &Opts, style: &Style) -> Result<Row,
             .to_string()
     });
 
-    let kind = if md.file_type().is_symlink() {
-        EntryKind::Link
-    } else if md.is_dir() {
-        EntryKind::Dir
-    } else if is_executable(&md) {
-        EntryKind::Exec
-    } else {
-        EntryKind::File
-    };
+    let kind = entry_kind(&md);
 
     let icon = if opts.icons {
         match kind {
@@ -73,6 +106,13 @@ pub(super) fn build_row(path: &Path, opts: &Opts, style: &Style) -> Result<Row,
 
     let perms = permissions(&md);
     let owner = owner_name(&md);
+    let group = group_name(&md);
+    let (inode, links, blocks) = counts(&md);
+    let blocks = if opts.human {
+        human_size(blocks * 512)
+    } else {
+        blocks.div_ceil(2).to_string()
+    };
     let author = author_name(&md);
     let size = if opts.human {
         human_size(md.len())
@@ -101,8 +141,15 @@ pub(super) fn build_row(path: &Path, opts: &Opts, style: &Style) -> Result<Row,
         .to_ascii_lowercase();
 
     Ok(Row {
+        path: path.to_path_buf(),
+        tree_prefix: String::new(),
+        git: None,
+        inode: inode.to_string(),
+        blocks,
         perms,
+        links: links.to_string(),
         owner,
+        group,
         author,
         size,
         size_bytes: md.len(),
@@ -110,11 +157,24 @@ pub(super) fn build_row(path: &Path, opts: &Opts, style: &Style) -> Result<Row,
         mtime_epoch,
         display,
         kind,
+        link,
         sort_name: name.to_ascii_lowercase(),
         sort_ext,
     })
 }
 
+fn entry_kind(md: &fs::Metadata) -> EntryKind {
+    if md.file_type().is_symlink() {
+        EntryKind::Link
+    } else if md.is_dir() {
+        EntryKind::Dir
+    } else if is_executable(md) {
+        EntryKind::Exec
+    } else {
+        EntryKind::File
+    }
+}
+
 fn special_name(path: &Path) -> Option<String> {
     let s = path.to_string_lossy();
     if s.ends_with("/.") || s == "." {
@@ -130,54 +190,68 @@ pub(super) fn permissions(md: &fs::Metadata) -> String {
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mode = md.permissions().mode();
-        let mut out = String::new();
-        let flags = [
-            0o400, 0o200, 0o100, 0o040, 0o020, 0o010, 0o004, 0o002, 0o001,
-        ];
-        for (i, bit) in flags.iter().enumerate() {
-            let c = match i % 3 {
-                0 => 'r',
-                1 => 'w',
-                _ => 'x',
-            };
-            out.push(if mode & bit != 0 { c } else { '-' });
-        }
-        out
+        mode_string(md.permissions().mode())
     }
     #[cfg(not(unix))]
     {
-        "---------".to_string()
+        let kind = if md.is_dir() { 'd' } else { '-' };
+        format!("{kind}---------")
     }
 }
 
-pub(super) fn is_executable(md: &fs::Metadata) -> bool {
+/// `st_mode` the way `ls -l` prints it: the type character, then `rwx` for
+/// user, group and others, with setuid/setgid/sticky shown as `s`/`t` over
+/// the execute bit (`S`/`T` when it is not set).
+#[cfg(unix)]
+fn mode_string(mode: u32) -> String {
+    // The `S_IFMT` type bits, spelled out since `mode_t` differs by platform.
+    let kind = match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '-',
+    };
+    let mut out = String::from(kind);
+    // (shift of the rwx triple, special bit, its letter)
+    for (shift, special, letter) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = mode >> shift;
+        out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        out.push(match (bits & 0o1 != 0, mode & special != 0) {
+            (true, true) => letter,
+            (false, true) => letter.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+/// Inode, hard-link count and 512-byte blocks allocated.
+fn counts(md: &fs::Metadata) -> (u64, u64, u64) {
     #[cfg(unix)]
     {
-        use std::os::unix::fs::PermissionsExt;
-        md.permissions().mode() & 0o111 != 0
+        use std::os::unix::fs::MetadataExt;
+        (md.ino(), md.nlink(), md.blocks())
     }
     #[cfg(not(unix))]
     {
-        false
+        (0, 1, md.len().div_ceil(512))
     }
 }
 
-pub(super) fn human_size(size: u64) -> String {
-    const UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "P"];
-    let mut value = size as f64;
-    let mut idx = 0usize;
-    while value >= 1024.0 && idx + 1 < UNITS.len() {
-        value /= 1024.0;
-        idx += 1;
+pub(super) fn is_executable(md: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        md.permissions().mode() & 0o111 != 0
     }
-
-    if idx == 0 {
-        format!("{size}B")
-    } else if value >= 10.0 {
-        format!("{value:.1}{}", UNITS[idx])
-    } else {
-        format!("{value:.2}{}", UNITS[idx])
+    #[cfg(not(unix))]
+    {
+        false
     }
 }
 
@@ -204,7 +278,50 @@ fn owner_name(md: &fs::Metadata) -> String {
     }
 }
 
+fn group_name(md: &fs::Metadata) -> String {
+    #[cfg(unix)]
+    {
+        use std::ffi::CStr;
+        use std::os::unix::fs::MetadataExt;
+
+        let gid = md.gid();
+        unsafe {
+            let grp = libc::getgrgid(gid);
+            if grp.is_null() {
+                return gid.to_string();
+            }
+            CStr::from_ptr((*grp).gr_name)
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        "-".to_string()
+    }
+}
+
 fn author_name(md: &fs::Metadata) -> String {
     // Portable fallback: same as owner.
     owner_name(md)
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::mode_string;
+
+    #[test]
+    fn renders_type_and_special_bits_like_ls() {
+        assert_eq!(mode_string(0o100644), "-rw-r--r--");
+        assert_eq!(mode_string(0o040755), "drwxr-xr-x");
+        assert_eq!(mode_string(0o120777), "lrwxrwxrwx");
+        assert_eq!(mode_string(0o020620), "crw--w----");
+        assert_eq!(mode_string(0o060660), "brw-rw----");
+        assert_eq!(mode_string(0o010600), "prw-------");
+        assert_eq!(mode_string(0o140755), "srwxr-xr-x");
+        assert_eq!(mode_string(0o104755), "-rwsr-xr-x");
+        assert_eq!(mode_string(0o102644), "-rw-r-Sr--");
+        assert_eq!(mode_string(0o041777), "drwxrwxrwt");
+        assert_eq!(mode_string(0o041776), "drwxrwxrwT");
+    }
+}
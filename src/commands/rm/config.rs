@@ -1,7 +1,9 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone)]
+use crate::core::settings::Settings;
+
+#[derive(Debug, Clone, Default)]
 pub struct Opts {
     pub force: bool,
     pub interactive: bool,
@@ -14,24 +16,17 @@ pub struct Opts {
     pub paths: Vec<PathBuf>,
 }
 
-impl Default for Opts {
-    fn default() -> Self {
-        Self {
-            force: false,
-            interactive: false,
-            recursive: false,
-            verbose: false,
-            permanent: false,
-            trash_tui: false,
-            restore: None,
-            empty_trash: false,
-            paths: Vec::new(),
-        }
-    }
-}
-
-pub fn parse(args: &[OsString]) -> Result<Opts, String> {
+pub fn parse(args: &[OsString], settings: &Settings) -> Result<Opts, String> {
     let mut opts = Opts::default();
+    if let Some(v) = settings.get_bool("rm", "permanent") {
+        opts.permanent = v;
+    }
+    if let Some(v) = settings.get_bool("rm", "interactive") {
+        opts.interactive = v;
+    }
+    if let Some(v) = settings.get_bool("rm", "verbose") {
+        opts.verbose = v;
+    }
     let mut it = args.iter().peekable();
 
     while let Some(arg) = it.next() {
@@ -1,6 +1,8 @@
 use std::ffi::OsString;
 use std::io::{self, Write};
 
+use crate::core::settings;
+
 mod config;
 mod help;
 mod ops;
@@ -8,7 +10,7 @@ mod trash;
 mod tui;
 
 pub fn run(args: &[OsString]) -> Result<(), String> {
-    let opts = match config::parse(args) {
+    let opts = match config::parse(args, settings::current()) {
         Ok(v) => v,
         Err(e) if e == "__SHOW_HELP__" => {
             help::print_help();
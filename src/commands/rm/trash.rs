@@ -44,10 +44,10 @@ pub fn trash_root() -> Result<PathBuf, String> {
         }
     }
 
-    if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
-        if !xdg.trim().is_empty() {
-            return Ok(PathBuf::from(xdg).join("Trash").join("dusk"));
-        }
+    if let Ok(xdg) = std::env::var("XDG_DATA_HOME")
+        && !xdg.trim().is_empty()
+    {
+        return Ok(PathBuf::from(xdg).join("Trash").join("dusk"));
     }
 
     if let Some(home) = home_dir() {
@@ -58,10 +58,10 @@ pub fn trash_root() -> Result<PathBuf, String> {
 }
 
 fn home_dir() -> Option<PathBuf> {
-    if let Ok(home) = std::env::var("HOME") {
-        if !home.trim().is_empty() {
-            return Some(PathBuf::from(home));
-        }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.trim().is_empty()
+    {
+        return Some(PathBuf::from(home));
     }
     None
 }
@@ -1,25 +1,42 @@
 use crate::core::style::Style;
-use crate::core::theme::THEMES;
+use crate::core::theme;
 
 pub fn list() {
     let style = Style::for_stdout();
     println!("Available themes:");
-    for theme in THEMES {
+    let name_w = theme::all().iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for theme in theme::all() {
+        let origin = if theme::is_user_defined(theme.name) {
+            " (user)"
+        } else {
+            ""
+        };
         if style.color {
+            let label = format!("{}{origin}", theme.name);
+            let swatch = [
+                (theme.accent, "●"),
+                (theme.ok, "●"),
+                (theme.warn, "●"),
+                (theme.directory, "dir/"),
+                (theme.executable, "exec*"),
+                (theme.symlink, "link@"),
+                (theme.size, "4.0K"),
+                (theme.date, "2024-01-01"),
+                (theme.diff_add, "+add"),
+                (theme.diff_del, "-del"),
+            ]
+            .iter()
+            .map(|(color, text)| format!("{color}{text}{}", theme.reset))
+            .collect::<Vec<_>>()
+            .join(" ");
             println!(
-                "  - {}{}{}  {}●{} {}●{} {}●{}",
-                theme.accent,
-                theme.name,
-                theme.reset,
+                "  - {}{label:<width$}{}  {swatch}",
                 theme.accent,
                 theme.reset,
-                theme.ok,
-                theme.reset,
-                theme.warn,
-                theme.reset
+                width = name_w + " (user)".len()
             );
         } else {
-            println!("  - {}", theme.name);
+            println!("  - {}{}", theme.name, origin);
         }
     }
 }
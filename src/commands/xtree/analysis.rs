@@ -4,82 +4,152 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use super::config::Config;
-use super::ignore::IgnoreMatcher;
-use super::theme::{Theme, format_size};
+use super::loc::{self, LineCounts};
+use super::model::TreeNode;
+use super::render::Renderer;
+use super::walk::Tree;
+use crate::core::format::human_size;
+use crate::core::theme::Theme;
 
 #[derive(Default)]
 pub struct Stats {
-    pub lang_stats: HashMap<String, usize>,
+    /// Files and line counts per language, see [`loc::language_for`].
+    pub languages: HashMap<&'static str, LanguageStats>,
+    /// Total lines per extension, for `--loc -c <ext>`.
     pub loc_stats: HashMap<String, u64>,
     pub total_size: u64,
-    pub total_loc: u64,
+    pub total_loc: LineCounts,
     pub dir_count: usize,
     pub file_count: usize,
 }
 
-pub fn collect_stats(root: &Path, cfg: &Config, ignore: &IgnoreMatcher) -> Result<Stats, String> {
-    let mut stats = Stats::default();
-    let walker = walkdir::WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.is_dir() {
+#[derive(Clone, Copy, Default)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub loc: LineCounts,
+}
+
+pub fn collect_stats(root: &TreeNode) -> Stats {
+    let mut stats = Stats {
+        dir_count: 1,
+        ..Stats::default()
+    };
+    for (_, node) in root.descendants() {
+        if node.is_dir() {
             stats.dir_count += 1;
             continue;
         }
-        if !path.is_file() {
+        if !node.is_file() {
             continue;
         }
         stats.file_count += 1;
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("no-ext")
-            .to_ascii_lowercase();
-        *stats.lang_stats.entry(ext).or_insert(0) += 1;
-        let ext_key = path
+        let ext = node
+            .path
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("no-ext")
             .to_ascii_lowercase();
-        let loc = count_lines(path);
-        *stats.loc_stats.entry(ext_key).or_insert(0) += loc;
-        stats.total_loc += loc;
-
-        if let Ok(md) = fs::metadata(path) {
-            stats.total_size += md.len();
-        }
+        *stats.loc_stats.entry(ext).or_insert(0) += node.loc.total();
+        let name = loc::language_for(&node.path).map_or(loc::OTHER, |lang| lang.title);
+        let lang = stats.languages.entry(name).or_default();
+        lang.files += 1;
+        lang.loc += node.loc;
+        stats.total_loc += node.loc;
+        stats.total_size += node.size;
     }
-    Ok(stats)
+    stats
 }
 
 pub fn print_stats(stats: &Stats, theme: &Theme) {
     println!();
-    println!("{}=== Language Statistics ==={}", theme.header, theme.reset);
-    println!(
-        "{}Total LOC: {}{}",
-        theme.meta, stats.total_loc, theme.reset
-    );
-    println!(
-        "{}Total Files: {}{}",
-        theme.meta, stats.file_count, theme.reset
-    );
-    println!();
-    let mut v = stats
-        .lang_stats
+    for line in stats_lines(stats, theme) {
+        println!("{line}");
+    }
+}
+
+/// The `--stats` report, one line per entry; also shown by the TUI.
+pub fn stats_lines(stats: &Stats, theme: &Theme) -> Vec<String> {
+    let mut out = vec![
+        format!("{}=== Language Statistics ==={}", theme.title, theme.reset),
+        total_loc_line(stats, theme),
+        format!(
+            "{}Total Files: {}{}",
+            theme.subtle, stats.file_count, theme.reset
+        ),
+        String::new(),
+    ];
+    out.extend(loc_table(stats, theme));
+    out
+}
+
+/// `Total LOC: <lines> (code, comments, blank)`, as `--loc` prints it.
+pub fn total_loc_line(stats: &Stats, theme: &Theme) -> String {
+    let loc = stats.total_loc;
+    format!(
+        "{}Total LOC: {} (code {}, comments {}, blank {}){}",
+        theme.title,
+        loc.total(),
+        loc.code,
+        loc.comment,
+        loc.blank,
+        theme.reset
+    )
+}
+
+/// Languages by code lines, most first, with [`loc::OTHER`] last.
+pub fn languages_by_code(stats: &Stats) -> Vec<(&'static str, LanguageStats)> {
+    let mut rows = stats
+        .languages
         .iter()
-        .map(|(ext, count)| (ext.clone(), *count))
+        .map(|(name, lang)| (*name, *lang))
         .collect::<Vec<_>>();
-    v.sort_by(|a, b| b.1.cmp(&a.1));
+    rows.sort_by(|a, b| {
+        (a.0 == loc::OTHER)
+            .cmp(&(b.0 == loc::OTHER))
+            .then_with(|| b.1.loc.code.cmp(&a.1.loc.code))
+            .then_with(|| b.1.files.cmp(&a.1.files))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    rows
+}
 
-    for (ext, count) in v {
-        let loc = stats.loc_stats.get(&ext).copied().unwrap_or(0);
-        println!(
-            "{}  .{}: {} files, {} LOC{}",
-            theme.meta, ext, count, loc, theme.reset
-        );
+/// The tokei-style table: files and line kinds per language, then totals.
+pub fn loc_table(stats: &Stats, theme: &Theme) -> Vec<String> {
+    let row = |name: &str, files: usize, loc: LineCounts| {
+        format!(
+            "{:<14} {:>6} {:>8} {:>8} {:>8} {:>8}",
+            name,
+            files,
+            loc.total(),
+            loc.code,
+            loc.comment,
+            loc.blank
+        )
+    };
+    let rule = format!("{}{}{}", theme.subtle, "─".repeat(57), theme.reset);
+    let mut out = vec![
+        format!(
+            "{}{:<14} {:>6} {:>8} {:>8} {:>8} {:>8}{}",
+            theme.title, "Language", "Files", "Lines", "Code", "Comments", "Blank", theme.reset
+        ),
+        rule.clone(),
+    ];
+    for (name, lang) in languages_by_code(stats) {
+        out.push(format!(
+            "{}{}{}",
+            theme.subtle,
+            row(name, lang.files, lang.loc),
+            theme.reset
+        ));
     }
+    out.push(rule);
+    out.push(format!(
+        "{}{}{}",
+        theme.number,
+        row("Total", stats.file_count, stats.total_loc),
+        theme.reset
+    ));
+    out
 }
 
 pub fn loc_for_extensions(stats: &Stats, exts: &[String]) -> u64 {
@@ -92,45 +162,32 @@ pub fn loc_for_extensions(stats: &Stats, exts: &[String]) -> u64 {
         .sum()
 }
 
-pub type DuplicateMap = HashMap<String, Vec<PathBuf>>;
+pub type DuplicateMap = HashMap<String, Vec<(PathBuf, u64)>>;
 
-pub fn collect_duplicates(
-    root: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-) -> Result<DuplicateMap, String> {
+pub fn collect_duplicates(tree: &Tree) -> DuplicateMap {
     let mut map: DuplicateMap = HashMap::new();
-
-    let walker = walkdir::WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+    for entry in tree.files() {
+        if let Some(hash) = &entry.digest {
+            map.entry(hash.clone())
+                .or_default()
+                .push((entry.path.clone(), entry.size));
         }
-        let bytes = match fs::read(path) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-        let digest = md5::compute(bytes);
-        let hash = format!("{:x}", digest);
-        map.entry(hash).or_default().push(path.to_path_buf());
     }
-
-    Ok(map)
+    map
 }
 
 pub fn print_duplicates(dupes: &DuplicateMap, cfg: &Config, theme: &Theme) {
     println!();
     println!(
         "{}=== Duplicate Files (by content) ==={}",
-        theme.header, theme.reset
+        theme.title, theme.reset
     );
     println!();
 
+    let mut groups = dupes.iter().collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.0.cmp(b.0));
     let mut any = false;
-    for (hash, files) in dupes {
+    for (hash, files) in groups {
         if files.len() <= 1 {
             continue;
         }
@@ -142,8 +199,7 @@ pub fn print_duplicates(dupes: &DuplicateMap, cfg: &Config, theme: &Theme) {
             files.len(),
             theme.reset
         );
-        for file in files {
-            let size = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        for (file, size) in files {
             let rel = file
                 .strip_prefix(&cfg.target_dir)
                 .ok()
@@ -154,7 +210,7 @@ pub fn print_duplicates(dupes: &DuplicateMap, cfg: &Config, theme: &Theme) {
                 "{}  - {} ({}){}",
                 theme.file,
                 rel,
-                format_size(size),
+                human_size(*size),
                 theme.reset
             );
         }
@@ -162,34 +218,23 @@ pub fn print_duplicates(dupes: &DuplicateMap, cfg: &Config, theme: &Theme) {
     }
 
     if !any {
-        println!("{}No duplicate files found.{}", theme.meta, theme.reset);
+        println!("{}No duplicate files found.{}", theme.subtle, theme.reset);
     }
 }
 
-pub fn grouped_view(
-    root: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    theme: &Theme,
-) -> Result<(), String> {
-    let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
-    let walker = walkdir::WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        let ext = path
+pub fn grouped_view(root: &Path, tree: &Tree, theme: &Theme) {
+    let mut groups: HashMap<String, Vec<&Path>> = HashMap::new();
+    for entry in tree.files() {
+        let ext = entry
+            .path
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("no-ext")
             .to_ascii_lowercase();
-        groups.entry(ext).or_default().push(path.to_path_buf());
+        groups.entry(ext).or_default().push(&entry.path);
     }
 
-    println!("{}{}{}/{}", theme.dir, root.display(), "", theme.reset);
+    println!("{}{}/{}", theme.directory, root.display(), theme.reset);
     println!();
 
     let mut keys = groups.keys().cloned().collect::<Vec<_>>();
@@ -198,7 +243,7 @@ pub fn grouped_view(
         let files = groups.get(&ext).cloned().unwrap_or_default();
         println!(
             "{}[{}] ({} files){}",
-            theme.header,
+            theme.title,
             ext,
             files.len(),
             theme.reset
@@ -208,58 +253,57 @@ pub fn grouped_view(
         }
         println!();
     }
+}
 
-    Ok(())
+/// `--fingerprint`: totals, language stats, git info and the largest files.
+pub struct FingerprintRenderer<'a> {
+    pub theme: &'a Theme,
 }
 
-pub fn print_fingerprint(
-    root: &Path,
-    _cfg: &Config,
-    theme: &Theme,
-    ignore: &IgnoreMatcher,
-    stats: &Stats,
-) -> Result<(), String> {
-    println!("{}=== Project Fingerprint ==={}", theme.header, theme.reset);
+impl Renderer for FingerprintRenderer<'_> {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String> {
+        print_fingerprint(root, self.theme, &collect_stats(root));
+        Ok(())
+    }
+}
+
+fn print_fingerprint(root: &TreeNode, theme: &Theme, stats: &Stats) {
+    println!("{}=== Project Fingerprint ==={}", theme.title, theme.reset);
     println!();
     println!(
         "{}📂 Directory: {}{}",
-        theme.meta,
-        root.display(),
+        theme.subtle,
+        root.path.display(),
         theme.reset
     );
     println!(
         "{}📁 Total Directories: {}{}",
-        theme.meta, stats.dir_count, theme.reset
+        theme.subtle, stats.dir_count, theme.reset
     );
     println!(
         "{}📄 Total Files: {}{}",
-        theme.meta, stats.file_count, theme.reset
+        theme.subtle, stats.file_count, theme.reset
     );
     println!(
         "{}💾 Total Size: {}{}",
-        theme.meta,
-        format_size(stats.total_size),
+        theme.subtle,
+        human_size(stats.total_size),
         theme.reset
     );
     println!(
         "{}🧾 Total LOC: {}{}",
-        theme.meta, stats.total_loc, theme.reset
+        theme.subtle,
+        stats.total_loc.total(),
+        theme.reset
     );
 
-    let mut max_depth = 0usize;
-    let fp_walker = walkdir::WalkDir::new(root)
+    let max_depth = root
+        .descendants()
         .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), false));
-    for entry in fp_walker.filter_map(Result::ok) {
-        let path = entry.path();
-        let depth = path
-            .strip_prefix(root)
-            .ok()
-            .map(|p| p.components().count())
-            .unwrap_or(0);
-        max_depth = max_depth.max(depth);
-    }
-    println!("{}📊 Max Depth: {}{}", theme.meta, max_depth, theme.reset);
+        .map(|(depth, _)| depth)
+        .max()
+        .unwrap_or(0);
+    println!("{}📊 Max Depth: {}{}", theme.subtle, max_depth, theme.reset);
 
     print_stats(stats, theme);
 
@@ -270,14 +314,14 @@ pub fn print_fingerprint(
         .unwrap_or(false)
     {
         println!();
-        println!("{}=== Git Status ==={}", theme.header, theme.reset);
+        println!("{}=== Git Status ==={}", theme.title, theme.reset);
         let branch = Command::new("git")
             .args(["branch", "--show-current"])
             .output()
             .ok()
             .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
             .unwrap_or_else(|| "unknown".to_string());
-        println!("{}🌿 Branch: {}{}", theme.meta, branch, theme.reset);
+        println!("{}🌿 Branch: {}{}", theme.subtle, branch, theme.reset);
 
         let commits = Command::new("git")
             .args(["rev-list", "--count", "HEAD"])
@@ -285,55 +329,31 @@ pub fn print_fingerprint(
             .ok()
             .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
             .unwrap_or_else(|| "0".to_string());
-        println!("{}📝 Commits: {}{}", theme.meta, commits, theme.reset);
+        println!("{}📝 Commits: {}{}", theme.subtle, commits, theme.reset);
     }
 
     println!();
     println!(
         "{}=== Largest Files (Top 10) ==={}",
-        theme.header, theme.reset
+        theme.title, theme.reset
     );
 
-    let mut files = Vec::new();
-    let largest_walker = walkdir::WalkDir::new(root)
+    let mut files = root
+        .descendants()
         .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), false));
-    for entry in largest_walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
-        files.push((size, path.to_path_buf()));
-    }
-    files.sort_by(|a, b| b.0.cmp(&a.0));
+        .filter(|(_, node)| node.is_file())
+        .map(|(_, node)| (node.size, node.path.as_path()))
+        .collect::<Vec<_>>();
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
     for (size, path) in files.into_iter().take(10) {
         println!(
             "{}  {} {}{}",
             theme.size,
-            format_size(size),
+            human_size(size),
             path.display(),
             theme.reset
         );
     }
-
-    Ok(())
-}
-
-fn count_lines(path: &Path) -> u64 {
-    match fs::read(path) {
-        Ok(bytes) => {
-            if bytes.is_empty() {
-                return 0;
-            }
-            let mut lines = bytes.iter().filter(|&&b| b == b'\n').count() as u64;
-            if *bytes.last().unwrap_or(&b'\n') != b'\n' {
-                lines += 1;
-            }
-            lines
-        }
-        Err(_) => 0,
-    }
 }
 
 pub fn audit_file(path: &Path, theme: &Theme) {
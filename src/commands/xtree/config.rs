@@ -1,5 +1,10 @@
 use std::ffi::OsString;
 use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::core::format::{parse_size, parse_time};
+use crate::core::pager::Paging;
+use crate::core::settings::Settings;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Mode {
@@ -17,18 +22,57 @@ pub enum SortMode {
     Time,
 }
 
-#[derive(Debug)]
+/// What `--du` totals: file lengths, or blocks allocated on disk like `du`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuMode {
+    #[default]
+    Apparent,
+    Disk,
+}
+
+impl DuMode {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "apparent" => Ok(Self::Apparent),
+            "disk" => Ok(Self::Disk),
+            _ => Err("--du supports: apparent | disk".to_string()),
+        }
+    }
+
+    pub fn pick(self, apparent: u64, disk: u64) -> u64 {
+        match self {
+            Self::Apparent => apparent,
+            Self::Disk => disk,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Config {
     pub max_depth: Option<usize>,
     pub show_hidden: bool,
     pub show_size: bool,
+    pub du: Option<DuMode>,
+    pub min_size: Option<u64>,
+    /// `--since`: only files modified at or after this time.
+    pub since: Option<SystemTime>,
+    /// `--before`: only files modified before this time.
+    pub before: Option<SystemTime>,
+    /// `--changed-since <ref>`: only files git saw change since the ref.
+    pub changed_since: Option<String>,
     pub show_info: bool,
     pub dir_only: bool,
     pub target_dir: PathBuf,
     pub excludes: Vec<String>,
     pub cat_exts: Vec<String>,
     pub grep_pattern: Option<String>,
+    pub grep_ignore_case: bool,
+    pub grep_word: bool,
+    pub grep_fixed: bool,
+    pub grep_context: usize,
+    pub grep_max_count: Option<usize>,
     pub use_gitignore: bool,
+    pub git_status: bool,
     pub show_stats: bool,
     pub show_loc: bool,
     pub prompt_mode: bool,
@@ -45,10 +89,13 @@ pub struct Config {
     pub clip: usize,
     pub show_file_count: bool,
     pub use_treeignore: bool,
+    pub use_default_ignores: bool,
     pub show_icons: bool,
     pub no_report: bool,
     pub theme: String,
     pub mode: Mode,
+    pub tui: bool,
+    pub paging: Option<Paging>,
     pub help: bool,
     pub tldr: bool,
 }
@@ -59,13 +106,24 @@ impl Default for Config {
             max_depth: None,
             show_hidden: false,
             show_size: true,
+            du: None,
+            min_size: None,
+            since: None,
+            before: None,
+            changed_since: None,
             show_info: false,
             dir_only: false,
             target_dir: PathBuf::from("."),
             excludes: Vec::new(),
             cat_exts: Vec::new(),
             grep_pattern: None,
+            grep_ignore_case: false,
+            grep_word: false,
+            grep_fixed: false,
+            grep_context: 0,
+            grep_max_count: None,
             use_gitignore: true,
+            git_status: false,
             show_stats: false,
             show_loc: false,
             prompt_mode: false,
@@ -82,10 +140,13 @@ impl Default for Config {
             clip: 100,
             show_file_count: false,
             use_treeignore: true,
+            use_default_ignores: true,
             show_icons: true,
             no_report: false,
             theme: "onedark-pro".to_string(),
             mode: Mode::Normal,
+            tui: false,
+            paging: None,
             help: false,
             tldr: false,
         }
@@ -93,9 +154,12 @@ impl Default for Config {
 }
 
 impl Config {
-    pub fn parse(args: &[OsString]) -> Result<Self, String> {
+    pub fn parse(args: &[OsString], settings: &Settings) -> Result<Self, String> {
         let mut cfg = Config::default();
+        cfg.apply_settings(settings)?;
         let mut it = args.iter().peekable();
+        // `-i` is grep's ignore-case next to `--grep`, metadata otherwise.
+        let mut short_i = false;
 
         while let Some(arg) = it.next() {
             let s = arg.to_string_lossy();
@@ -121,7 +185,40 @@ impl Config {
                 "-a" => cfg.show_hidden = true,
                 "-d" => cfg.dir_only = true,
                 "-s" => cfg.show_size = false,
-                "-i" => cfg.show_info = true,
+                "--du" => cfg.du = Some(DuMode::Apparent),
+                _ if s.starts_with("--du=") => {
+                    cfg.du = Some(DuMode::parse(&s["--du=".len()..])?);
+                }
+                "--min-size" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| "--min-size requires a size like 10M".to_string())?;
+                    cfg.min_size = Some(parse_min_size(&v.to_string_lossy())?);
+                }
+                _ if s.starts_with("--min-size=") => {
+                    cfg.min_size = Some(parse_min_size(&s["--min-size=".len()..])?);
+                }
+                "--since" | "--before" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| format!("{s} requires an age like 2d or a date"))?;
+                    cfg.set_time_bound(&s, &v.to_string_lossy())?;
+                }
+                _ if s.starts_with("--since=") || s.starts_with("--before=") => {
+                    let (flag, value) = s.split_once('=').unwrap_or_default();
+                    cfg.set_time_bound(flag, value)?;
+                }
+                "--changed-since" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| "--changed-since requires a git ref".to_string())?;
+                    cfg.changed_since = Some(v.to_string_lossy().to_string());
+                }
+                _ if s.starts_with("--changed-since=") => {
+                    cfg.changed_since = Some(s["--changed-since=".len()..].to_string());
+                }
+                "-i" => short_i = true,
+                "--info" => cfg.show_info = true,
                 "-c" | "--cat" => {
                     while let Some(next) = it.peek() {
                         let nexts = next.to_string_lossy();
@@ -139,8 +236,32 @@ impl Config {
                         .ok_or_else(|| "--grep requires a pattern".to_string())?;
                     cfg.grep_pattern = Some(v.to_string_lossy().to_string());
                 }
+                "--ignore-case" => cfg.grep_ignore_case = true,
+                "-w" | "--word-regexp" => cfg.grep_word = true,
+                "-F" | "--fixed-strings" => cfg.grep_fixed = true,
+                "-C" | "--context" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| "--context requires a line count".to_string())?;
+                    cfg.grep_context = v
+                        .to_string_lossy()
+                        .parse::<usize>()
+                        .map_err(|_| "invalid line count for --context".to_string())?;
+                }
+                "-m" | "--max-count" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| "--max-count requires a number".to_string())?;
+                    cfg.grep_max_count = Some(
+                        v.to_string_lossy()
+                            .parse::<usize>()
+                            .map_err(|_| "invalid number for --max-count".to_string())?,
+                    );
+                }
                 "--no-git" => cfg.use_gitignore = false,
+                "--git-status" => cfg.git_status = true,
                 "--no-treeignore" => cfg.use_treeignore = false,
+                "--no-default-ignores" => cfg.use_default_ignores = false,
                 "--no-icon" => cfg.show_icons = false,
                 "--stats" => cfg.show_stats = true,
                 "--loc" => cfg.show_loc = true,
@@ -188,6 +309,7 @@ impl Config {
                 }
                 "--tests" => cfg.show_tests = true,
                 "--fingerprint" => cfg.mode = Mode::Fingerprint,
+                "--tui" => cfg.tui = true,
                 "--resolve" => cfg.resolve_symlinks = true,
                 "--theme" => {
                     let v = it
@@ -195,6 +317,15 @@ impl Config {
                         .ok_or_else(|| "--theme requires a theme name".to_string())?;
                     cfg.theme = v.to_string_lossy().to_string();
                 }
+                "--paging" => {
+                    let v = it
+                        .next()
+                        .ok_or_else(|| "--paging requires auto|always|never".to_string())?;
+                    cfg.paging = Some(Paging::from_flag(&v.to_string_lossy())?);
+                }
+                _ if s.starts_with("--paging=") => {
+                    cfg.paging = Some(Paging::from_flag(&s["--paging=".len()..])?);
+                }
                 "--count" => cfg.show_file_count = true,
                 "--noreport" => cfg.no_report = true,
                 "--" => break,
@@ -202,14 +333,125 @@ impl Config {
                 _ => cfg.target_dir = PathBuf::from(s.to_string()),
             }
         }
+        if short_i {
+            if cfg.grep_pattern.is_some() {
+                cfg.grep_ignore_case = true;
+            } else {
+                cfg.show_info = true;
+            }
+        }
 
         Ok(cfg)
     }
+
+    /// Applies `[xtree]` (and the `[global]` theme) from config.toml; CLI flags
+    /// parsed afterwards win. `DUSK_THEME` still beats the file.
+    fn apply_settings(&mut self, settings: &Settings) -> Result<(), String> {
+        let flag = |key: &str| settings.get_bool("xtree", key);
+        if let Some(v) = flag("hidden") {
+            self.show_hidden = v;
+        }
+        if let Some(v) = flag("size") {
+            self.show_size = v;
+        }
+        if let Some(v) = flag("info") {
+            self.show_info = v;
+        }
+        if let Some(v) = flag("dirs-only") {
+            self.dir_only = v;
+        }
+        if let Some(v) = flag("git") {
+            self.use_gitignore = v;
+        }
+        if let Some(v) = flag("git-status") {
+            self.git_status = v;
+        }
+        if let Some(v) = flag("treeignore") {
+            self.use_treeignore = v;
+        }
+        if let Some(v) = flag("default-ignores") {
+            self.use_default_ignores = v;
+        }
+        if let Some(v) = flag("icons") {
+            self.show_icons = v;
+        }
+        if let Some(v) = flag("stats") {
+            self.show_stats = v;
+        }
+        if let Some(v) = flag("loc") {
+            self.show_loc = v;
+        }
+        if let Some(v) = flag("big") {
+            self.highlight_big = v;
+        }
+        if let Some(v) = flag("tests") {
+            self.show_tests = v;
+        }
+        if let Some(v) = flag("count") {
+            self.show_file_count = v;
+        }
+        if let Some(v) = flag("noreport") {
+            self.no_report = v;
+        }
+        if let Some(v) = settings.get_usize("xtree", "depth") {
+            self.max_depth = Some(v);
+        }
+        if let Some(v) = settings.get_usize("xtree", "clip") {
+            self.clip = v;
+        }
+        if let Some(v) = settings.get_list("xtree", "exclude") {
+            self.excludes = v;
+        }
+        if let Some(v) = settings.get_str("xtree", "sort") {
+            self.sort_mode = match v {
+                "name" => SortMode::Name,
+                "size" => SortMode::Size,
+                "time" => SortMode::Time,
+                _ => return Err("config [xtree] sort supports: name | size | time".to_string()),
+            };
+        }
+        let env_theme = std::env::var("DUSK_THEME").ok();
+        if let Some(name) = env_theme
+            .as_deref()
+            .or_else(|| settings.get_str("xtree", "theme"))
+            .or_else(|| settings.get_str("global", "theme"))
+        {
+            self.theme = name.to_string();
+        }
+        Ok(())
+    }
+
+    fn set_time_bound(&mut self, flag: &str, value: &str) -> Result<(), String> {
+        let time = parse_time(value, SystemTime::now()).ok_or_else(|| {
+            format!("invalid time for {flag}: {value} (use an age like 30m/2d/1w or YYYY-MM-DD)")
+        })?;
+        if flag == "--since" {
+            self.since = Some(time);
+        } else {
+            self.before = Some(time);
+        }
+        Ok(())
+    }
+
+    /// Whether only some files are kept, so directories without a kept file
+    /// below them are pruned.
+    pub fn filters_files(&self) -> bool {
+        !self.focus_exts.is_empty()
+            || self.grep_pattern.is_some()
+            || self.since.is_some()
+            || self.before.is_some()
+            || self.changed_since.is_some()
+    }
+}
+
+fn parse_min_size(value: &str) -> Result<u64, String> {
+    parse_size(value).ok_or_else(|| format!("invalid size for --min-size: {value}"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, Mode, SortMode};
+    use super::{Config, DuMode, Mode, SortMode};
+    use crate::core::settings::Settings;
     use std::ffi::OsString;
 
     #[test]
@@ -219,11 +461,25 @@ mod tests {
             OsString::from("target"),
             OsString::from("--noreport"),
         ];
-        let cfg = Config::parse(&args).expect("parse");
+        let cfg = Config::parse(&args, &Settings::default()).expect("parse");
         assert!(cfg.excludes.contains(&"target".to_string()));
         assert!(cfg.no_report);
     }
 
+    #[test]
+    fn short_i_ignores_case_with_grep_and_shows_info_without() {
+        let parse = |args: &[&str]| {
+            let args = args.iter().map(OsString::from).collect::<Vec<_>>();
+            Config::parse(&args, &Settings::default()).expect("parse")
+        };
+        let cfg = parse(&["-i", "-g", "todo"]);
+        assert!(cfg.grep_ignore_case && !cfg.show_info);
+        let cfg = parse(&["-i"]);
+        assert!(cfg.show_info && !cfg.grep_ignore_case);
+        let cfg = parse(&["--grep", "todo", "--info", "-i"]);
+        assert!(cfg.grep_ignore_case && cfg.show_info);
+    }
+
     #[test]
     fn parse_modes_and_sort() {
         let args = vec![
@@ -232,9 +488,45 @@ mod tests {
             OsString::from("time"),
             OsString::from("--loc"),
         ];
-        let cfg = Config::parse(&args).expect("parse");
+        let cfg = Config::parse(&args, &Settings::default()).expect("parse");
         assert!(matches!(cfg.mode, Mode::Json));
         assert!(matches!(cfg.sort_mode, SortMode::Time));
         assert!(cfg.show_loc);
     }
+
+    #[test]
+    fn parse_du_modes_and_min_size() {
+        let args = vec![OsString::from("--du"), OsString::from("--min-size=1.5K")];
+        let cfg = Config::parse(&args, &Settings::default()).expect("parse");
+        assert_eq!(cfg.du, Some(DuMode::Apparent));
+        assert_eq!(cfg.min_size, Some(1536));
+
+        let args = vec![OsString::from("--du=disk")];
+        let cfg = Config::parse(&args, &Settings::default()).expect("parse");
+        assert_eq!(cfg.du, Some(DuMode::Disk));
+
+        let args = vec![OsString::from("--du=blocks")];
+        assert!(Config::parse(&args, &Settings::default()).is_err());
+    }
+
+    #[test]
+    fn parse_time_filters() {
+        let args = vec![
+            OsString::from("--since=2026-01-01"),
+            OsString::from("--before"),
+            OsString::from("2026-02-01"),
+            OsString::from("--changed-since"),
+            OsString::from("main"),
+        ];
+        let cfg = Config::parse(&args, &Settings::default()).expect("parse");
+        assert!(
+            cfg.since
+                .is_some_and(|since| cfg.before.is_some_and(|b| since < b))
+        );
+        assert_eq!(cfg.changed_since.as_deref(), Some("main"));
+        assert!(cfg.filters_files());
+
+        let args = vec![OsString::from("--since"), OsString::from("yesterday")];
+        assert!(Config::parse(&args, &Settings::default()).is_err());
+    }
 }
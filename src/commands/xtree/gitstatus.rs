@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub(crate) use crate::commands::git::status::{GitStatus, Mark};
+use crate::core::process;
+use crate::core::theme::Theme;
+
+/// `--git-status` marker colors: additions green, edits blue, deletions and
+/// conflicts red.
+pub fn paint(mark: Mark, theme: &Theme) -> String {
+    let color = |c: char| match c {
+        'N' => theme.diff_add,
+        'M' => theme.info,
+        'D' | 'U' => theme.diff_del,
+        'R' => theme.warn,
+        'T' => theme.accent,
+        _ => theme.subtle,
+    };
+    format!(
+        "{}{}{}{}{}",
+        color(mark.staged),
+        mark.staged,
+        color(mark.unstaged),
+        mark.unstaged,
+        theme.reset
+    )
+}
+
+/// Files under `root` that differ from the merge base of `rev` and `HEAD`,
+/// committed or not, plus untracked files: what `--changed-since` keeps.
+pub fn changed_since(root: &Path, rev: &str) -> Result<HashSet<PathBuf>, String> {
+    if !root.ancestors().any(|dir| dir.join(".git").exists()) {
+        return Err("--changed-since needs a git repository".to_string());
+    }
+    // git would read `--output=...` and friends as options, not a revision.
+    if rev.starts_with('-') {
+        return Err(format!("invalid --changed-since revision: {rev}"));
+    }
+    process::ensure_command_exists("git", "dusk xtree --changed-since")?;
+
+    let run = |args: &[&str]| {
+        process::run_capture_in(root, "git", args)
+            .map_err(|err| format!("git {} failed: {err}", args[0]))?
+            .ok_or_else(|| format!("git {} failed for `{rev}`", args[0]))
+    };
+    // Resolve to a commit id first, so only a hash ever reaches `git diff`.
+    let commit = run(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{rev}^{{commit}}"),
+    ])
+    .map_err(|_| format!("unknown --changed-since revision: {rev}"))?;
+    // `--relative` keeps paths relative to `root`, like `--git-status`.
+    let diff = run(&[
+        "diff",
+        "--name-only",
+        "-z",
+        "--relative",
+        "--no-renames",
+        "--merge-base",
+        commit.trim(),
+        "--",
+    ])?;
+    let untracked = run(&[
+        "ls-files",
+        "-z",
+        "--others",
+        "--exclude-standard",
+        "--",
+        ".",
+    ])?;
+    Ok(diff
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|line| !line.is_empty())
+        .map(|line| root.join(line))
+        .collect())
+}
@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use super::config::Config;
+
+/// How much of a file is checked for NUL bytes before it is skipped as
+/// binary.
+const BINARY_SNIFF: usize = 8 * 1024;
+
+/// One printed line of a file's grep result. `spans` are the byte ranges of
+/// the matches; an empty list means a context line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepLine {
+    pub number: usize,
+    pub text: String,
+    pub spans: Vec<Range<usize>>,
+}
+
+impl GrepLine {
+    pub fn is_match(&self) -> bool {
+        !self.spans.is_empty()
+    }
+}
+
+/// `-g/--grep` compiled from the pattern and its flags.
+pub struct Grep {
+    regex: Regex,
+    context: usize,
+    max_count: Option<usize>,
+}
+
+impl Grep {
+    pub fn new(cfg: &Config) -> Result<Option<Self>, String> {
+        let Some(pattern) = &cfg.grep_pattern else {
+            return Ok(None);
+        };
+        let mut source = if cfg.grep_fixed {
+            regex::escape(pattern)
+        } else {
+            pattern.clone()
+        };
+        if cfg.grep_word {
+            source = format!(r"\b(?:{source})\b");
+        }
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(cfg.grep_ignore_case)
+            .build()
+            .map_err(|err| format!("invalid --grep pattern `{pattern}`: {err}"))?;
+        Ok(Some(Self {
+            regex,
+            context: cfg.grep_context,
+            max_count: cfg.grep_max_count,
+        }))
+    }
+
+    /// Matching lines of `bytes` plus up to `context` lines around each,
+    /// in file order. Empty for binary content or when nothing matches.
+    pub fn search(&self, bytes: &[u8]) -> Vec<GrepLine> {
+        if looks_binary(bytes) {
+            return Vec::new();
+        }
+        let text = String::from_utf8_lossy(bytes);
+        let lines = text
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect::<Vec<_>>();
+
+        let mut hits = Vec::new();
+        for (idx, line) in lines.iter().enumerate() {
+            if self.max_count.is_some_and(|max| hits.len() >= max) {
+                break;
+            }
+            let spans = self
+                .regex
+                .find_iter(line)
+                .map(|m| m.range())
+                .filter(|span| !span.is_empty())
+                .collect::<Vec<_>>();
+            if !spans.is_empty() {
+                hits.push((idx, spans));
+            }
+        }
+
+        let context = |range: Range<usize>| {
+            range.map(|idx| GrepLine {
+                number: idx + 1,
+                text: lines[idx].to_string(),
+                spans: Vec::new(),
+            })
+        };
+        let mut out: Vec<GrepLine> = Vec::new();
+        for (idx, spans) in hits {
+            let first = idx.saturating_sub(self.context);
+            let start = out.last().map_or(first, |last| first.max(last.number));
+            out.extend(context(start..idx));
+            // Trailing context of the previous hit may run past this line.
+            while out.last().is_some_and(|last| last.number > idx) {
+                out.pop();
+            }
+            out.push(GrepLine {
+                number: idx + 1,
+                text: lines[idx].to_string(),
+                spans,
+            });
+            let end = (idx + 1 + self.context).min(lines.len());
+            out.extend(context(idx + 1..end));
+        }
+        out
+    }
+}
+
+/// Whether `bytes` has a NUL byte near the start, like git and grep check.
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF)].contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grep;
+    use crate::commands::xtree::config::Config;
+
+    fn grep(pattern: &str, edit: impl FnOnce(&mut Config)) -> Grep {
+        let mut cfg = Config {
+            grep_pattern: Some(pattern.to_string()),
+            ..Config::default()
+        };
+        edit(&mut cfg);
+        Grep::new(&cfg).expect("pattern").expect("grep")
+    }
+
+    fn numbers(grep: &Grep, text: &str) -> Vec<(usize, bool)> {
+        grep.search(text.as_bytes())
+            .iter()
+            .map(|line| (line.number, line.is_match()))
+            .collect()
+    }
+
+    #[test]
+    fn matches_regex_words_fixed_strings_and_case() {
+        let text = "let todo = 1;\nTODO: fix\ntodos.len()\na.b\n";
+        assert_eq!(numbers(&grep("TODO", |_| {}), text), [(2, true)]);
+        assert_eq!(
+            numbers(&grep("todo", |c| c.grep_ignore_case = true), text),
+            [(1, true), (2, true), (3, true)]
+        );
+        assert_eq!(
+            numbers(&grep("todo", |c| c.grep_word = true), text),
+            [(1, true)]
+        );
+        assert_eq!(
+            numbers(&grep("a.b", |_| {}), "axb\na.b\n"),
+            [(1, true), (2, true)]
+        );
+        assert_eq!(
+            numbers(&grep("a.b", |c| c.grep_fixed = true), "axb\na.b\n"),
+            [(2, true)]
+        );
+
+        let spans = grep("o", |_| {}).search(b"foo")[0].spans.clone();
+        assert_eq!(spans, [1..2, 2..3]);
+    }
+
+    #[test]
+    fn merges_overlapping_context() {
+        let text = "1\n2\nhit\n4\nhit\n6\n7\n8\nhit\n";
+        let wide = grep("hit", |c| c.grep_context = 2);
+        assert_eq!(
+            numbers(&wide, "hit\nhit\n3\n"),
+            [(1, true), (2, true), (3, false)]
+        );
+
+        let grep = grep("hit", |c| c.grep_context = 1);
+        assert_eq!(
+            numbers(&grep, text),
+            [
+                (2, false),
+                (3, true),
+                (4, false),
+                (5, true),
+                (6, false),
+                (8, false),
+                (9, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn caps_matches_and_skips_binary() {
+        let grep = grep("x", |c| c.grep_max_count = Some(2));
+        assert_eq!(numbers(&grep, "x\nx\nx\n"), [(1, true), (2, true)]);
+        assert!(grep.search(b"x\0x").is_empty());
+    }
+}
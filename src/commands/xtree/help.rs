@@ -122,10 +122,20 @@ pub fn full_help(theme_name: Option<&str>) -> String {
     out.push_str(&format!("{}\n", opt("DISPLAY")));
     out.push_str(&format!(
         "  {} {}\n",
-        opt("-i"),
-        desc("Show metadata (permissions, owner, modified)")
+        opt("--info, -i"),
+        desc("Show metadata (permissions, owner, modified); -i is ignore-case with --grep")
     ));
     out.push_str(&format!("  {} {}\n", opt("-s"), desc("Hide file sizes")));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("--du[=apparent|disk]"),
+        desc("Recursive sizes with share of parent and a bar (disk = allocated blocks)")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("--min-size <size>"),
+        desc("Hide entries smaller than e.g. 500K, 10M (totals for directories)")
+    ));
     out.push_str(&format!(
         "  {} {}\n",
         opt("--no-icon"),
@@ -157,7 +167,22 @@ pub fn full_help(theme_name: Option<&str>) -> String {
     out.push_str(&format!(
         "  {} {}\n",
         opt("-g, --grep <pattern>"),
-        desc("Search inside files (first 5 matches per file)")
+        desc("Show regex matches nested under each file; prunes files without hits")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("-i, --ignore-case, -w, -F"),
+        desc("Grep case-insensitively, whole words only, or for a fixed string")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("-C, --context <n>"),
+        desc("Grep context lines around each match")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("-m, --max-count <n>"),
+        desc("Stop after n matching lines per file")
     ));
     out.push_str(&format!(
         "  {} {}\n",
@@ -174,7 +199,12 @@ pub fn full_help(theme_name: Option<&str>) -> String {
     out.push_str(&format!(
         "  {} {}\n",
         opt("--no-git"),
-        desc("Disable .gitignore filtering")
+        desc("Disable .gitignore/info/exclude/core.excludesFile filtering")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("--git-status"),
+        desc("Mark entries with their git status (staged/work tree, rolled up on directories)")
     ));
     out.push_str(&format!(
         "  {} {}\n",
@@ -182,10 +212,25 @@ pub fn full_help(theme_name: Option<&str>) -> String {
         desc("Disable .treeignore filtering")
     ));
     out.push_str(&format!(
-        "  {} {}\n\n",
+        "  {} {}\n",
+        opt("--no-default-ignores"),
+        desc("Show node_modules, target, build, dist, out, ... (or `!build` in .treeignore)")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n",
         opt("--focus <ext...>"),
         desc("Keep only directories containing matching extensions")
     ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("--since, --before <when>"),
+        desc("Keep files modified since / before an age (30m, 2d, 1w) or date (YYYY-MM-DD)")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n\n",
+        opt("--changed-since <ref>"),
+        desc("Keep files changed in git since <ref> (committed, uncommitted or untracked)")
+    ));
 
     out.push_str(&format!("{}\n", opt("ANALYSIS")));
     out.push_str(&format!(
@@ -196,7 +241,7 @@ pub fn full_help(theme_name: Option<&str>) -> String {
     out.push_str(&format!(
         "  {} {}\n",
         opt("--loc"),
-        desc("Show total lines of code (LOC)")
+        desc("Code/comment/blank lines per language, with totals")
     ));
     out.push_str(&format!(
         "  {} {}\n",
@@ -240,10 +285,20 @@ pub fn full_help(theme_name: Option<&str>) -> String {
     out.push_str(&format!("  {} {}\n", opt("--md"), desc("Markdown export")));
     out.push_str(&format!("  {} {}\n", opt("--json"), desc("JSON output")));
     out.push_str(&format!(
-        "  {} {}\n\n",
+        "  {} {}\n",
         opt("--prompt"),
         desc("AI-friendly dump and save to your OS temp directory")
     ));
+    out.push_str(&format!(
+        "  {} {}\n",
+        opt("--tui"),
+        desc("Interactive browser: h/l fold, / filter, . hidden, i ignored, s stats, y copy")
+    ));
+    out.push_str(&format!(
+        "  {} {}\n\n",
+        opt("--paging auto|always|never"),
+        desc("Pipe long output through $DUSK_PAGER/$PAGER (default: less -RFX)")
+    ));
 
     out.push_str(&format!("{}\n", opt("EXAMPLES")));
     out.push_str(&format!("  {} {}\n", opt("dusk"), cmd("xtree")));
@@ -1,12 +1,19 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use ignore::gitignore::{self, Gitignore, GitignoreBuilder};
 
 use super::config::Config;
 
-const COMMON_IGNORES: [&str; 15] = [
+/// Names hidden unless `--no-default-ignores` is given or `.treeignore`
+/// negates them (`!build`). Matched against the entry name and its path
+/// relative to the root.
+const DEFAULT_IGNORES: [&str; 16] = [
     "node_modules",
     ".git",
     "dist",
@@ -22,24 +29,183 @@ const COMMON_IGNORES: [&str; 15] = [
     "__pycache__",
     "*.pyc",
     ".DS_Store",
+    "Thumbs.db",
 ];
 
+/// Why an entry was hidden, for the per-source report line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Hidden,
+    Defaults,
+    Exclude,
+    Treeignore,
+    Git,
+}
+
+impl Source {
+    const ALL: [Source; 5] = [
+        Source::Git,
+        Source::Defaults,
+        Source::Treeignore,
+        Source::Exclude,
+        Source::Hidden,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Hidden => "dotfiles",
+            Source::Defaults => "defaults",
+            Source::Exclude => "--exclude",
+            Source::Treeignore => ".treeignore",
+            Source::Git => "git",
+        }
+    }
+}
+
 pub struct IgnoreMatcher {
     root: PathBuf,
+    default_globs: Option<GlobSet>,
     user_globs: GlobSet,
     tree_globs: GlobSet,
-    gitignore: Option<Gitignore>,
-    use_gitignore: bool,
+    tree_negations: GlobSet,
+    git: Option<GitRules>,
     use_treeignore: bool,
+    hidden: [AtomicUsize; 5],
+}
+
+/// Git's ignore sources, in the order git consults them: `.gitignore` files
+/// from the entry's directory up to the repository root (deepest wins), then
+/// `.git/info/exclude`, then `core.excludesFile`.
+struct GitRules {
+    /// Repository root, or the xtree root outside a repository.
+    top: PathBuf,
+    info_exclude: Option<Gitignore>,
+    global: Option<Gitignore>,
+    /// Per-directory `.gitignore`, loaded the first time a walk enters it.
+    per_dir: RwLock<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+    /// The first nested `.gitignore` that failed to load, reported once the
+    /// walk is done.
+    error: Mutex<Option<String>>,
+}
+
+impl GitRules {
+    fn load(root: &Path) -> Result<Self, String> {
+        let repo = root
+            .ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf);
+        let top = repo.clone().unwrap_or_else(|| root.to_path_buf());
+
+        let info_exclude = match &repo {
+            Some(repo) => load_gitignore(&top, &repo.join(".git/info/exclude"))?,
+            None => None,
+        };
+        let global = match gitignore::gitconfig_excludes_path() {
+            Some(path) => load_gitignore(&top, &path)?,
+            None => None,
+        };
+
+        let rules = Self {
+            top: top.clone(),
+            info_exclude,
+            global,
+            per_dir: RwLock::new(HashMap::new()),
+            error: Mutex::new(None),
+        };
+        // Surface a broken top-level file up front; nested ones load lazily.
+        let top_rules = load_gitignore(&top, &top.join(".gitignore"))?;
+        rules
+            .per_dir
+            .write()
+            .map_err(|_| "ignore cache poisoned".to_string())?
+            .insert(top, top_rules.map(Arc::new));
+        Ok(rules)
+    }
+
+    fn dir_rules(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        if let Some(rules) = self.per_dir.read().ok()?.get(dir) {
+            return rules.clone();
+        }
+        let rules = match load_gitignore(dir, &dir.join(".gitignore")) {
+            Ok(rules) => rules.map(Arc::new),
+            Err(err) => {
+                if let Ok(mut slot) = self.error.lock() {
+                    slot.get_or_insert(err);
+                }
+                None
+            }
+        };
+        self.per_dir
+            .write()
+            .ok()?
+            .entry(dir.to_path_buf())
+            .or_insert(rules)
+            .clone()
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Some(parent) = path.parent() else {
+            return false;
+        };
+        if !parent.starts_with(&self.top) {
+            return false;
+        }
+        for dir in parent.ancestors() {
+            if let Some(rules) = self.dir_rules(dir)
+                && let Ok(rel) = path.strip_prefix(dir)
+            {
+                match rules.matched_path_or_any_parents(rel, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            if dir == self.top {
+                break;
+            }
+        }
+
+        let Ok(rel) = path.strip_prefix(&self.top) else {
+            return false;
+        };
+        for rules in [&self.info_exclude, &self.global].into_iter().flatten() {
+            match rules.matched_path_or_any_parents(rel, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+}
+
+fn load_gitignore(base: &Path, file: &Path) -> Result<Option<Gitignore>, String> {
+    if !file.is_file() {
+        return Ok(None);
+    }
+    let mut builder = GitignoreBuilder::new(base);
+    if let Some(err) = builder.add(file) {
+        return Err(format!("failed loading {}: {err}", file.display()));
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|err| format!("failed parsing {}: {err}", file.display()))
 }
 
 impl IgnoreMatcher {
     pub fn new(root: &Path, cfg: &Config) -> Result<Self, String> {
+        let default_globs = if cfg.use_default_ignores {
+            let mut builder = GlobSetBuilder::new();
+            for common in DEFAULT_IGNORES {
+                builder.add(Glob::new(common).map_err(|err| err.to_string())?);
+            }
+            Some(builder.build().map_err(|err| err.to_string())?)
+        } else {
+            None
+        };
+
         let mut user_builder = GlobSetBuilder::new();
-        for common in COMMON_IGNORES {
-            let glob = Glob::new(common).map_err(|err| err.to_string())?;
-            user_builder.add(glob);
-        }
         for pattern in &cfg.excludes {
             let glob = Glob::new(pattern)
                 .or_else(|_| Glob::new(&format!("*{pattern}*")))
@@ -48,6 +214,7 @@ impl IgnoreMatcher {
         }
 
         let mut tree_builder = GlobSetBuilder::new();
+        let mut negation_builder = GlobSetBuilder::new();
         let treeignore = root.join(".treeignore");
         if treeignore.is_file() {
             let content = fs::read_to_string(&treeignore).unwrap_or_default();
@@ -56,46 +223,75 @@ impl IgnoreMatcher {
                 if trimmed.is_empty() || trimmed.starts_with('#') {
                     continue;
                 }
-                let glob = Glob::new(trimmed)
-                    .or_else(|_| Glob::new(&format!("*{trimmed}*")))
+                // `!pattern` re-shows entries hidden by the defaults or by
+                // other `.treeignore` lines.
+                let (builder, pattern) = match trimmed.strip_prefix('!') {
+                    Some(negated) => (&mut negation_builder, negated.trim()),
+                    None => (&mut tree_builder, trimmed),
+                };
+                let glob = Glob::new(pattern)
+                    .or_else(|_| Glob::new(&format!("*{pattern}*")))
                     .map_err(|err| format!("invalid .treeignore pattern `{trimmed}`: {err}"))?;
-                tree_builder.add(glob);
+                builder.add(glob);
             }
         }
 
-        let gitignore = if cfg.use_gitignore {
-            let path = root.join(".gitignore");
-            if path.is_file() {
-                let mut builder = GitignoreBuilder::new(root);
-                if let Some(err) = builder.add(path) {
-                    return Err(format!("failed loading .gitignore: {err}"));
-                }
-                Some(
-                    builder
-                        .build()
-                        .map_err(|err| format!("failed parsing .gitignore: {err}"))?,
-                )
-            } else {
-                None
-            }
+        let git = if cfg.use_gitignore {
+            Some(GitRules::load(root)?)
         } else {
             None
         };
 
         Ok(Self {
             root: root.to_path_buf(),
+            default_globs,
             user_globs: user_builder.build().map_err(|err| err.to_string())?,
             tree_globs: tree_builder.build().map_err(|err| err.to_string())?,
-            gitignore,
-            use_gitignore: cfg.use_gitignore,
+            tree_negations: negation_builder.build().map_err(|err| err.to_string())?,
+            git,
             use_treeignore: cfg.use_treeignore,
+            hidden: Default::default(),
         })
     }
 
+    /// Whether a walk should skip `path`. Hidden entries are tallied per
+    /// source for [`IgnoreMatcher::hidden_counts`].
     pub fn is_ignored(&self, path: &Path, is_dir: bool, show_hidden: bool) -> bool {
+        match self.source(path, is_dir, show_hidden) {
+            Some(source) => {
+                self.hidden[source as usize].fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fails with the first nested `.gitignore` a walk could not load, like
+    /// [`IgnoreMatcher::new`] does for the top-level one.
+    pub fn check(&self) -> Result<(), String> {
+        let Some(git) = &self.git else {
+            return Ok(());
+        };
+        match git.error.lock() {
+            Ok(mut slot) => slot.take().map_or(Ok(()), Err),
+            Err(_) => Err("ignore cache poisoned".to_string()),
+        }
+    }
+
+    /// Entries hidden so far by each source that hid any, most specific
+    /// first.
+    pub fn hidden_counts(&self) -> Vec<(Source, usize)> {
+        Source::ALL
+            .into_iter()
+            .map(|source| (source, self.hidden[source as usize].load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    fn source(&self, path: &Path, is_dir: bool, show_hidden: bool) -> Option<Source> {
         let rel = path.strip_prefix(&self.root).unwrap_or(path);
         if rel.as_os_str().is_empty() {
-            return false;
+            return None;
         }
 
         let name = path
@@ -103,51 +299,33 @@ impl IgnoreMatcher {
             .and_then(|n| n.to_str())
             .unwrap_or_default();
         if !show_hidden && name.starts_with('.') && name != "." {
-            return true;
+            return Some(Source::Hidden);
         }
 
-        if matches!(
-            name,
-            "node_modules"
-                | ".git"
-                | "dist"
-                | "build"
-                | "target"
-                | ".next"
-                | ".nuxt"
-                | "out"
-                | "coverage"
-                | ".cache"
-                | ".venv"
-                | "venv"
-                | "__pycache__"
-                | ".DS_Store"
-                | "Thumbs.db"
-        ) {
-            return true;
-        }
-        if name.ends_with(".pyc") {
-            return true;
+        let negated = self.use_treeignore
+            && (self.tree_negations.is_match(name) || self.tree_negations.is_match(rel));
+
+        if !negated
+            && let Some(defaults) = &self.default_globs
+            && (defaults.is_match(name) || defaults.is_match(rel))
+        {
+            return Some(Source::Defaults);
         }
 
         if self.user_globs.is_match(rel) || self.user_globs.is_match(path) {
-            return true;
+            return Some(Source::Exclude);
         }
 
-        if self.use_treeignore && (self.tree_globs.is_match(rel) || self.tree_globs.is_match(path))
+        if self.use_treeignore
+            && !negated
+            && (self.tree_globs.is_match(rel) || self.tree_globs.is_match(path))
         {
-            return true;
+            return Some(Source::Treeignore);
         }
 
-        if self.use_gitignore {
-            if let Some(gitignore) = &self.gitignore {
-                let m = gitignore.matched_path_or_any_parents(rel, is_dir);
-                if m.is_ignore() {
-                    return true;
-                }
-            }
-        }
-
-        false
+        self.git
+            .as_ref()
+            .is_some_and(|git| git.is_ignored(path, is_dir))
+            .then_some(Source::Git)
     }
 }
@@ -0,0 +1,179 @@
+use std::ops::{Add, AddAssign};
+use std::path::Path;
+
+use super::grep::looks_binary;
+use crate::commands::cat::syntax::{self, Grammar};
+
+/// Lines of a file (or a directory total) split the way tokei and cloc do:
+/// a line with any code is code, one with only comment text is a comment,
+/// and one with only whitespace is blank.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub code: u64,
+    pub comment: u64,
+    pub blank: u64,
+}
+
+impl LineCounts {
+    pub fn total(self) -> u64 {
+        self.code + self.comment + self.blank
+    }
+}
+
+impl Add for LineCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            code: self.code + other.code,
+            comment: self.comment + other.comment,
+            blank: self.blank + other.blank,
+        }
+    }
+}
+
+impl AddAssign for LineCounts {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Row name for files of no known language.
+pub const OTHER: &str = "Other";
+
+/// The language of `path`, by extension or by well-known file name. Comment
+/// syntax comes from the same grammars `dusk bat` highlights with.
+pub fn language_for(path: &Path) -> Option<&'static Grammar> {
+    syntax::for_path(path)
+}
+
+/// Classifies every line of `bytes`. Files of no known language count all
+/// non-blank lines as code; binary files count nothing.
+pub fn count(path: &Path, bytes: &[u8]) -> LineCounts {
+    if looks_binary(bytes) {
+        return LineCounts::default();
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let lang = language_for(path);
+    let mut counts = LineCounts::default();
+    // The closing token of the block comment a line starts inside.
+    let mut open: Option<&str> = None;
+    for line in text.lines() {
+        let (has_code, has_comment) = match lang {
+            Some(lang) => scan(lang, line, &mut open),
+            None => (!line.trim().is_empty(), false),
+        };
+        if has_code {
+            counts.code += 1;
+        } else if has_comment {
+            counts.comment += 1;
+        } else {
+            counts.blank += 1;
+        }
+    }
+    counts
+}
+
+/// Whether `line` holds code and whether it holds comment text, carrying
+/// an unclosed block comment over to the next line in `open`.
+fn scan(lang: &Grammar, line: &str, open: &mut Option<&'static str>) -> (bool, bool) {
+    let (mut code, mut comment) = (false, false);
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        if let Some(close) = *open {
+            comment = true;
+            match rest.find(close) {
+                Some(at) => {
+                    rest = &rest[at + close.len()..];
+                    *open = None;
+                }
+                None => break,
+            }
+        } else if let Some((start, close)) = lang
+            .block_comment
+            .iter()
+            .chain(lang.docstrings)
+            .find(|(s, _)| rest.starts_with(s))
+        {
+            comment = true;
+            rest = &rest[start.len()..];
+            *open = Some(close);
+        } else if lang
+            .line_comments
+            .iter()
+            .any(|token| rest.starts_with(token))
+        {
+            comment = true;
+            break;
+        } else if let Some(tail) = rest.strip_prefix('"') {
+            // Skip string literals so `"/*"` does not open a comment.
+            code = true;
+            rest = skip_string(tail);
+        } else {
+            let mut chars = rest.chars();
+            if !chars.next().is_some_and(char::is_whitespace) {
+                code = true;
+            }
+            rest = chars.as_str();
+        }
+    }
+    (code, comment)
+}
+
+/// What follows the closing quote of a string literal, or nothing if the
+/// string does not close on this line.
+fn skip_string(tail: &str) -> &str {
+    let mut escaped = false;
+    for (at, c) in tail.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return &tail[at + 1..],
+            _ => escaped = false,
+        }
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineCounts, count, language_for};
+    use std::path::Path;
+
+    fn counts(name: &str, text: &str) -> (u64, u64, u64) {
+        let LineCounts {
+            code,
+            comment,
+            blank,
+        } = count(Path::new(name), text.as_bytes());
+        (code, comment, blank)
+    }
+
+    #[test]
+    fn classifies_code_comment_and_blank_lines() {
+        let rust = "// header\n\nfn main() { // trailing\n    /* one */\n    /*\n\n     */ let x = \"/*\";\n    x\n}\n";
+        assert_eq!(counts("main.rs", rust), (4, 3, 2));
+
+        let python = "#!/usr/bin/env python\n\"\"\"Module\ndocs.\"\"\"\nimport os\n\n# done\n";
+        assert_eq!(counts("tool.py", python), (1, 4, 1));
+
+        let lua = "--[[ block\n]] print(1)\n-- line\n";
+        assert_eq!(counts("init.lua", lua), (1, 2, 0));
+
+        let ruby = "=begin\nnotes\n=end\nputs 1 # done\n";
+        assert_eq!(counts("task.rb", ruby), (1, 3, 0));
+
+        assert_eq!(counts("notes.xyz", "a\n\n  b\n"), (2, 0, 1));
+        assert_eq!(counts("blob.rs", "a\0b\n"), (0, 0, 0));
+    }
+
+    #[test]
+    fn finds_languages_by_extension_and_file_name() {
+        let name = |p: &str| language_for(Path::new(p)).map(|l| l.title);
+        assert_eq!(name("src/lib.RS"), Some("Rust"));
+        assert_eq!(name("Makefile"), Some("Makefile"));
+        assert_eq!(name("site.scss"), Some("SCSS"));
+        assert_eq!(name("icon.svg"), Some("XML"));
+        assert_eq!(name("docker/Dockerfile"), Some("Dockerfile"));
+        assert_eq!(name("LICENSE"), None);
+    }
+}
@@ -1,27 +1,39 @@
 mod analysis;
 mod config;
+mod gitstatus;
+mod grep;
 mod help;
 mod icons;
 mod ignore;
+mod loc;
+mod model;
 mod outputs;
 mod render;
-mod theme;
+mod tui;
+mod walk;
 
+use std::collections::HashSet;
 use std::ffi::OsString;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
+use crate::core::pager;
+use crate::core::settings;
 use crate::core::style::Style;
+use crate::core::theme::{self, Theme};
 use analysis::{
-    collect_duplicates, collect_stats, grouped_view, print_duplicates, print_fingerprint,
+    FingerprintRenderer, collect_duplicates, collect_stats, grouped_view, print_duplicates,
 };
-use config::{Config, Mode, SortMode};
+use config::{Config, Mode};
+use gitstatus::GitStatus;
+use grep::Grep;
 use ignore::IgnoreMatcher;
-use outputs::{print_json, print_markdown, print_prompt_dump};
-use render::{print_tree, walk_files};
-use theme::Theme;
+use model::TreeNode;
+use outputs::{JsonRenderer, MarkdownRenderer, PromptRenderer};
+use render::{Renderer, TextRenderer};
+use walk::{Tree, WalkOptions};
 
 pub fn run(args: &[OsString]) -> Result<(), String> {
-    let cfg = Config::parse(args)?;
+    let cfg = Config::parse(args, settings::current())?;
 
     if cfg.help {
         print!("{}", help::full_help(Some(cfg.theme.as_str())));
@@ -36,6 +48,15 @@ pub fn run(args: &[OsString]) -> Result<(), String> {
         return Err(format!("Directory not found: {}", cfg.target_dir.display()));
     }
 
+    if cfg.tui {
+        let root = cfg
+            .target_dir
+            .canonicalize()
+            .map_err(|err| format!("failed to open target directory: {err}"))?;
+        return tui::run(&cfg, &root);
+    }
+
+    let _pager = pager::start(cfg.paging);
     let mut runtime = Runtime::new(cfg)?;
     runtime.execute()
 }
@@ -45,6 +66,8 @@ struct Runtime {
     root: PathBuf,
     theme: Theme,
     ignore: IgnoreMatcher,
+    tree: Tree,
+    git: Option<GitStatus>,
 }
 
 impl Runtime {
@@ -53,78 +76,105 @@ impl Runtime {
             .target_dir
             .canonicalize()
             .map_err(|err| format!("failed to open target directory: {err}"))?;
-        let mut theme = Theme::resolve(&cfg.theme);
-        if cfg.prompt_mode || !Style::for_stdout().color {
-            theme = Theme::plain();
-        }
+        let theme = if cfg.prompt_mode || !Style::for_stdout().color {
+            theme::plain()
+        } else {
+            theme::resolve(Some(&cfg.theme))
+        };
         let ignore = IgnoreMatcher::new(&root, &cfg)?;
+        let grep = Grep::new(&cfg)?;
+        let changed = cfg
+            .changed_since
+            .as_deref()
+            .map(|rev| gitstatus::changed_since(&root, rev))
+            .transpose()?;
+        let opts = walk_options(&cfg, grep.as_ref(), changed.as_ref());
+        let tree = Tree::walk(&root, &cfg, &ignore, opts)?;
+        let git = if cfg.git_status {
+            GitStatus::load(&root, !cfg.use_gitignore, "dusk xtree --git-status")?
+        } else {
+            None
+        };
         Ok(Self {
             cfg,
             root,
             theme,
             ignore,
+            tree,
+            git,
         })
     }
 
     fn execute(&mut self) -> Result<(), String> {
         if self.cfg.group_by_ext {
-            grouped_view(&self.root, &self.cfg, &self.ignore, &self.theme)?;
+            grouped_view(&self.root, &self.tree, &self.theme);
             return Ok(());
         }
 
+        let view = TreeNode::build(&self.tree, &self.cfg, self.git.as_ref());
         match self.cfg.mode {
             Mode::Json => {
-                print_json(&self.root, &self.cfg, &self.ignore)?;
-                Ok(())
-            }
-            Mode::Markdown => {
-                print_markdown(&self.root, &self.cfg, &self.ignore)?;
-                Ok(())
+                let stats = (self.cfg.show_loc || self.cfg.show_stats)
+                    .then(|| collect_stats(&TreeNode::build_unlimited(&self.tree)));
+                JsonRenderer {
+                    stats: stats.as_ref(),
+                }
+                .render(&view)
             }
-            Mode::Prompt => print_prompt_dump(&self.root, &self.cfg, &self.ignore, &self.theme),
+            Mode::Markdown => MarkdownRenderer.render(&view),
+            Mode::Prompt => PromptRenderer { theme: &self.theme }.render(&view),
             Mode::Fingerprint => {
-                let stats = collect_stats(&self.root, &self.cfg, &self.ignore)?;
-                print_fingerprint(&self.root, &self.cfg, &self.theme, &self.ignore, &stats)?;
+                FingerprintRenderer { theme: &self.theme }.render(&view)?;
                 if self.cfg.find_dupes {
-                    let dupes = collect_duplicates(&self.root, &self.cfg, &self.ignore)?;
+                    let dupes = collect_duplicates(&self.tree);
                     print_duplicates(&dupes, &self.cfg, &self.theme);
                 }
                 Ok(())
             }
             Mode::Normal => {
                 let mut filtered_loc_summary: Option<(String, u64)> = None;
-                println!(
-                    "{}{}{}",
-                    self.theme.dir,
-                    self.root.display(),
-                    self.theme.reset
-                );
-                let tree = print_tree(&self.root, &self.cfg, &self.ignore, &self.theme)?;
+                let mut tree = TextRenderer::new(&self.cfg, &self.theme);
+                tree.render(&view)?;
                 if !self.cfg.no_report {
                     println!();
                     if self.cfg.dir_only {
                         println!(
                             "{}📁 {} directories{}",
-                            self.theme.meta, tree.dir_count, self.theme.reset
+                            self.theme.subtle, tree.dir_count, self.theme.reset
                         );
                     } else {
                         println!(
                             "{}📁 {} directories, 📄 {} files{}",
-                            self.theme.meta, tree.dir_count, tree.file_count, self.theme.reset
+                            self.theme.subtle, tree.dir_count, tree.file_count, self.theme.reset
+                        );
+                    }
+                    let hidden = self.ignore.hidden_counts();
+                    if !hidden.is_empty() {
+                        let parts = hidden
+                            .iter()
+                            .map(|(source, count)| format!("{count} by {}", source.label()))
+                            .collect::<Vec<_>>();
+                        println!(
+                            "{}🙈 hidden: {}{}",
+                            self.theme.subtle,
+                            parts.join(", "),
+                            self.theme.reset
                         );
                     }
                 }
 
                 if self.cfg.show_stats || self.cfg.show_loc {
-                    let stats = collect_stats(&self.root, &self.cfg, &self.ignore)?;
+                    // Stats cover the whole walk, not just the `-L` levels shown.
+                    let stats = collect_stats(&TreeNode::build_unlimited(&self.tree));
                     if self.cfg.show_stats {
                         analysis::print_stats(&stats, &self.theme);
                     } else {
                         println!();
-                        println!(
-                            "{}Total LOC: {}{}",
-                            self.theme.header, stats.total_loc, self.theme.reset
-                        );
+                        println!("{}", analysis::total_loc_line(&stats, &self.theme));
+                        println!();
+                        for line in analysis::loc_table(&stats, &self.theme) {
+                            println!("{line}");
+                        }
                     }
                     if !self.cfg.cat_exts.is_empty() {
                         let selected_loc = analysis::loc_for_extensions(&stats, &self.cfg.cat_exts);
@@ -134,32 +184,27 @@ impl Runtime {
                 }
 
                 if self.cfg.find_dupes {
-                    let dupes = collect_duplicates(&self.root, &self.cfg, &self.ignore)?;
+                    let dupes = collect_duplicates(&self.tree);
                     print_duplicates(&dupes, &self.cfg, &self.theme);
                 }
 
-                if self.cfg.grep_pattern.is_some()
-                    || !self.cfg.cat_exts.is_empty()
-                    || self.cfg.audit_mode
-                {
-                    let _ = walk_files(&self.root, &self.cfg, &self.ignore, |path| {
-                        if let Some(pattern) = &self.cfg.grep_pattern {
-                            render::print_grep(path, pattern, &self.theme);
-                        }
+                if !self.cfg.cat_exts.is_empty() || self.cfg.audit_mode {
+                    for entry in self.tree.files() {
+                        let path = entry.path.as_path();
                         if render::should_cat(path, &self.cfg) {
                             render::print_file_content(path, &self.cfg, &self.theme);
                         }
                         if self.cfg.audit_mode {
                             analysis::audit_file(path, &self.theme);
                         }
-                    });
+                    }
                 }
 
                 if let Some((label, loc)) = filtered_loc_summary {
                     println!();
                     println!(
                         "{}LOC for -c [{}]: {}{}",
-                        self.theme.header, label, loc, self.theme.reset
+                        self.theme.title, label, loc, self.theme.reset
                     );
                 }
                 Ok(())
@@ -168,6 +213,31 @@ impl Runtime {
     }
 }
 
-fn _sort_key(path: &Path, mode: SortMode) -> (i64, String) {
-    render::sort_key(path, mode)
+/// Every output format honours `-L`, so the walk can stop there (one level
+/// deeper for `--count`) unless an analysis, `--du`/`--min-size` totals,
+/// file filters that prune directories or `--group` need the whole tree.
+fn walk_options<'a>(
+    cfg: &Config,
+    grep: Option<&'a Grep>,
+    changed: Option<&'a HashSet<PathBuf>>,
+) -> WalkOptions<'a> {
+    let analysis = cfg.show_stats
+        || cfg.show_loc
+        || cfg.du.is_some()
+        || cfg.min_size.is_some()
+        || cfg.find_dupes
+        || cfg.audit_mode
+        || !cfg.cat_exts.is_empty()
+        || cfg.filters_files();
+    let tree_only = !cfg.group_by_ext && !analysis;
+    WalkOptions {
+        max_depth: cfg
+            .max_depth
+            .filter(|_| tree_only)
+            .map(|depth| depth + usize::from(cfg.show_file_count)),
+        lines: cfg.show_stats || cfg.show_loc || matches!(cfg.mode, Mode::Fingerprint),
+        digests: cfg.find_dupes,
+        grep,
+        changed,
+    }
 }
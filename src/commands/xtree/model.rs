@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::config::{Config, DuMode};
+use super::gitstatus::{GitStatus, Mark};
+use super::grep::GrepLine;
+use super::icons::{ICON_DIR, ICON_EXEC, ICON_LINK, file_icon};
+use super::loc::LineCounts;
+use super::walk::{Entry, Kind, Tree};
+
+/// The tree an output format renders: the walk with `-L`, `--focus` and
+/// `--sort` already applied, so every format shows the same entries in the
+/// same order.
+pub struct TreeNode {
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: Kind,
+    /// File size; for directories, the total of every file below, including
+    /// levels `-L` leaves out.
+    pub size: u64,
+    /// Bytes on disk, totalled like `size`.
+    pub disk: u64,
+    pub modified: Option<SystemTime>,
+    pub mode: u32,
+    /// Code/comment/blank lines, totalled like `size`. Zero unless the walk
+    /// counted lines.
+    pub loc: LineCounts,
+    pub icon: &'static str,
+    /// Regular files directly inside a directory, counted before `-L` cuts
+    /// its children off.
+    pub files: usize,
+    /// `--git-status` marker, rolled up for directories.
+    pub git: Option<Mark>,
+    /// `--grep` matching lines; for directories, the total below.
+    pub hits: usize,
+    /// `--grep` matches and context lines of a file.
+    pub grep: Vec<GrepLine>,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn build(tree: &Tree, cfg: &Config, git: Option<&GitStatus>) -> Self {
+        let min_size = cfg.min_size.map(|min| (min, cfg.du.unwrap_or_default()));
+        Self::from_entry(tree, 0, cfg.max_depth, min_size, git)
+    }
+
+    /// Same as [`TreeNode::build`] but ignoring `-L` and `--min-size`, for
+    /// analyses that always cover the whole tree.
+    pub fn build_unlimited(tree: &Tree) -> Self {
+        Self::from_entry(tree, 0, None, None, None)
+    }
+
+    fn from_entry(
+        tree: &Tree,
+        idx: usize,
+        levels: Option<usize>,
+        min_size: Option<(u64, DuMode)>,
+        git: Option<&GitStatus>,
+    ) -> Self {
+        let entry = &tree.entries[idx];
+        let children = if levels == Some(0) {
+            Vec::new()
+        } else {
+            entry
+                .children
+                .iter()
+                .filter(|&&c| {
+                    let child = &tree.entries[c];
+                    child.focused
+                        && min_size.is_none_or(|(min, du)| du.pick(child.size, child.disk) >= min)
+                })
+                .map(|&c| Self::from_entry(tree, c, levels.map(|l| l - 1), min_size, git))
+                .collect::<Vec<_>>()
+        };
+        Self {
+            name: entry.name.clone(),
+            path: entry.path.clone(),
+            kind: entry.kind,
+            size: entry.size,
+            disk: entry.disk,
+            modified: entry.modified,
+            mode: entry.mode,
+            loc: entry.loc,
+            icon: icon_for(entry),
+            files: entry
+                .children
+                .iter()
+                .filter(|&&c| tree.entries[c].is_file())
+                .count(),
+            git: git.and_then(|git| git.mark(&entry.path, entry.is_dir())),
+            hits: entry.hits,
+            grep: entry.grep.clone(),
+            children,
+        }
+    }
+
+    /// The size `--du` reports for this node.
+    pub fn usage(&self, du: DuMode) -> u64 {
+        du.pick(self.size, self.disk)
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == Kind::Dir
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == Kind::File
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.kind == Kind::Symlink
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.kind == Kind::File && self.mode & 0o111 != 0
+    }
+
+    /// Every node below this one with its depth (children are 1), depth
+    /// first.
+    pub fn descendants(&self) -> Vec<(usize, &TreeNode)> {
+        let mut out = Vec::new();
+        let mut stack = self
+            .children
+            .iter()
+            .rev()
+            .map(|c| (1, c))
+            .collect::<Vec<_>>();
+        while let Some((depth, node)) = stack.pop() {
+            out.push((depth, node));
+            stack.extend(node.children.iter().rev().map(|c| (depth + 1, c)));
+        }
+        out
+    }
+}
+
+fn icon_for(entry: &Entry) -> &'static str {
+    match entry.kind {
+        Kind::Dir => ICON_DIR,
+        Kind::Symlink => ICON_LINK,
+        _ if entry.is_executable() => ICON_EXEC,
+        _ => file_icon(&entry.path),
+    }
+}
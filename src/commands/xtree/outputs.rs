@@ -1,102 +1,156 @@
 use std::fs;
 use std::io::Write;
-use std::path::Path;
 
 use chrono::Local;
 
-use super::config::Config;
-use super::ignore::IgnoreMatcher;
-use super::render;
-use super::theme::Theme;
+use super::analysis::{Stats, languages_by_code};
+use super::loc::LineCounts;
+use super::model::TreeNode;
+use super::render::Renderer;
+use crate::core::format::human_size;
+use crate::core::theme::Theme;
 
-pub fn print_json(root: &Path, cfg: &Config, ignore: &IgnoreMatcher) -> Result<(), String> {
-    let node = render::json_node(root, cfg, ignore);
-    let payload = serde_json::json!({"root": node});
-    let txt = serde_json::to_string_pretty(&payload).map_err(|err| err.to_string())?;
-    println!("{txt}");
-    Ok(())
+/// `--json`: the model as nested objects under `root`. With `--loc` or
+/// `--stats`, every node gets a `loc` object and a `languages` table is
+/// added next to `root`.
+pub struct JsonRenderer<'a> {
+    pub stats: Option<&'a Stats>,
 }
 
-pub fn print_markdown(root: &Path, cfg: &Config, ignore: &IgnoreMatcher) -> Result<(), String> {
-    println!("# 📂 Directory Structure");
-    println!();
-    println!("**Path:** `{}`", root.display());
-    println!();
-    println!("**Generated:** {}", Local::now().to_rfc2822());
-    println!();
-    println!("---");
-    println!();
-
-    walk_md(root, root, cfg, ignore, "", 0)?;
+impl Renderer for JsonRenderer<'_> {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String> {
+        let mut payload = serde_json::json!({"root": json_node(root, self.stats.is_some())});
+        if let Some(stats) = self.stats {
+            let languages = languages_by_code(stats)
+                .into_iter()
+                .map(|(name, lang)| {
+                    let mut value = json_loc(lang.loc);
+                    value["name"] = name.into();
+                    value["files"] = lang.files.into();
+                    value
+                })
+                .collect::<Vec<_>>();
+            let mut total = json_loc(stats.total_loc);
+            total["files"] = stats.file_count.into();
+            payload["languages"] = languages.into();
+            payload["total"] = total;
+        }
+        let txt = serde_json::to_string_pretty(&payload).map_err(|err| err.to_string())?;
+        println!("{txt}");
+        Ok(())
+    }
+}
 
-    println!();
-    println!("---");
-    println!();
-    println!("_Generated by dusk xtree (pure rust)_");
-    Ok(())
+fn json_loc(loc: LineCounts) -> serde_json::Value {
+    serde_json::json!({
+        "lines": loc.total(),
+        "code": loc.code,
+        "comment": loc.comment,
+        "blank": loc.blank
+    })
 }
 
-fn walk_md(
-    root: &Path,
-    dir: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    prefix: &str,
-    depth: usize,
-) -> Result<(), String> {
-    if let Some(max_depth) = cfg.max_depth {
-        if depth > max_depth {
-            return Ok(());
-        }
+fn json_node(node: &TreeNode, with_loc: bool) -> serde_json::Value {
+    let mut value = if node.is_dir() {
+        let children = node
+            .children
+            .iter()
+            .map(|child| json_node(child, with_loc))
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "name": node.name,
+            "type": "directory",
+            "path": node.path.display().to_string(),
+            "children": children
+        })
+    } else {
+        serde_json::json!({
+            "name": node.name,
+            "type": "file",
+            "path": node.path.display().to_string(),
+            "size": node.size
+        })
+    };
+    if let Some(mark) = node.git {
+        value["git"] = format!("{}{}", mark.staged, mark.unstaged).into();
+    }
+    if with_loc {
+        value["loc"] = json_loc(node.loc);
+    }
+    if node.hits > 0 {
+        value["hits"] = node.hits.into();
+    }
+    if !node.grep.is_empty() {
+        let matches = node
+            .grep
+            .iter()
+            .map(|line| {
+                serde_json::json!({
+                    "line": line.number,
+                    "text": line.text,
+                    "match": line.is_match()
+                })
+            })
+            .collect::<Vec<_>>();
+        value["matches"] = matches.into();
     }
+    value
+}
 
-    let mut items = fs::read_dir(dir)
-        .map_err(|err| format!("failed reading {}: {err}", dir.display()))?
-        .filter_map(Result::ok)
-        .map(|e| e.path())
-        .filter(|p| !ignore.is_ignored(p, p.is_dir(), cfg.show_hidden))
-        .collect::<Vec<_>>();
-    items.sort();
+/// `--md`: a nested bullet list.
+pub struct MarkdownRenderer;
 
-    for item in items {
-        let mut line = render::md_line_for(&item);
-        if !prefix.is_empty() {
-            line = format!("{prefix}{line}");
-        }
-        println!("{line}");
-        if item.is_dir() {
-            walk_md(root, &item, cfg, ignore, &format!("{prefix}  "), depth + 1)?;
+impl Renderer for MarkdownRenderer {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String> {
+        println!("# 📂 Directory Structure");
+        println!();
+        println!("**Path:** `{}`", root.path.display());
+        println!();
+        println!("**Generated:** {}", Local::now().to_rfc2822());
+        println!();
+        println!("---");
+        println!();
+
+        for (depth, node) in root.descendants() {
+            let indent = "  ".repeat(depth - 1);
+            if node.is_dir() {
+                println!("{indent}- 📁 **{}/**", node.name);
+            } else {
+                println!("{indent}- 📄 `{}` _({})_", node.name, human_size(node.size));
+            }
         }
+
+        println!();
+        println!("---");
+        println!();
+        println!("_Generated by dusk xtree (pure rust)_");
+        Ok(())
     }
+}
 
-    let _ = root;
-    Ok(())
+/// `--prompt`: a flat path list plus totals, also saved to the temp dir.
+pub struct PromptRenderer<'a> {
+    pub theme: &'a Theme,
 }
 
-pub fn print_prompt_dump(
-    root: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    theme: &Theme,
-) -> Result<(), String> {
+impl Renderer for PromptRenderer<'_> {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String> {
+        print_prompt_dump(root, self.theme)
+    }
+}
+
+fn print_prompt_dump(root: &TreeNode, theme: &Theme) -> Result<(), String> {
     let prompt_path = std::env::temp_dir().join("tree_prompt.txt");
     let mut tree_lines = Vec::new();
     let mut dirs = 0usize;
     let mut files = 0usize;
-    let walker = walkdir::WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if path == root {
-            continue;
-        }
-        if path.is_dir() {
+    for (_, node) in root.descendants() {
+        if node.is_dir() {
             dirs += 1;
-        } else if path.is_file() {
+        } else if node.is_file() {
             files += 1;
         }
-        let rel = path.strip_prefix(root).ok().unwrap_or(path);
+        let rel = node.path.strip_prefix(&root.path).unwrap_or(&node.path);
         tree_lines.push(format!("- {}", rel.display()));
     }
 
@@ -121,7 +175,7 @@ pub fn print_prompt_dump(
     fs::write(&prompt_path, out.as_bytes()).map_err(|err| err.to_string())?;
     println!(
         "{}Prompt saved to {}{}",
-        theme.header,
+        theme.title,
         prompt_path.display(),
         theme.reset
     );
@@ -1,255 +1,260 @@
-use std::cmp::Reverse;
 use std::fs;
-use std::fs::Metadata;
-use std::io::ErrorKind;
 use std::io::{BufRead, BufReader};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use chrono::{DateTime, Local};
 
-use super::config::{Config, SortMode};
-use super::icons::{ICON_DIR, ICON_EXEC, ICON_LINK, file_icon};
-use super::ignore::IgnoreMatcher;
-use super::theme::{Theme, format_size};
+use super::config::Config;
+use super::gitstatus;
+use super::model::TreeNode;
+use crate::core::format::human_size;
+use crate::core::theme::Theme;
 
-pub struct TreeSummary {
-    pub dir_count: usize,
-    pub file_count: usize,
+/// One output format. Each renders the same [`TreeNode`], so filters and
+/// ordering are decided once when the model is built.
+pub trait Renderer {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String>;
 }
 
-pub fn print_tree(
-    root: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    theme: &Theme,
-) -> Result<TreeSummary, String> {
-    let mut summary = TreeSummary {
-        dir_count: 0,
-        file_count: 0,
-    };
-
-    render_dir(root, root, "", 0, cfg, ignore, theme, &mut summary)?;
-    Ok(summary)
+/// The default `tree`-style view. Counts what it printed for the report.
+pub struct TextRenderer<'a> {
+    cfg: &'a Config,
+    theme: &'a Theme,
+    pub dir_count: usize,
+    pub file_count: usize,
 }
 
-fn render_dir(
-    root: &Path,
-    dir: &Path,
-    prefix: &str,
-    depth: usize,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    theme: &Theme,
-    summary: &mut TreeSummary,
-) -> Result<(), String> {
-    if let Some(max_depth) = cfg.max_depth {
-        if depth >= max_depth {
-            return Ok(());
+impl<'a> TextRenderer<'a> {
+    pub fn new(cfg: &'a Config, theme: &'a Theme) -> Self {
+        Self {
+            cfg,
+            theme,
+            dir_count: 0,
+            file_count: 0,
         }
     }
 
-    let read_dir = match fs::read_dir(dir) {
-        Ok(rd) => rd,
-        Err(err) if err.kind() == ErrorKind::PermissionDenied => return Ok(()),
-        Err(err) => return Err(format!("failed reading {}: {err}", dir.display())),
-    };
+    fn render_dir(&mut self, dir: &TreeNode, prefix: &str) {
+        let total = dir.children.len();
+        for (pos, node) in dir.children.iter().enumerate() {
+            let is_last = pos + 1 == total;
+            let branch = if is_last { "└── " } else { "├── " };
+            let next_prefix = if is_last {
+                format!("{prefix}    ")
+            } else {
+                format!("{prefix}│   ")
+            };
 
-    let mut items = read_dir
-        .filter_map(Result::ok)
-        .map(|e| e.path())
-        .filter(|path| {
-            let is_dir = path.is_dir();
-            !ignore.is_ignored(path, is_dir, cfg.show_hidden)
-        })
-        .collect::<Vec<_>>();
+            self.print_single_item(node, dir, prefix, branch);
+            self.print_grep_lines(node, &next_prefix);
 
-    if !cfg.focus_exts.is_empty() {
-        items.retain(|path| {
-            if path.is_dir() {
-                dir_has_focus_ext(path, cfg, ignore)
-            } else {
-                has_focus_ext(path, cfg)
+            if node.is_dir() {
+                self.dir_count += 1;
+                self.render_dir(node, &next_prefix);
+            } else if node.is_file() || node.is_symlink() {
+                self.file_count += 1;
             }
-        });
+        }
     }
 
-    sort_entries(&mut items, cfg.sort_mode);
+    fn print_single_item(&self, node: &TreeNode, parent: &TreeNode, prefix: &str, branch: &str) {
+        let (cfg, theme) = (self.cfg, self.theme);
+        let name = node.name.as_str();
+        let path = node.path.as_path();
+
+        let mut info = String::new();
+        if cfg.show_info {
+            info.push_str(&format!(
+                "{}[{}]{} ",
+                theme.subtle,
+                metadata_str(node),
+                theme.reset
+            ));
+        }
+        if let Some(du) = cfg.du {
+            let (size, total) = (node.usage(du), parent.usage(du));
+            let share = if total == 0 {
+                0.0
+            } else {
+                size as f64 / total as f64
+            };
+            info.push_str(&format!(
+                "{}[{:>6} {:>3.0}% {}{}{}]{} ",
+                theme.size,
+                human_size(size),
+                share * 100.0,
+                theme.accent,
+                usage_bar(share, DU_BAR_WIDTH),
+                theme.size,
+                theme.reset
+            ));
+        } else if cfg.show_size && node.is_file() {
+            info.push_str(&format!(
+                "{}[{}]{} ",
+                theme.size,
+                human_size(node.size),
+                theme.reset
+            ));
+        }
+        if cfg.highlight_big && node.is_file() && node.size > cfg.big_threshold {
+            info.push_str(&format!("{}[LARGE]{} ", theme.warn, theme.reset));
+        }
 
-    let total = items.len();
-    for (idx, path) in items.iter().enumerate() {
-        let is_last = idx + 1 == total;
-        let branch = if is_last { "└── " } else { "├── " };
-        let next_prefix = if is_last {
-            format!("{prefix}    ")
+        let icon = if cfg.show_icons {
+            format!("{} ", node.icon)
         } else {
-            format!("{prefix}│   ")
+            String::new()
         };
 
-        let md = match fs::symlink_metadata(path) {
-            Ok(md) => md,
-            Err(_) => continue,
-        };
-        let is_symlink = md.file_type().is_symlink();
-
-        print_single_item(root, path, &md, prefix, branch, cfg, theme)?;
-
-        if md.is_dir() {
-            summary.dir_count += 1;
-            render_dir(
-                root,
-                path,
-                &next_prefix,
-                depth + 1,
-                cfg,
-                ignore,
-                theme,
-                summary,
-            )?;
-        } else if md.is_file() {
-            summary.file_count += 1;
-        } else if is_symlink {
-            summary.file_count += 1;
-        }
-    }
+        let git = node
+            .git
+            .map(|mark| format!("{} ", gitstatus::paint(mark, theme)))
+            .unwrap_or_default();
 
-    Ok(())
-}
-
-pub fn sort_key(path: &Path, mode: SortMode) -> (i64, String) {
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or_default()
-        .to_string();
-
-    let v = match mode {
-        SortMode::Name => 0,
-        SortMode::Size => fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0),
-        SortMode::Time => fs::metadata(path)
-            .and_then(|m| m.modified())
-            .ok()
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0),
-    };
+        let hits = if cfg.grep_pattern.is_some() {
+            let noun = if node.hits == 1 { "hit" } else { "hits" };
+            format!(" {}({} {noun}){}", theme.number, node.hits, theme.reset)
+        } else {
+            String::new()
+        };
 
-    (v, name)
-}
+        print!(
+            "{prefix}{}{}{}{}{}",
+            theme.subtle, branch, theme.reset, git, info
+        );
 
-fn sort_entries(items: &mut [PathBuf], mode: SortMode) {
-    match mode {
-        SortMode::Name => items.sort_by(|a, b| a.file_name().cmp(&b.file_name())),
-        SortMode::Size | SortMode::Time => {
-            items.sort_by_key(|p| Reverse(sort_key(p, mode).0));
+        if node.is_symlink() {
+            let target = fs::read_link(path)
+                .ok()
+                .map(|p| {
+                    if cfg.resolve_symlinks {
+                        fs::canonicalize(path).unwrap_or(p).display().to_string()
+                    } else {
+                        p.display().to_string()
+                    }
+                })
+                .unwrap_or_else(|| "<broken>".to_string());
+            println!(
+                "{}{}{} -> {}{}{}",
+                theme.symlink, icon, name, target, theme.reset, hits
+            );
+            return;
         }
-    }
-}
 
-fn print_single_item(
-    root: &Path,
-    path: &Path,
-    md: &Metadata,
-    prefix: &str,
-    branch: &str,
-    cfg: &Config,
-    theme: &Theme,
-) -> Result<(), String> {
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or_default();
-
-    let mut info = String::new();
-    if cfg.show_info {
-        info.push_str(&format!(
-            "{}[{}]{} ",
-            theme.meta,
-            metadata_str(md),
-            theme.reset
-        ));
-    }
-    if cfg.show_size && md.is_file() {
-        info.push_str(&format!(
-            "{}[{}]{} ",
-            theme.size,
-            format_size(md.len()),
-            theme.reset
-        ));
-    }
-    if cfg.highlight_big && md.is_file() && md.len() > cfg.big_threshold {
-        info.push_str(&format!("{}[LARGE]{} ", theme.warn, theme.reset));
-    }
+        if node.is_dir() {
+            let count = if cfg.show_file_count {
+                format!("{}[{} files]{} ", theme.number, node.files, theme.reset)
+            } else {
+                String::new()
+            };
+
+            if cfg.show_tests && is_test_name(name) {
+                println!(
+                    "{}{}{}{}/{}{}",
+                    count, theme.ok, icon, name, theme.reset, hits
+                );
+            } else {
+                println!(
+                    "{}{}{}{}/{}{}",
+                    count, theme.directory, icon, name, theme.reset, hits
+                );
+            }
+            return;
+        }
 
-    let mut icon = String::new();
-    if cfg.show_icons {
-        if md.is_dir() {
-            icon = format!("{ICON_DIR} ");
-        } else if md.file_type().is_symlink() {
-            icon = format!("{ICON_LINK} ");
-        } else if is_executable(md) {
-            icon = format!("{ICON_EXEC} ");
+        if cfg.show_tests && is_test_name(name) {
+            println!("{}{}{}{}{}", theme.ok, icon, name, theme.reset, hits);
+        } else if node.is_executable() {
+            println!(
+                "{}{}{}*{}{}",
+                theme.executable, icon, name, theme.reset, hits
+            );
         } else {
-            icon = format!("{} ", file_icon(path));
+            let color = file_category_color(path, theme);
+            println!("{}{}{}{}{}", color, icon, name, theme.reset, hits);
         }
     }
 
-    print!("{prefix}{}{}{}{}", theme.meta, branch, theme.reset, info);
-
-    if md.file_type().is_symlink() {
-        let target = fs::read_link(path)
-            .ok()
-            .map(|p| {
-                if cfg.resolve_symlinks {
-                    fs::canonicalize(path).unwrap_or(p).display().to_string()
-                } else {
-                    p.display().to_string()
-                }
-            })
-            .unwrap_or_else(|| "<broken>".to_string());
-        println!(
-            "{}{}{} -> {}{}",
-            theme.link, icon, name, target, theme.reset
-        );
-        return Ok(());
-    }
-
-    if md.is_dir() {
-        let count = if cfg.show_file_count {
-            format!(
-                "{}[{} files]{} ",
-                theme.count,
-                count_files(path),
-                theme.reset
-            )
-        } else {
-            String::new()
+    /// `--grep` results of a file, nested under it like children: `12:` for
+    /// a match, `13-` for context and `⋮` where lines were skipped.
+    fn print_grep_lines(&self, node: &TreeNode, prefix: &str) {
+        let theme = self.theme;
+        let Some(last) = node.grep.last() else {
+            return;
         };
-
-        if cfg.show_tests && is_test_name(name) {
+        let width = last.number.to_string().len();
+        let mut previous: Option<usize> = None;
+        for line in &node.grep {
+            if previous.is_some_and(|prev| line.number > prev + 1) {
+                println!("{prefix}{}{:>width$}{}", theme.subtle, "⋮", theme.reset);
+            }
+            previous = Some(line.number);
+            let sep = if line.is_match() { ':' } else { '-' };
+            let mut text = String::new();
+            let mut at = 0;
+            for span in &line.spans {
+                text.push_str(&line.text[at..span.start]);
+                text.push_str(&format!(
+                    "{}{}{}",
+                    theme.warn,
+                    &line.text[span.clone()],
+                    theme.reset
+                ));
+                at = span.end;
+            }
+            text.push_str(&line.text[at..]);
             println!(
-                "{}{}{}{}{}/{}",
-                count, theme.test, icon, name, "", theme.reset
+                "{prefix}{}{:>width$}{sep}{} {text}",
+                theme.number, line.number, theme.reset
             );
-        } else {
-            println!("{}{}{}{}/{}", count, theme.dir, icon, name, theme.reset);
         }
-        return Ok(());
     }
+}
 
-    if cfg.show_tests && is_test_name(name) {
-        println!("{}{}{}{}", theme.test, icon, name, theme.reset);
-    } else if is_executable(md) {
-        println!("{}{}{}*{}", theme.exec, icon, name, theme.reset);
-    } else {
-        let color = file_category_color(path, theme);
-        println!("{}{}{}{}", color, icon, name, theme.reset);
+impl Renderer for TextRenderer<'_> {
+    fn render(&mut self, root: &TreeNode) -> Result<(), String> {
+        let total = self
+            .cfg
+            .du
+            .map(|du| {
+                format!(
+                    " {}[{}]{}",
+                    self.theme.size,
+                    human_size(root.usage(du)),
+                    self.theme.reset
+                )
+            })
+            .unwrap_or_default();
+        println!(
+            "{}{}{}{}",
+            self.theme.directory,
+            root.path.display(),
+            self.theme.reset,
+            total
+        );
+        self.render_dir(root, "");
+        Ok(())
     }
+}
 
-    let _ = root;
-    Ok(())
+/// Cells in a `--du` bar; each cell is split into eighths.
+const DU_BAR_WIDTH: usize = 10;
+
+/// A fixed-width bar filled to `share` (0.0-1.0) with partial blocks.
+fn usage_bar(share: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let eighths = (share.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    let partial = PARTIAL[eighths % 8];
+    if partial != ' ' {
+        bar.push(partial);
+    }
+    let filled = bar.chars().count();
+    bar.push_str(&" ".repeat(width - filled));
+    bar
 }
 
-fn file_category_color<'a>(path: &Path, theme: &'a Theme) -> &'a str {
+pub(super) fn file_category_color<'a>(path: &Path, theme: &'a Theme) -> &'a str {
     let name = path
         .file_name()
         .and_then(|n| n.to_str())
@@ -289,7 +294,7 @@ fn file_category_color<'a>(path: &Path, theme: &'a Theme) -> &'a str {
             | "fish"
             | "sql"
     ) {
-        return theme.content; // source code / scripts
+        return theme.accent; // source code / scripts
     }
 
     if matches!(
@@ -298,21 +303,21 @@ fn file_category_color<'a>(path: &Path, theme: &'a Theme) -> &'a str {
     ) || name.starts_with(".env")
         || name == "dockerfile"
     {
-        return theme.meta; // config
+        return theme.subtle; // config
     }
 
     if matches!(
         ext.as_str(),
         "md" | "markdown" | "txt" | "rst" | "org" | "pdf" | "doc" | "docx"
     ) {
-        return theme.header; // docs
+        return theme.title; // docs
     }
 
     if matches!(
         ext.as_str(),
         "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "ico"
     ) {
-        return theme.count; // images/assets
+        return theme.number; // images/assets
     }
 
     if matches!(
@@ -333,25 +338,20 @@ fn file_category_color<'a>(path: &Path, theme: &'a Theme) -> &'a str {
         ext.as_str(),
         "csv" | "tsv" | "parquet" | "db" | "sqlite" | "sqlite3"
     ) {
-        return theme.dir; // data files
+        return theme.directory; // data files
     }
 
     theme.file
 }
 
-fn metadata_str(md: &Metadata) -> String {
-    let perm = md.permissions();
+fn metadata_str(node: &TreeNode) -> String {
     #[cfg(unix)]
-    let mode = {
-        use std::os::unix::fs::PermissionsExt;
-        format!("{:o}", perm.mode() & 0o777)
-    };
+    let mode = format!("{:o}", node.mode & 0o777);
     #[cfg(not(unix))]
     let mode = "---".to_string();
 
-    let modified = md
-        .modified()
-        .ok()
+    let modified = node
+        .modified
         .map(|t| {
             let dt: DateTime<Local> = t.into();
             dt.format("%Y-%m-%d %H:%M").to_string()
@@ -361,16 +361,6 @@ fn metadata_str(md: &Metadata) -> String {
     format!("{mode} {modified}")
 }
 
-fn count_files(dir: &Path) -> usize {
-    fs::read_dir(dir)
-        .map(|rd| {
-            rd.filter_map(Result::ok)
-                .filter(|e| e.path().is_file())
-                .count()
-        })
-        .unwrap_or(0)
-}
-
 pub fn is_test_name(name: &str) -> bool {
     name.contains("_test.")
         || name.contains(".test.")
@@ -379,28 +369,6 @@ pub fn is_test_name(name: &str) -> bool {
         || name.contains(".spec.")
 }
 
-pub fn has_focus_ext(path: &Path, cfg: &Config) -> bool {
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or_default()
-        .to_ascii_lowercase();
-    cfg.focus_exts.iter().any(|e| *e == ext)
-}
-
-fn dir_has_focus_ext(dir: &Path, cfg: &Config, ignore: &IgnoreMatcher) -> bool {
-    let walker = walkdir::WalkDir::new(dir)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.is_file() && has_focus_ext(path, cfg) {
-            return true;
-        }
-    }
-    false
-}
-
 pub fn should_cat(path: &Path, cfg: &Config) -> bool {
     if cfg.cat_exts.is_empty() {
         return false;
@@ -413,42 +381,6 @@ pub fn should_cat(path: &Path, cfg: &Config) -> bool {
     cfg.cat_exts.iter().any(|x| x == &ext)
 }
 
-pub fn print_grep(path: &Path, pattern: &str, theme: &Theme) {
-    if !path.is_file() {
-        return;
-    }
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return,
-    };
-    let reader = BufReader::new(file);
-    let mut matches = Vec::new();
-
-    for (idx, line) in reader.lines().enumerate() {
-        if let Ok(line) = line {
-            if line.contains(pattern) {
-                matches.push(format!("{}:{}", idx + 1, line));
-                if matches.len() >= 5 {
-                    break;
-                }
-            }
-        }
-    }
-
-    if !matches.is_empty() {
-        println!(
-            "{}    ╭── matches in {} ──{}",
-            theme.content,
-            path.display(),
-            theme.reset
-        );
-        for m in matches {
-            println!("{}    │ {}{}", theme.content, m, theme.reset);
-        }
-        println!("{}    ╰────────────────{}", theme.content, theme.reset);
-    }
-}
-
 pub fn print_file_content(path: &Path, cfg: &Config, theme: &Theme) {
     if !path.is_file() {
         return;
@@ -460,7 +392,7 @@ pub fn print_file_content(path: &Path, cfg: &Config, theme: &Theme) {
 
     println!(
         "{}    ╭── content of {} ──{}",
-        theme.content,
+        theme.accent,
         path.display(),
         theme.reset
     );
@@ -471,99 +403,8 @@ pub fn print_file_content(path: &Path, cfg: &Config, theme: &Theme) {
             break;
         }
         if let Ok(line) = line {
-            println!("{}    │ {}{}", theme.content, line, theme.reset);
-        }
-    }
-    println!("{}    ╰────────────────────{}", theme.content, theme.reset);
-}
-
-pub fn walk_files<F>(
-    root: &Path,
-    cfg: &Config,
-    ignore: &IgnoreMatcher,
-    mut f: F,
-) -> Result<(), String>
-where
-    F: FnMut(&Path),
-{
-    let walker = walkdir::WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !ignore.is_ignored(e.path(), e.file_type().is_dir(), cfg.show_hidden));
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
-        if path.is_file() {
-            f(path);
-        }
-    }
-    Ok(())
-}
-
-fn is_executable(md: &Metadata) -> bool {
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        md.permissions().mode() & 0o111 != 0
-    }
-    #[cfg(not(unix))]
-    {
-        false
-    }
-}
-
-pub fn md_line_for(path: &Path) -> String {
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or_default();
-    let md = fs::metadata(path).ok();
-    let size = md
-        .as_ref()
-        .map(|m| format_size(m.len()))
-        .unwrap_or_else(|| "0B".to_string());
-
-    if path.is_dir() {
-        format!("- 📁 **{name}/**")
-    } else {
-        format!("- 📄 `{name}` _({size})_")
-    }
-}
-
-pub fn json_node(path: &Path, cfg: &Config, ignore: &IgnoreMatcher) -> serde_json::Value {
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .map(str::to_string)
-        .unwrap_or_else(|| path.to_string_lossy().to_string());
-
-    if path.is_dir() {
-        let mut children = Vec::new();
-        if cfg.max_depth != Some(0) {
-            let mut items = fs::read_dir(path)
-                .ok()
-                .into_iter()
-                .flatten()
-                .filter_map(Result::ok)
-                .map(|e| e.path())
-                .filter(|p| !ignore.is_ignored(p, p.is_dir(), cfg.show_hidden))
-                .collect::<Vec<_>>();
-            sort_entries(&mut items, cfg.sort_mode);
-            for item in items {
-                children.push(json_node(&item, cfg, ignore));
-            }
+            println!("{}    │ {}{}", theme.accent, line, theme.reset);
         }
-        serde_json::json!({
-            "name": name,
-            "type": "directory",
-            "path": path.display().to_string(),
-            "children": children
-        })
-    } else {
-        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
-        serde_json::json!({
-            "name": name,
-            "type": "file",
-            "path": path.display().to_string(),
-            "size": size
-        })
     }
+    println!("{}    ╰────────────────────{}", theme.accent, theme.reset);
 }
@@ -0,0 +1,718 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crossterm::cursor::MoveTo;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEventKind};
+use crossterm::queue;
+use crossterm::style::Print;
+use crossterm::terminal::{self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate};
+use unicode_width::UnicodeWidthChar;
+
+use super::analysis::{collect_stats, stats_lines};
+use super::config::Config;
+use super::gitstatus::changed_since;
+use super::ignore::IgnoreMatcher;
+use super::model::TreeNode;
+use super::render::file_category_color;
+use super::walk::{Tree, WalkOptions};
+use crate::commands::cat;
+use crate::core::format::human_size;
+use crate::core::style::Style;
+use crate::core::terminal::TerminalGuard;
+use crate::core::theme::{self, Theme};
+
+const REVERSE: &str = "\x1b[7m";
+/// Below this width the preview pane is dropped.
+const PREVIEW_MIN_WIDTH: usize = 80;
+
+struct Browser {
+    /// The command-line config without `-L`; the toggles below override it.
+    cfg: Config,
+    root_path: PathBuf,
+    style: Style,
+    theme: Theme,
+    root: TreeNode,
+    /// Entries the ignore rules hid in the last walk.
+    hidden: usize,
+    show_hidden: bool,
+    show_ignored: bool,
+    /// Whether the walk counted lines; turned on the first time `s` is used.
+    with_lines: bool,
+    expanded: HashSet<PathBuf>,
+    cursor: usize,
+    scroll: usize,
+    filter: String,
+    editing: bool,
+    preview_top: usize,
+    stats: Option<Vec<String>>,
+    message: Option<String>,
+    body_rows: usize,
+}
+
+/// `dusk xtree --tui`: a collapsible tree with a preview pane. `-L` sets how
+/// many levels start expanded.
+pub fn run(cfg: &Config, root: &Path) -> Result<(), String> {
+    if !atty::is(atty::Stream::Stdout) {
+        return Err("--tui needs an interactive terminal".to_string());
+    }
+    let style = Style::for_stdout();
+    let theme = if style.color {
+        theme::resolve(Some(&cfg.theme))
+    } else {
+        theme::plain()
+    };
+    let mut browser = Browser::new(cfg, root, style, theme)?;
+
+    let _guard = TerminalGuard::enter()?;
+    let mut dirty = true;
+    loop {
+        if dirty {
+            browser.render()?;
+            dirty = false;
+        }
+        if !event::poll(Duration::from_millis(120)).map_err(|e| e.to_string())? {
+            continue;
+        }
+        match event::read().map_err(|e| e.to_string())? {
+            Event::Key(key) => {
+                if !browser.handle_key(key)? {
+                    break;
+                }
+                dirty = true;
+            }
+            Event::Mouse(m) => match m.kind {
+                MouseEventKind::ScrollUp => {
+                    browser.move_by(-3);
+                    dirty = true;
+                }
+                MouseEventKind::ScrollDown => {
+                    browser.move_by(3);
+                    dirty = true;
+                }
+                _ => {}
+            },
+            Event::Resize(_, _) => dirty = true,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+impl Browser {
+    fn new(cfg: &Config, root: &Path, style: Style, theme: Theme) -> Result<Self, String> {
+        let mut cfg = cfg.clone();
+        let levels = cfg.max_depth.take().unwrap_or(1);
+        let (tree, hidden) = load(root, &cfg, false)?;
+
+        let mut expanded = HashSet::new();
+        for (depth, node) in tree.descendants() {
+            if node.is_dir() && depth < levels {
+                expanded.insert(node.path.clone());
+            }
+        }
+        Ok(Self {
+            show_hidden: cfg.show_hidden,
+            cfg,
+            root_path: root.to_path_buf(),
+            style,
+            theme,
+            root: tree,
+            hidden,
+            show_ignored: false,
+            with_lines: false,
+            expanded,
+            cursor: 0,
+            scroll: 0,
+            filter: String::new(),
+            editing: false,
+            preview_top: 0,
+            stats: None,
+            message: None,
+            body_rows: 1,
+        })
+    }
+
+    /// Walks again with the current toggles, keeping the selection when the
+    /// selected entry is still there.
+    fn reload(&mut self) -> Result<(), String> {
+        let selected = self.selected().map(|node| node.path.clone());
+        let mut cfg = self.cfg.clone();
+        cfg.show_hidden = self.show_hidden;
+        if self.show_ignored {
+            cfg.use_gitignore = false;
+            cfg.use_default_ignores = false;
+            cfg.use_treeignore = false;
+        }
+        (self.root, self.hidden) = load(&self.root_path, &cfg, self.with_lines)?;
+        if let Some(path) = selected {
+            let rows = visible_rows(&self.root, &self.expanded, &self.filter);
+            if let Some(pos) = rows.iter().position(|(_, node)| node.path == path) {
+                self.cursor = pos;
+            }
+        }
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&TreeNode> {
+        visible_rows(&self.root, &self.expanded, &self.filter)
+            .get(self.cursor)
+            .map(|(_, node)| *node)
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let count = visible_rows(&self.root, &self.expanded, &self.filter).len();
+        let cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(count.saturating_sub(1));
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.preview_top = 0;
+        }
+    }
+
+    /// Returns `false` when the browser should close.
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool, String> {
+        if self.editing {
+            self.handle_filter_key(key);
+            return Ok(true);
+        }
+        if self.stats.take().is_some() {
+            return Ok(true);
+        }
+        self.message = None;
+
+        let rows = visible_rows(&self.root, &self.expanded, &self.filter);
+        let current = rows
+            .get(self.cursor)
+            .map(|(depth, node)| (*depth, node.path.clone(), node.is_dir()));
+        let parent = current
+            .as_ref()
+            .and_then(|(depth, ..)| rows[..self.cursor].iter().rposition(|(d, _)| d < depth));
+        let has_children = rows
+            .get(self.cursor + 1)
+            .zip(current.as_ref())
+            .is_some_and(|((next, _), (depth, ..))| next > depth);
+
+        let page = self.body_rows.max(1) as isize;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('q') => return Ok(false),
+            KeyCode::Char('c') if ctrl => return Ok(false),
+            KeyCode::Esc if self.filter.is_empty() => return Ok(false),
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.cursor = 0;
+            }
+            KeyCode::Char('d') if ctrl => self.move_by(page / 2),
+            KeyCode::Char('u') if ctrl => self.move_by(-page / 2),
+            KeyCode::Char('j') | KeyCode::Down => self.move_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_by(-1),
+            KeyCode::PageDown => self.move_by(page),
+            KeyCode::PageUp => self.move_by(-page),
+            KeyCode::Char('g') | KeyCode::Home => self.move_by(-(self.cursor as isize)),
+            KeyCode::Char('G') | KeyCode::End => self.move_by(isize::MAX / 2),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                if let Some((_, path, true)) = current {
+                    if self.expanded.contains(&path) || !self.filter.is_empty() {
+                        if has_children {
+                            self.move_by(1);
+                        }
+                    } else {
+                        self.expanded.insert(path);
+                    }
+                }
+            }
+            KeyCode::Char('h') | KeyCode::Left => match current {
+                Some((_, path, true))
+                    if self.filter.is_empty() && self.expanded.contains(&path) =>
+                {
+                    self.expanded.remove(&path);
+                }
+                _ => {
+                    if let Some(parent) = parent {
+                        self.cursor = parent;
+                        self.preview_top = 0;
+                    }
+                }
+            },
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Char('.') => {
+                self.show_hidden = !self.show_hidden;
+                self.reload()?;
+                self.message = Some(format!("hidden files {}", on_off(self.show_hidden)));
+            }
+            KeyCode::Char('i') => {
+                self.show_ignored = !self.show_ignored;
+                self.reload()?;
+                self.message = Some(format!("ignored files {}", on_off(self.show_ignored)));
+            }
+            KeyCode::Char('s') => {
+                self.show_stats(current.map(|(_, path, is_dir)| (path, is_dir)))?
+            }
+            KeyCode::Char('y') => {
+                if let Some((_, path, _)) = current {
+                    copy_to_clipboard(&path.display().to_string())?;
+                    self.message = Some(format!("copied {}", path.display()));
+                }
+            }
+            KeyCode::Char('J') => self.preview_top += 1,
+            KeyCode::Char('K') => self.preview_top = self.preview_top.saturating_sub(1),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.editing = false;
+            }
+            KeyCode::Enter => self.editing = false,
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        self.cursor = 0;
+        self.scroll = 0;
+        self.preview_top = 0;
+    }
+
+    /// `--stats` for the selected directory, or the one holding the
+    /// selected file.
+    fn show_stats(&mut self, current: Option<(PathBuf, bool)>) -> Result<(), String> {
+        if !self.with_lines {
+            self.with_lines = true;
+            self.reload()?;
+        }
+        let target = match current {
+            Some((path, true)) => path,
+            Some((path, false)) => path
+                .parent()
+                .map_or_else(|| self.root_path.clone(), Path::to_path_buf),
+            None => self.root_path.clone(),
+        };
+        let Some(node) = find(&self.root, &target) else {
+            return Ok(());
+        };
+        let mut lines = vec![self.style.paint(
+            self.theme.directory,
+            format!("{} (any key closes)", target.display()),
+        )];
+        lines.extend(stats_lines(&collect_stats(node), &self.theme));
+        self.stats = Some(lines);
+        Ok(())
+    }
+
+    fn render(&mut self) -> Result<(), String> {
+        let (w, h) = terminal::size().map_err(|e| e.to_string())?;
+        let (w, h) = (w as usize, h as usize);
+        self.body_rows = h.saturating_sub(2).max(1);
+
+        let rows = visible_rows(&self.root, &self.expanded, &self.filter);
+        self.cursor = self.cursor.min(rows.len().saturating_sub(1));
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.body_rows {
+            self.scroll = self.cursor + 1 - self.body_rows;
+        }
+
+        let split = w >= PREVIEW_MIN_WIDTH;
+        let left_w = if split { (w * 2 / 5).max(30) } else { w };
+        let right_w = w.saturating_sub(left_w + 3);
+        let side = match (&self.stats, rows.get(self.cursor)) {
+            (Some(stats), _) => stats.clone(),
+            (None, Some((_, node))) if split => self.preview(node, right_w),
+            _ => Vec::new(),
+        };
+
+        let (style, theme) = (&self.style, &self.theme);
+        let mut out = io::stdout();
+        queue!(
+            out,
+            BeginSynchronizedUpdate,
+            MoveTo(0, 0),
+            Clear(ClearType::All)
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut header = format!("{}  {} shown", self.root_path.display(), rows.len());
+        if self.hidden > 0 {
+            header.push_str(&format!(" · {} hidden", self.hidden));
+        }
+        if !self.filter.is_empty() {
+            header.push_str(&format!(" · filter: {}", self.filter));
+        }
+        draw_line(&mut out, 0, &style.paint(theme.title, fit(&header, w)))?;
+
+        for row in 0..self.body_rows {
+            let idx = self.scroll + row;
+            let left = match rows.get(idx) {
+                Some((depth, node)) => self.row_line(*depth, node, left_w, idx == self.cursor),
+                None => " ".repeat(left_w),
+            };
+            let line = if !split && self.stats.is_some() {
+                side.get(row).cloned().unwrap_or_default()
+            } else if split {
+                let right = side.get(row).map(String::as_str).unwrap_or_default();
+                format!("{left}{}{right}", style.paint(theme.subtle, " │ "))
+            } else {
+                left
+            };
+            draw_line(&mut out, (row + 1) as u16, &line)?;
+        }
+
+        let status = if self.editing {
+            format!("/{}", self.filter)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else {
+            "j/k move  h/l fold  / filter  . hidden  i ignored  s stats  y copy  J/K preview  q quit"
+                .to_string()
+        };
+        let status_color = if self.editing {
+            theme.accent
+        } else {
+            theme.subtle
+        };
+        draw_line(
+            &mut out,
+            h.saturating_sub(1) as u16,
+            &style.paint(status_color, fit(&status, w)),
+        )?;
+
+        queue!(out, EndSynchronizedUpdate).map_err(|e| e.to_string())?;
+        out.flush().map_err(|e| e.to_string())
+    }
+
+    fn row_line(&self, depth: usize, node: &TreeNode, width: usize, selected: bool) -> String {
+        let (style, theme) = (&self.style, &self.theme);
+        let arrow = match node.is_dir() {
+            true if self.expanded.contains(&node.path) || !self.filter.is_empty() => "▾ ",
+            true => "▸ ",
+            false => "  ",
+        };
+        let icon = if style.icons && self.cfg.show_icons {
+            format!("{} ", node.icon)
+        } else {
+            String::new()
+        };
+        let suffix = if node.is_dir() { "/" } else { "" };
+        let size = human_size(node.size);
+        let label = format!(
+            "{}{}{arrow}{icon}{}{suffix}",
+            if selected { "▌" } else { " " },
+            "  ".repeat(depth - 1),
+            node.name
+        );
+        let label = fit(&label, width.saturating_sub(size.len() + 1));
+        if selected {
+            return style.paint(REVERSE, format!("{label} {size}"));
+        }
+        let color = if node.is_dir() {
+            theme.directory
+        } else if node.is_symlink() {
+            theme.symlink
+        } else if node.is_executable() {
+            theme.executable
+        } else {
+            file_category_color(&node.path, theme)
+        };
+        format!(
+            "{} {}",
+            style.paint(color, label),
+            style.paint(theme.size, size)
+        )
+    }
+
+    /// The right pane: highlighted file content, a directory summary or a
+    /// symlink target.
+    fn preview(&self, node: &TreeNode, width: usize) -> Vec<String> {
+        let (style, theme) = (&self.style, &self.theme);
+        if node.is_dir() {
+            let mut lines = vec![style.paint(
+                theme.subtle,
+                format!(
+                    "{} entries · {}",
+                    node.children.len(),
+                    human_size(node.size)
+                ),
+            )];
+            lines.extend(node.children.iter().skip(self.preview_top).map(|child| {
+                let suffix = if child.is_dir() { "/" } else { "" };
+                fit(&format!("{}{suffix}", child.name), width)
+            }));
+            return lines;
+        }
+        if node.is_symlink() {
+            let target = fs::read_link(&node.path)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "<broken>".to_string());
+            return vec![style.paint(theme.symlink, format!("-> {target}"))];
+        }
+        // Opening a FIFO blocks until a writer shows up, and devices can do
+        // worse; only regular files are read.
+        if !node.is_file() {
+            return vec![style.paint(
+                theme.subtle,
+                format!("special file ({})", special_kind(node.mode)),
+            )];
+        }
+        cat::preview(
+            &node.path,
+            self.preview_top,
+            self.body_rows,
+            width,
+            style,
+            theme,
+        )
+    }
+}
+
+/// What a `Kind::Other` entry is, from the `st_mode` type bits.
+fn special_kind(mode: u32) -> &'static str {
+    match mode & 0o170000 {
+        0o010000 => "fifo",
+        0o140000 => "socket",
+        0o020000 => "character device",
+        0o060000 => "block device",
+        _ => "unknown type",
+    }
+}
+
+fn load(root: &Path, cfg: &Config, with_lines: bool) -> Result<(TreeNode, usize), String> {
+    let ignore = IgnoreMatcher::new(root, cfg)?;
+    let changed = cfg
+        .changed_since
+        .as_deref()
+        .map(|rev| changed_since(root, rev))
+        .transpose()?;
+    let opts = WalkOptions {
+        max_depth: None,
+        lines: with_lines,
+        digests: false,
+        grep: None,
+        changed: changed.as_ref(),
+    };
+    let tree = Tree::walk(root, cfg, &ignore, opts)?;
+    let hidden = ignore.hidden_counts().iter().map(|(_, count)| count).sum();
+    Ok((TreeNode::build(&tree, cfg, None), hidden))
+}
+
+/// Rows to draw, with their depth: children of expanded directories, or,
+/// while filtering, every fuzzy match plus the directories leading to it.
+fn visible_rows<'a>(
+    root: &'a TreeNode,
+    expanded: &HashSet<PathBuf>,
+    filter: &str,
+) -> Vec<(usize, &'a TreeNode)> {
+    fn walk<'a>(
+        dir: &'a TreeNode,
+        depth: usize,
+        expanded: &HashSet<PathBuf>,
+        filter: &str,
+        out: &mut Vec<(usize, &'a TreeNode)>,
+    ) {
+        for child in &dir.children {
+            let mark = out.len();
+            out.push((depth, child));
+            if child.is_dir() && (!filter.is_empty() || expanded.contains(&child.path)) {
+                walk(child, depth + 1, expanded, filter, out);
+            }
+            if !filter.is_empty() && out.len() == mark + 1 && !fuzzy_match(&child.name, filter) {
+                out.truncate(mark);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(root, 1, expanded, filter, &mut out);
+    out
+}
+
+/// Case-insensitive subsequence match, so `mdrs` finds `model.rs`.
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut chars = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| chars.any(|c| c == q))
+}
+
+fn find<'a>(node: &'a TreeNode, path: &Path) -> Option<&'a TreeNode> {
+    if node.path == path {
+        return Some(node);
+    }
+    node.children
+        .iter()
+        .filter(|child| path.starts_with(&child.path))
+        .find_map(|child| find(child, path))
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "shown" } else { "hidden" }
+}
+
+/// Puts `text` on the terminal's clipboard with OSC 52, which also works
+/// over SSH.
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+        .and_then(|_| out.flush())
+        .map_err(|e| e.to_string())
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Truncates (with `…`) or pads `s` to exactly `width` columns.
+fn fit(s: &str, width: usize) -> String {
+    let char_width = |ch: char| UnicodeWidthChar::width(ch).unwrap_or(0);
+    let total = s.chars().map(char_width).sum::<usize>();
+    let (mut out, mut col) = (String::new(), 0);
+    if total <= width {
+        out.push_str(s);
+        col = total;
+    } else if width > 0 {
+        for ch in s.chars() {
+            if col + char_width(ch) > width - 1 {
+                break;
+            }
+            out.push(ch);
+            col += char_width(ch);
+        }
+        out.push('…');
+        col += 1;
+    }
+    out.push_str(&" ".repeat(width.saturating_sub(col)));
+    out
+}
+
+fn draw_line(out: &mut io::Stdout, y: u16, line: &str) -> Result<(), String> {
+    queue!(
+        out,
+        MoveTo(0, y),
+        Clear(ClearType::CurrentLine),
+        Print(line)
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, fit, fuzzy_match, visible_rows};
+    use crate::commands::xtree::loc::LineCounts;
+    use crate::commands::xtree::model::TreeNode;
+    use crate::commands::xtree::walk::Kind;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+
+    fn node(path: &str, children: Vec<TreeNode>) -> TreeNode {
+        let path = PathBuf::from(path);
+        TreeNode {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            kind: if children.is_empty() {
+                Kind::File
+            } else {
+                Kind::Dir
+            },
+            path,
+            size: 0,
+            disk: 0,
+            modified: None,
+            mode: 0,
+            loc: LineCounts::default(),
+            icon: "",
+            files: 0,
+            git: None,
+            hits: 0,
+            grep: Vec::new(),
+            children,
+        }
+    }
+
+    fn names(rows: &[(usize, &TreeNode)]) -> Vec<String> {
+        rows.iter()
+            .map(|(depth, node)| format!("{depth}:{}", node.name))
+            .collect()
+    }
+
+    #[test]
+    fn rows_follow_expansion_and_filter() {
+        let root = node(
+            "/r",
+            vec![
+                node(
+                    "/r/src",
+                    vec![
+                        node("/r/src/model.rs", vec![]),
+                        node("/r/src/main.rs", vec![]),
+                    ],
+                ),
+                node("/r/README.md", vec![]),
+            ],
+        );
+        let mut expanded = HashSet::new();
+        assert_eq!(
+            names(&visible_rows(&root, &expanded, "")),
+            ["1:src", "1:README.md"]
+        );
+
+        expanded.insert(Path::new("/r/src").to_path_buf());
+        assert_eq!(
+            names(&visible_rows(&root, &expanded, "")),
+            ["1:src", "2:model.rs", "2:main.rs", "1:README.md"]
+        );
+
+        // Filtering opens every directory that leads to a match.
+        assert_eq!(
+            names(&visible_rows(&root, &HashSet::new(), "mdrs")),
+            ["1:src", "2:model.rs"]
+        );
+    }
+
+    #[test]
+    fn fuzzy_matches_subsequences_ignoring_case() {
+        assert!(fuzzy_match("model.rs", "mdrs"));
+        assert!(fuzzy_match("README.md", "readme"));
+        assert!(!fuzzy_match("main.rs", "mdrs"));
+    }
+
+    #[test]
+    fn encodes_base64_with_padding() {
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"/tmp/a b"), "L3RtcC9hIGI=");
+    }
+
+    #[test]
+    fn fits_text_to_a_width() {
+        assert_eq!(fit("abc", 5), "abc  ");
+        assert_eq!(fit("abcdef", 4), "abc…");
+    }
+}
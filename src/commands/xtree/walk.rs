@@ -0,0 +1,318 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::SystemTime;
+
+use ignore::{WalkBuilder, WalkState};
+
+use super::config::{Config, SortMode};
+use super::grep::{Grep, GrepLine};
+use super::ignore::IgnoreMatcher;
+use super::loc::{self, LineCounts};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Dir,
+    File,
+    Symlink,
+    Other,
+}
+
+/// One walked entry. Metadata is taken from `lstat`, so symlinks are never
+/// followed.
+pub struct Entry {
+    pub path: PathBuf,
+    pub name: String,
+    pub depth: usize,
+    pub kind: Kind,
+    /// Apparent size; for directories, the total of every focused file below.
+    pub size: u64,
+    /// Bytes allocated on disk (`st_blocks`), totalled like `size`.
+    pub disk: u64,
+    pub modified: Option<SystemTime>,
+    pub mode: u32,
+    /// Code/comment/blank lines, only filled when the walk was asked for
+    /// LOC; totalled like `size`.
+    pub loc: LineCounts,
+    /// Content hash, only filled when the walk was asked for duplicates.
+    pub digest: Option<String>,
+    /// `--grep` matches and their context, for files.
+    pub grep: Vec<GrepLine>,
+    /// Matching lines; for directories, the total of every focused file below.
+    pub hits: usize,
+    /// Whether this file, or any file below this directory, passes the file
+    /// filters (`--focus`, `--grep`, `--since`, ...). Always true without
+    /// them.
+    pub focused: bool,
+    /// Child indices, sorted by `--sort`.
+    pub children: Vec<usize>,
+}
+
+impl Entry {
+    pub fn is_dir(&self) -> bool {
+        self.kind == Kind::Dir
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == Kind::File
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.kind == Kind::File && self.mode & 0o111 != 0
+    }
+}
+
+/// What the walk collects besides metadata; reading file contents on the
+/// walker threads keeps `--loc`/`--dupes`/`--grep` from needing a second pass.
+#[derive(Clone, Copy, Default)]
+pub struct WalkOptions<'a> {
+    pub max_depth: Option<usize>,
+    pub lines: bool,
+    pub digests: bool,
+    pub grep: Option<&'a Grep>,
+    /// `--changed-since`: the files git reports as changed.
+    pub changed: Option<&'a HashSet<PathBuf>>,
+}
+
+/// Every entry below the root, collected by one parallel walk. Entry 0 is the
+/// root; the rest are in depth-first order with siblings sorted by `--sort`,
+/// so output does not depend on thread scheduling.
+pub struct Tree {
+    pub entries: Vec<Entry>,
+}
+
+impl Tree {
+    pub fn walk(
+        root: &Path,
+        cfg: &Config,
+        ignore: &IgnoreMatcher,
+        opts: WalkOptions,
+    ) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel();
+        WalkBuilder::new(root)
+            .standard_filters(false)
+            .follow_links(false)
+            .max_depth(opts.max_depth)
+            .build_parallel()
+            .run(|| {
+                let tx = tx.clone();
+                Box::new(move |result| {
+                    let Ok(dent) = result else {
+                        return WalkState::Continue;
+                    };
+                    let is_dir = dent.file_type().is_some_and(|t| t.is_dir());
+                    if ignore.is_ignored(dent.path(), is_dir, cfg.show_hidden) {
+                        return WalkState::Skip;
+                    }
+                    let Ok(md) = fs::symlink_metadata(dent.path()) else {
+                        return WalkState::Continue;
+                    };
+                    let depth = dent.depth();
+                    let entry = Entry::new(dent.into_path(), depth, &md, opts);
+                    if tx.send(entry).is_err() {
+                        return WalkState::Quit;
+                    }
+                    WalkState::Continue
+                })
+            });
+        drop(tx);
+        ignore.check()?;
+
+        let mut entries = rx.into_iter().collect::<Vec<_>>();
+        // Component-wise path order is a depth-first preorder.
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        if entries.first().is_none_or(|e| e.depth != 0) {
+            return Err(format!("failed reading {}", root.display()));
+        }
+
+        let mut stack: Vec<usize> = Vec::new();
+        for idx in 0..entries.len() {
+            let depth = entries[idx].depth;
+            while stack.last().is_some_and(|&top| entries[top].depth >= depth) {
+                stack.pop();
+            }
+            if let Some(&parent) = stack.last() {
+                entries[parent].children.push(idx);
+            }
+            stack.push(idx);
+        }
+
+        let mut tree = Self { entries };
+        if cfg.filters_files() {
+            tree.mark_focus(cfg, opts);
+        }
+        tree.sum_totals();
+        tree.sort_children(cfg);
+        Ok(tree)
+    }
+
+    /// Indices of every entry below the root, depth first.
+    pub fn preorder(&self) -> Vec<usize> {
+        let mut out = Vec::with_capacity(self.entries.len());
+        let mut stack = self.entries[0]
+            .children
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(idx) = stack.pop() {
+            out.push(idx);
+            stack.extend(self.entries[idx].children.iter().rev());
+        }
+        out
+    }
+
+    /// Regular files below the root, depth first.
+    pub fn files(&self) -> impl Iterator<Item = &Entry> {
+        self.preorder()
+            .into_iter()
+            .map(|idx| &self.entries[idx])
+            .filter(|e| e.is_file())
+    }
+
+    /// Marks the files `--focus`, `--grep`, `--since`/`--before` and
+    /// `--changed-since` all keep, and the directories holding one.
+    fn mark_focus(&mut self, cfg: &Config, opts: WalkOptions) {
+        // Children always come after their parent, so a reverse pass sees
+        // every child before the directory that holds it.
+        for idx in (0..self.entries.len()).rev() {
+            let entry = &self.entries[idx];
+            let focused = if entry.is_dir() {
+                entry.children.iter().any(|&c| self.entries[c].focused)
+            } else {
+                (cfg.focus_exts.is_empty() || has_focus_ext(&entry.path, cfg))
+                    && (opts.grep.is_none() || entry.hits > 0)
+                    && in_time_window(entry.modified, cfg)
+                    && opts
+                        .changed
+                        .is_none_or(|changed| changed.contains(&entry.path))
+            };
+            self.entries[idx].focused = focused;
+        }
+    }
+
+    fn sum_totals(&mut self) {
+        for idx in (0..self.entries.len()).rev() {
+            if !self.entries[idx].is_dir() {
+                continue;
+            }
+            let (size, disk, loc, hits) = self.entries[idx]
+                .children
+                .iter()
+                .map(|&c| &self.entries[c])
+                .filter(|c| c.focused)
+                .fold((0, 0, LineCounts::default(), 0), |(s, d, l, h), c| {
+                    (s + c.size, d + c.disk, l + c.loc, h + c.hits)
+                });
+            let entry = &mut self.entries[idx];
+            (entry.size, entry.disk, entry.loc, entry.hits) = (size, disk, loc, hits);
+        }
+    }
+
+    /// `--sort size` orders directories by their totals, in the `--du`
+    /// measure when one is chosen.
+    fn sort_children(&mut self, cfg: &Config) {
+        let du = cfg.du.unwrap_or_default();
+        for idx in 0..self.entries.len() {
+            let mut children = std::mem::take(&mut self.entries[idx].children);
+            let entries = &self.entries;
+            children.sort_by(|&a, &b| entries[a].name.cmp(&entries[b].name));
+            match cfg.sort_mode {
+                SortMode::Name => {}
+                SortMode::Size => {
+                    children.sort_by_key(|&c| Reverse(du.pick(entries[c].size, entries[c].disk)))
+                }
+                SortMode::Time => children.sort_by_key(|&c| Reverse(entries[c].modified)),
+            }
+            self.entries[idx].children = children;
+        }
+    }
+}
+
+impl Entry {
+    fn new(path: PathBuf, depth: usize, md: &Metadata, opts: WalkOptions) -> Self {
+        let file_type = md.file_type();
+        let kind = if file_type.is_symlink() {
+            Kind::Symlink
+        } else if file_type.is_dir() {
+            Kind::Dir
+        } else if file_type.is_file() {
+            Kind::File
+        } else {
+            Kind::Other
+        };
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            md.permissions().mode()
+        };
+        #[cfg(not(unix))]
+        let mode = 0;
+        #[cfg(unix)]
+        let disk = {
+            use std::os::unix::fs::MetadataExt;
+            md.blocks() * 512
+        };
+        #[cfg(not(unix))]
+        let disk = md.len();
+
+        let reads = opts.lines || opts.digests || opts.grep.is_some();
+        let (loc, digest, grep) = if kind == Kind::File && reads {
+            match fs::read(&path) {
+                Ok(bytes) => (
+                    if opts.lines {
+                        loc::count(&path, &bytes)
+                    } else {
+                        LineCounts::default()
+                    },
+                    opts.digests.then(|| format!("{:x}", md5::compute(&bytes))),
+                    opts.grep.map(|g| g.search(&bytes)).unwrap_or_default(),
+                ),
+                Err(_) => (LineCounts::default(), None, Vec::new()),
+            }
+        } else {
+            (LineCounts::default(), None, Vec::new())
+        };
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        Self {
+            depth,
+            name,
+            kind,
+            size: md.len(),
+            disk,
+            modified: md.modified().ok(),
+            mode,
+            loc,
+            digest,
+            hits: grep.iter().filter(|line| line.is_match()).count(),
+            grep,
+            focused: true,
+            children: Vec::new(),
+            path,
+        }
+    }
+}
+
+fn in_time_window(modified: Option<SystemTime>, cfg: &Config) -> bool {
+    if cfg.since.is_none() && cfg.before.is_none() {
+        return true;
+    }
+    modified.is_some_and(|time| {
+        cfg.since.is_none_or(|since| time >= since) && cfg.before.is_none_or(|before| time < before)
+    })
+}
+
+pub fn has_focus_ext(path: &Path, cfg: &Config) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    cfg.focus_exts.contains(&ext)
+}
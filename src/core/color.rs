@@ -0,0 +1,296 @@
+use std::env;
+use std::sync::OnceLock;
+
+use crate::core::settings;
+
+/// How many colors the terminal can show. Theme colors are RGB and are
+/// downsampled to this depth when a theme is rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    pub fn parse(value: &str) -> Option<Option<Self>> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Some(None),
+            "16" | "ansi" => Some(Some(Self::Ansi16)),
+            "256" => Some(Some(Self::Ansi256)),
+            "truecolor" | "24bit" => Some(Some(Self::TrueColor)),
+            _ => None,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Self::Ansi16 => 0,
+            Self::Ansi256 => 1,
+            Self::TrueColor => 2,
+        }
+    }
+}
+
+/// Foreground color stored as RGB plus a bold flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub bold: bool,
+}
+
+impl Color {
+    pub const fn hex(rgb: u32) -> Self {
+        Self {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Color of a 256-color palette index (xterm defaults for 0-15).
+    pub fn from_ansi256(idx: u8) -> Self {
+        let (r, g, b) = ansi256_rgb(idx);
+        Self {
+            r,
+            g,
+            b,
+            bold: false,
+        }
+    }
+
+    /// Escape sequence for this color at `depth`.
+    pub fn sgr(self, depth: ColorDepth) -> String {
+        let params = match depth {
+            ColorDepth::TrueColor => format!("38;2;{};{};{}", self.r, self.g, self.b),
+            ColorDepth::Ansi256 => format!("38;5;{}", nearest_256(self.rgb())),
+            ColorDepth::Ansi16 => basic_code(nearest_16(self.rgb())).to_string(),
+        };
+        if self.bold {
+            format!("\x1b[1;{params}m")
+        } else {
+            format!("\x1b[{params}m")
+        }
+    }
+
+    fn rgb(self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+}
+
+const BASE16: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn ansi256_rgb(idx: u8) -> (u8, u8, u8) {
+    match idx {
+        0..=15 => BASE16[idx as usize],
+        16..=231 => {
+            let i = idx - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (idx - 232);
+            (v, v, v)
+        }
+    }
+}
+
+fn basic_code(idx: u8) -> u8 {
+    if idx < 8 { 30 + idx } else { 90 + idx - 8 }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    // Weighted toward green, roughly following perceived brightness.
+    2 * d(a.0, b.0) + 4 * d(a.1, b.1) + 3 * d(a.2, b.2)
+}
+
+/// Nearest entry of the 6x6x6 cube or the gray ramp (16-255). The base 16
+/// are skipped because terminals remap them.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| {
+        (0..6u8)
+            .min_by_key(|&i| (i32::from(CUBE[i as usize]) - i32::from(v)).abs())
+            .unwrap_or(0)
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let avg = (u32::from(rgb.0) + u32::from(rgb.1) + u32::from(rgb.2)) / 3;
+    let gray = 232 + (avg.saturating_sub(3) / 10).min(23) as u8;
+    if distance(ansi256_rgb(gray), rgb) < distance(ansi256_rgb(cube), rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> u8 {
+    (0..16u8)
+        .min_by_key(|&idx| distance(BASE16[idx as usize], rgb))
+        .unwrap_or(7)
+}
+
+static OVERRIDE: OnceLock<Option<ColorDepth>> = OnceLock::new();
+static DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// Records `--color-depth` (`None` for `auto`); must run before the first
+/// `depth()` call.
+pub fn set_override(depth: Option<ColorDepth>) {
+    let _ = OVERRIDE.set(depth);
+}
+
+/// Effective depth: `--color-depth` > `DUSK_COLOR_DEPTH` > `[global]
+/// color-depth` > detection from `COLORTERM`/`TERM`.
+pub fn depth() -> ColorDepth {
+    *DEPTH.get_or_init(|| {
+        let configured = match OVERRIDE.get() {
+            Some(depth) => *depth,
+            None => env::var("DUSK_COLOR_DEPTH")
+                .ok()
+                .or_else(|| {
+                    settings::current()
+                        .get_str("global", "color-depth")
+                        .map(str::to_string)
+                })
+                .as_deref()
+                .and_then(ColorDepth::parse)
+                .flatten(),
+        };
+        if let Some(depth) = configured {
+            return depth;
+        }
+        detect(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    })
+}
+
+pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    if let Some(ct) = colorterm
+        && matches!(ct.to_ascii_lowercase().as_str(), "truecolor" | "24bit")
+    {
+        return ColorDepth::TrueColor;
+    }
+    let term = term.unwrap_or_default().to_ascii_lowercase();
+    if term.contains("direct") || term.contains("truecolor") || term.contains("24bit") {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else if term.is_empty()
+        || matches!(term.as_str(), "linux" | "ansi" | "cons25" | "dumb")
+        || term.starts_with("vt")
+    {
+        ColorDepth::Ansi16
+    } else {
+        ColorDepth::Ansi256
+    }
+}
+
+/// Whether `--color-depth` was given, and its value (`None` for `auto`).
+pub type DepthFlag = Option<Option<ColorDepth>>;
+
+/// Removes leading `--color-depth <v>` / `--color-depth=<v>` options from
+/// `args`, stopping at the first other argument (the command).
+pub fn take_depth_flag(args: Vec<String>) -> Result<(Vec<String>, DepthFlag), String> {
+    let mut depth = None;
+    let mut it = args.into_iter().peekable();
+    while let Some(arg) =
+        it.next_if(|arg| arg == "--color-depth" || arg.starts_with("--color-depth="))
+    {
+        let value = match arg.strip_prefix("--color-depth=") {
+            Some(v) => v.to_string(),
+            None => it
+                .next()
+                .ok_or_else(|| "--color-depth requires auto|16|256|truecolor".to_string())?,
+        };
+        depth =
+            Some(ColorDepth::parse(&value).ok_or_else(|| {
+                "--color-depth supports: auto | 16 | 256 | truecolor".to_string()
+            })?);
+    }
+    Ok((it.collect(), depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, ColorDepth, detect, take_depth_flag};
+
+    #[test]
+    fn detects_depth_from_colorterm_and_term() {
+        assert_eq!(
+            detect(Some("truecolor"), Some("xterm")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(detect(None, Some("tmux-256color")), ColorDepth::Ansi256);
+        assert_eq!(detect(None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn downsamples_rgb_per_depth() {
+        let blue = Color::hex(0x61afef);
+        assert_eq!(blue.sgr(ColorDepth::TrueColor), "\x1b[38;2;97;175;239m");
+        assert_eq!(blue.sgr(ColorDepth::Ansi256), "\x1b[38;5;75m");
+        assert_eq!(blue.sgr(ColorDepth::Ansi16), "\x1b[36m");
+        assert_eq!(
+            Color::hex(0x5c5cff).bold().sgr(ColorDepth::Ansi16),
+            "\x1b[1;94m"
+        );
+    }
+
+    #[test]
+    fn palette_indices_round_trip_through_256() {
+        for idx in 16..=255u8 {
+            assert_eq!(
+                Color::from_ansi256(idx).sgr(ColorDepth::Ansi256),
+                format!("\x1b[38;5;{idx}m")
+            );
+        }
+    }
+
+    #[test]
+    fn strips_only_leading_color_depth_flags() {
+        let args = ["--color-depth", "16", "cat", "--color-depth"]
+            .map(String::from)
+            .to_vec();
+        let (rest, depth) = take_depth_flag(args).expect("parse");
+        assert_eq!(rest, ["cat", "--color-depth"]);
+        assert_eq!(depth, Some(Some(ColorDepth::Ansi16)));
+
+        let args = ["ls", "--color-depth=16"].map(String::from).to_vec();
+        let (rest, depth) = take_depth_flag(args).expect("parse");
+        assert_eq!(rest, ["ls", "--color-depth=16"]);
+        assert_eq!(depth, None);
+        assert!(take_depth_flag(vec!["--color-depth=lots".to_string()]).is_err());
+    }
+}
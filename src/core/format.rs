@@ -0,0 +1,120 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+/// Human-readable byte count shared by ls and xtree (`512B`, `4.00K`, `12.3M`).
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "P"];
+    let mut value = size as f64;
+    let mut idx = 0usize;
+    while value >= 1024.0 && idx + 1 < UNITS.len() {
+        value /= 1024.0;
+        idx += 1;
+    }
+
+    if idx == 0 {
+        format!("{size}B")
+    } else if value >= 10.0 {
+        format!("{value:.1}{}", UNITS[idx])
+    } else {
+        format!("{value:.2}{}", UNITS[idx])
+    }
+}
+
+/// Parses a size like `512`, `10K`, `1.5M` or `2GiB` (binary units, as
+/// printed by [`human_size`]) into bytes.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let unit = unit
+        .strip_suffix("IB")
+        .or_else(|| unit.strip_suffix('B'))
+        .unwrap_or(&unit);
+    let power = match unit {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        "P" => 5,
+        _ => return None,
+    };
+    Some((number * 1024f64.powi(power)) as u64)
+}
+
+/// Parses a point in time: an age like `30m`, `2d` or `1w` before `now`
+/// (units `s`, `m`, `h`, `d`, `w`), or a local date `2026-01-01` with an
+/// optional `12:30` time.
+pub fn parse_time(value: &str, now: SystemTime) -> Option<SystemTime> {
+    let value = value.trim();
+    if let Some(unit) = value.chars().last().filter(char::is_ascii_alphabetic)
+        && let Ok(count) = value[..value.len() - 1].parse::<u64>()
+    {
+        let seconds = match unit.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        return now.checked_sub(Duration::from_secs(count.checked_mul(seconds)?));
+    }
+
+    let datetime = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .ok()?;
+    let local = Local.from_local_datetime(&datetime).earliest()?;
+    Some(local.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{human_size, parse_size, parse_time};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn scales_units() {
+        assert_eq!(human_size(512), "512B");
+        assert_eq!(human_size(4096), "4.00K");
+        assert_eq!(human_size(12 * 1024 * 1024 + 300 * 1024), "12.3M");
+    }
+
+    #[test]
+    fn parses_sizes_with_units() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("10K"), Some(10 * 1024));
+        assert_eq!(parse_size("1.5m"), Some(1536 * 1024));
+        assert_eq!(parse_size("2GiB"), Some(2 << 30));
+        assert_eq!(parse_size("3 kb"), Some(3 * 1024));
+        assert_eq!(parse_size("12Q"), None);
+        assert_eq!(parse_size("M"), None);
+    }
+
+    #[test]
+    fn parses_ages_and_local_dates() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(30 * 24 * 60 * 60);
+        let ago = |secs: u64| Some(now - Duration::from_secs(secs));
+        assert_eq!(parse_time("90s", now), ago(90));
+        assert_eq!(parse_time("2d", now), ago(2 * 24 * 60 * 60));
+        assert_eq!(parse_time("1W", now), ago(7 * 24 * 60 * 60));
+        assert_eq!(parse_time("3y", now), None);
+        assert_eq!(parse_time("soon", now), None);
+
+        let day = parse_time("2026-01-01", now).expect("date");
+        let noon = parse_time("2026-01-01 12:00", now).expect("datetime");
+        assert_eq!(
+            noon.duration_since(day).ok(),
+            Some(Duration::from_secs(12 * 60 * 60))
+        );
+        assert_eq!(parse_time("2026-01-01T12:00", now), Some(noon));
+        assert_eq!(parse_time("2026-13-01", now), None);
+    }
+}
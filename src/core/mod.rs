@@ -1,5 +1,11 @@
+pub mod color;
 pub mod devicons;
+pub mod format;
 pub mod icons;
+pub mod pager;
+pub mod paths;
 pub mod process;
+pub mod settings;
 pub mod style;
+pub mod terminal;
 pub mod theme;
@@ -0,0 +1,289 @@
+use std::env;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+use atty::Stream;
+
+use crate::core::process;
+use crate::core::settings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paging {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Paging {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// Value of `--paging <mode>` / `--paging=<mode>`.
+    pub fn from_flag(value: &str) -> Result<Self, String> {
+        Self::parse(value).ok_or_else(|| "--paging supports: auto | always | never".to_string())
+    }
+}
+
+/// Strips `--paging <mode>` / `--paging=<mode>` from `args` for commands whose
+/// parsers treat bare words as positional values (theme names).
+pub fn take_flag(args: &[String]) -> Result<(Vec<String>, Option<Paging>), String> {
+    let mut out = Vec::with_capacity(args.len());
+    let mut paging = None;
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        if arg == "--paging" {
+            let value = it
+                .next()
+                .ok_or_else(|| "--paging requires auto|always|never".to_string())?;
+            paging = Some(Paging::from_flag(value)?);
+        } else if let Some(value) = arg.strip_prefix("--paging=") {
+            paging = Some(Paging::from_flag(value)?);
+        } else {
+            out.push(arg.clone());
+        }
+    }
+    Ok((out, paging))
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// True while stdout is redirected into a pager that writes to the terminal,
+/// so `Style::for_stdout` keeps colors and icons on.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Pager command: `DUSK_PAGER` > `[global] pager` > `PAGER` > `less -RFX`.
+/// An empty value (or `cat`) disables paging.
+fn command() -> Option<Vec<String>> {
+    let configured = env::var("DUSK_PAGER")
+        .ok()
+        .or_else(|| {
+            settings::current()
+                .get_str("global", "pager")
+                .filter(|v| *v != "-")
+                .map(str::to_string)
+        })
+        .or_else(|| env::var("PAGER").ok())
+        .unwrap_or_else(|| "less".to_string());
+    let mut argv: Vec<String> = configured.split_whitespace().map(str::to_string).collect();
+    let program = argv.first()?;
+    if program == "cat" || !process::command_exists(program) {
+        return None;
+    }
+    // Plain `less` would show escape codes literally and page short output.
+    if argv.len() == 1 && program.rsplit('/').next() == Some("less") {
+        argv.push("-RFX".to_string());
+    }
+    Some(argv)
+}
+
+/// Redirects stdout into the pager until dropped. In `auto` mode output is
+/// held back until it outgrows the screen, so short runs print directly.
+pub struct Pager {
+    #[cfg(unix)]
+    saved_stdout: libc::c_int,
+    worker: Option<JoinHandle<()>>,
+}
+
+/// Starts paging for the rest of the command. `cli` is the `--paging` flag;
+/// without it `[global] paging` (default `auto`) decides. Returns `None` when
+/// stdout is not a terminal, paging is off, or no pager is installed.
+pub fn start(cli: Option<Paging>) -> Option<Pager> {
+    let mode = cli
+        .or_else(|| {
+            settings::current()
+                .get_str("global", "paging")
+                .and_then(Paging::parse)
+        })
+        .unwrap_or(Paging::Auto);
+    if mode == Paging::Never || !atty::is(Stream::Stdout) {
+        return None;
+    }
+    let argv = command()?;
+    // `(columns, rows)` the output must outgrow before the pager starts.
+    let threshold = match mode {
+        Paging::Always => None,
+        _ => {
+            let (w, h) = crossterm::terminal::size().unwrap_or((80, 24));
+            Some((usize::from(w.max(1)), usize::from(h.saturating_sub(1))))
+        }
+    };
+    redirect(argv, threshold)
+}
+
+#[cfg(unix)]
+fn redirect(argv: Vec<String>, threshold: Option<(usize, usize)>) -> Option<Pager> {
+    use std::fs::File;
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    let _ = io::stdout().flush();
+    let mut fds = [0 as libc::c_int; 2];
+    // SAFETY: plain fd bookkeeping; every descriptor created here is either
+    // handed to an owner below or closed on the error paths.
+    let (saved, terminal) = unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            return None;
+        }
+        let saved = libc::dup(libc::STDOUT_FILENO);
+        let terminal = if saved >= 0 { libc::dup(saved) } else { -1 };
+        if saved < 0 || terminal < 0 || libc::dup2(fds[1], libc::STDOUT_FILENO) < 0 {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+            for fd in [saved, terminal] {
+                if fd >= 0 {
+                    libc::close(fd);
+                }
+            }
+            return None;
+        }
+        libc::close(fds[1]);
+        (saved, terminal)
+    };
+    // SAFETY: `fds[0]` is the read end created above and `terminal` a valid
+    // duplicate of the real stdout; both are owned only here and handed to
+    // the worker (and the pager).
+    let (mut input, terminal) =
+        unsafe { (File::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(terminal)) };
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    let worker = std::thread::spawn(move || {
+        let mut held = Vec::new();
+        let mut chunk = [0u8; 8192];
+        let overflowed = loop {
+            match input.read(&mut chunk) {
+                Ok(0) | Err(_) => break false,
+                Ok(n) => held.extend_from_slice(&chunk[..n]),
+            }
+            if threshold.is_none_or(|(width, height)| screen_rows(&held, width) > height) {
+                break true;
+            }
+        };
+        let mut terminal = File::from(terminal);
+        if !overflowed {
+            let _ = terminal.write_all(&held);
+            return;
+        }
+        // Without a pager the output still goes to the terminal.
+        let Some((mut child, mut pipe)) = spawn_pager(&argv, &terminal) else {
+            let _ = terminal.write_all(&held);
+            let _ = io::copy(&mut input, &mut terminal);
+            return;
+        };
+        if pipe.write_all(&held).is_ok() {
+            let _ = io::copy(&mut input, &mut pipe);
+        }
+        drop(pipe);
+        // The pager may quit early; keep draining so the command never blocks
+        // on a full pipe.
+        let _ = io::copy(&mut input, &mut io::sink());
+        let _ = child.wait();
+    });
+
+    Some(Pager {
+        saved_stdout: saved,
+        worker: Some(worker),
+    })
+}
+
+/// Starts `argv` writing to `terminal`, with a pipe to its stdin; `None` if
+/// either cannot be set up.
+#[cfg(unix)]
+fn spawn_pager(
+    argv: &[String],
+    terminal: &std::fs::File,
+) -> Option<(std::process::Child, std::process::ChildStdin)> {
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(&argv[0])
+        .args(&argv[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(terminal.try_clone().ok()?))
+        .spawn()
+        .ok()?;
+    match child.stdin.take() {
+        Some(pipe) => Some((child, pipe)),
+        None => {
+            let _ = child.kill();
+            let _ = child.wait();
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn redirect(_argv: Vec<String>, _threshold: Option<(usize, usize)>) -> Option<Pager> {
+    None
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        #[cfg(unix)]
+        // SAFETY: restores the descriptor saved in `redirect`; this closes the
+        // last write end of the pipe so the worker sees EOF.
+        unsafe {
+            libc::dup2(self.saved_stdout, libc::STDOUT_FILENO);
+            libc::close(self.saved_stdout);
+        }
+        ACTIVE.store(false, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Terminal rows needed for `bytes`, counting wrapped lines and ignoring ANSI
+/// escape sequences.
+fn screen_rows(bytes: &[u8], width: usize) -> usize {
+    let text = String::from_utf8_lossy(bytes);
+    text.split_terminator('\n')
+        .map(|line| visible_width(line).div_ceil(width).max(1))
+        .sum()
+}
+
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Paging, screen_rows, take_flag};
+
+    #[test]
+    fn strips_paging_flag_in_both_forms() {
+        let args = ["log", "--paging", "never", "nord"].map(String::from);
+        let (rest, paging) = take_flag(&args).expect("parse");
+        assert_eq!(rest, ["log", "nord"]);
+        assert_eq!(paging, Some(Paging::Never));
+
+        let args = ["--paging=always".to_string()];
+        assert_eq!(take_flag(&args).expect("parse").1, Some(Paging::Always));
+        assert!(take_flag(&["--paging=sometimes".to_string()]).is_err());
+    }
+
+    #[test]
+    fn counts_wrapped_rows_without_escape_codes() {
+        assert_eq!(screen_rows(b"\x1b[31mabc\x1b[0m\n\nabcdefgh\n", 4), 4);
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+pub fn home_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        if let Ok(profile) = std::env::var("USERPROFILE")
+            && !profile.trim().is_empty()
+        {
+            return Some(PathBuf::from(profile));
+        }
+    }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.trim().is_empty()
+    {
+        return Some(PathBuf::from(home));
+    }
+    None
+}
+
+/// Root of dusk's user configuration: `$XDG_CONFIG_HOME/dusk`, falling back to
+/// `~/.config/dusk` (or `%APPDATA%\dusk` on Windows).
+pub fn config_dir() -> Option<PathBuf> {
+    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME")
+        && !xdg.trim().is_empty()
+    {
+        return Some(PathBuf::from(xdg).join("dusk"));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Ok(app_data) = std::env::var("APPDATA")
+            && !app_data.trim().is_empty()
+        {
+            return Some(PathBuf::from(app_data).join("dusk"));
+        }
+    }
+
+    home_dir().map(|home| home.join(".config").join("dusk"))
+}
+
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
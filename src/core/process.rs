@@ -1,6 +1,6 @@
 use std::ffi::OsString;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 
 pub fn run_passthrough(bin: &str, args: &[OsString]) -> io::Result<ExitStatus> {
@@ -17,6 +17,15 @@ pub fn run_capture(bin: &str, args: &[&str]) -> io::Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Like `run_capture`, but `None` when the command exits unsuccessfully.
+pub fn run_capture_in(dir: &Path, bin: &str, args: &[&str]) -> io::Result<Option<String>> {
+    let output = Command::new(bin).current_dir(dir).args(args).output()?;
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
 pub fn command_exists(bin: &str) -> bool {
     find_command_path(bin).is_some()
 }
@@ -0,0 +1,288 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::core::color::ColorDepth;
+use crate::core::pager::Paging;
+use crate::core::paths;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bool,
+    Int,
+    Str,
+    List,
+}
+
+pub struct Key {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub default: &'static str,
+}
+
+const fn key(name: &'static str, kind: Kind, default: &'static str) -> Key {
+    Key {
+        name,
+        kind,
+        default,
+    }
+}
+
+/// Every section and key accepted in `config.toml`, with the built-in default
+/// shown by `dusk config show`.
+pub const SECTIONS: &[(&str, &[Key])] = &[
+    (
+        "global",
+        &[
+            key("theme", Kind::Str, "onedark-pro"),
+            key("color", Kind::Str, "auto"),
+            key("color-depth", Kind::Str, "auto"),
+            key("icons", Kind::Bool, "true"),
+            key("paging", Kind::Str, "auto"),
+            key("pager", Kind::Str, "-"),
+        ],
+    ),
+    (
+        "ls",
+        &[
+            key("all", Kind::Bool, "false"),
+            key("almost-all", Kind::Bool, "false"),
+            key("long", Kind::Bool, "false"),
+            key("headers", Kind::Bool, "false"),
+            key("icons", Kind::Bool, "true"),
+            key("reverse", Kind::Bool, "false"),
+            key("human-readable", Kind::Bool, "false"),
+            key("file-type", Kind::Bool, "false"),
+            key("author", Kind::Bool, "false"),
+            key("sort", Kind::Str, "name"),
+            key("theme", Kind::Str, "-"),
+        ],
+    ),
+    (
+        "xtree",
+        &[
+            key("hidden", Kind::Bool, "false"),
+            key("size", Kind::Bool, "true"),
+            key("info", Kind::Bool, "false"),
+            key("dirs-only", Kind::Bool, "false"),
+            key("depth", Kind::Int, "-"),
+            key("exclude", Kind::List, "[]"),
+            key("git", Kind::Bool, "true"),
+            key("git-status", Kind::Bool, "false"),
+            key("treeignore", Kind::Bool, "true"),
+            key("default-ignores", Kind::Bool, "true"),
+            key("icons", Kind::Bool, "true"),
+            key("stats", Kind::Bool, "false"),
+            key("loc", Kind::Bool, "false"),
+            key("big", Kind::Bool, "false"),
+            key("tests", Kind::Bool, "false"),
+            key("count", Kind::Bool, "false"),
+            key("noreport", Kind::Bool, "false"),
+            key("clip", Kind::Int, "100"),
+            key("sort", Kind::Str, "name"),
+            key("theme", Kind::Str, "-"),
+        ],
+    ),
+    (
+        "rm",
+        &[
+            key("permanent", Kind::Bool, "false"),
+            key("interactive", Kind::Bool, "false"),
+            key("verbose", Kind::Bool, "false"),
+        ],
+    ),
+    (
+        "cat",
+        &[
+            key("number", Kind::Bool, "false"),
+            key("squeeze-blank", Kind::Bool, "false"),
+            key("theme", Kind::Str, "-"),
+        ],
+    ),
+    (
+        "bat",
+        &[
+            key("number", Kind::Bool, "true"),
+            key("squeeze-blank", Kind::Bool, "false"),
+            key("theme", Kind::Str, "-"),
+        ],
+    ),
+];
+
+/// Parsed `config.toml`. Values here sit between built-in defaults and
+/// environment variables / CLI flags.
+#[derive(Default)]
+pub struct Settings {
+    path: Option<PathBuf>,
+    table: toml::Table,
+}
+
+static CURRENT: OnceLock<Result<Settings, String>> = OnceLock::new();
+static EMPTY: OnceLock<Settings> = OnceLock::new();
+
+pub fn config_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Loaded settings, or empty settings when the file is missing or invalid.
+/// Call `check` first to surface load errors.
+pub fn current() -> &'static Settings {
+    match load_cached() {
+        Ok(settings) => settings,
+        Err(_) => EMPTY.get_or_init(Settings::default),
+    }
+}
+
+pub fn check() -> Result<(), String> {
+    load_cached().map(|_| ())
+}
+
+fn load_cached() -> Result<&'static Settings, String> {
+    CURRENT
+        .get_or_init(|| match config_path() {
+            Some(path) if path.is_file() => Settings::load(&path),
+            _ => Ok(Settings::default()),
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+impl Settings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed reading {}: {err}", path.display()))?;
+        let mut settings = Self::from_toml(&content)
+            .map_err(|err| format!("invalid config {}: {err}", path.display()))?;
+        settings.path = Some(path.to_path_buf());
+        Ok(settings)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let table = content
+            .parse::<toml::Table>()
+            .map_err(|err| err.message().to_string())?;
+
+        for (section, value) in &table {
+            let Some((_, keys)) = SECTIONS.iter().find(|(name, _)| name == section) else {
+                return Err(format!("unknown section [{section}]"));
+            };
+            let toml::Value::Table(entries) = value else {
+                return Err(format!("`{section}` must be a [section]"));
+            };
+            for (name, value) in entries {
+                let Some(key) = keys.iter().find(|k| k.name == name) else {
+                    return Err(format!("unknown key `{name}` in [{section}]"));
+                };
+                let ok = match key.kind {
+                    Kind::Bool => value.is_bool(),
+                    Kind::Int => value.as_integer().is_some_and(|n| n >= 0),
+                    Kind::Str => value.is_str(),
+                    Kind::List => value
+                        .as_array()
+                        .is_some_and(|items| items.iter().all(toml::Value::is_str)),
+                };
+                if !ok {
+                    let expected = match key.kind {
+                        Kind::Bool => "true or false",
+                        Kind::Int => "a non-negative integer",
+                        Kind::Str => "a string",
+                        Kind::List => "a list of strings",
+                    };
+                    return Err(format!("[{section}] {name} must be {expected}"));
+                }
+            }
+        }
+
+        if let Some(color) = table
+            .get("global")
+            .and_then(|g| g.get("color"))
+            .and_then(toml::Value::as_str)
+            && !matches!(color, "auto" | "always" | "never")
+        {
+            return Err("[global] color must be auto|always|never".to_string());
+        }
+        if let Some(depth) = table
+            .get("global")
+            .and_then(|g| g.get("color-depth"))
+            .and_then(toml::Value::as_str)
+            && ColorDepth::parse(depth).is_none()
+        {
+            return Err("[global] color-depth must be auto|16|256|truecolor".to_string());
+        }
+        if let Some(paging) = table
+            .get("global")
+            .and_then(|g| g.get("paging"))
+            .and_then(toml::Value::as_str)
+            && Paging::parse(paging).is_none()
+        {
+            return Err("[global] paging must be auto|always|never".to_string());
+        }
+
+        Ok(Self { path: None, table })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&toml::Value> {
+        self.table.get(section).and_then(|s| s.get(key))
+    }
+
+    pub fn get_bool(&self, section: &str, key: &str) -> Option<bool> {
+        self.get(section, key).and_then(toml::Value::as_bool)
+    }
+
+    pub fn get_str(&self, section: &str, key: &str) -> Option<&str> {
+        self.get(section, key).and_then(toml::Value::as_str)
+    }
+
+    pub fn get_usize(&self, section: &str, key: &str) -> Option<usize> {
+        self.get(section, key)
+            .and_then(toml::Value::as_integer)
+            .and_then(|n| usize::try_from(n).ok())
+    }
+
+    pub fn get_list(&self, section: &str, key: &str) -> Option<Vec<String>> {
+        self.get(section, key)
+            .and_then(toml::Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(toml::Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+
+    #[test]
+    fn reads_typed_values_from_sections() {
+        let s = Settings::from_toml(
+            "[global]\ntheme = \"nord\"\n\n[ls]\nlong = true\n\n[xtree]\ndepth = 2\nexclude = [\"*.log\"]\n",
+        )
+        .expect("parse");
+        assert_eq!(s.get_str("global", "theme"), Some("nord"));
+        assert_eq!(s.get_bool("ls", "long"), Some(true));
+        assert_eq!(s.get_usize("xtree", "depth"), Some(2));
+        assert_eq!(
+            s.get_list("xtree", "exclude"),
+            Some(vec!["*.log".to_string()])
+        );
+        assert_eq!(s.get_bool("ls", "reverse"), None);
+    }
+
+    #[test]
+    fn rejects_unknown_sections_keys_and_wrong_types() {
+        assert!(Settings::from_toml("[lss]\nlong = true\n").is_err());
+        assert!(Settings::from_toml("[ls]\nlng = true\n").is_err());
+        assert!(Settings::from_toml("[ls]\nlong = \"yes\"\n").is_err());
+        assert!(Settings::from_toml("[global]\ncolor = \"sometimes\"\n").is_err());
+        assert!(Settings::from_toml("[global]\ncolor-depth = \"8\"\n").is_err());
+        assert!(Settings::from_toml("[global]\npaging = \"maybe\"\n").is_err());
+    }
+}
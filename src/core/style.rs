@@ -2,53 +2,43 @@ use std::env;
 
 use atty::Stream;
 
+use crate::core::color::{self, ColorDepth};
+use crate::core::pager;
+use crate::core::settings;
+
 #[derive(Clone, Copy)]
 pub struct Style {
     pub color: bool,
     pub icons: bool,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::Style;
-
-    #[test]
-    fn paint_without_color_is_plain() {
-        let style = Style {
-            color: false,
-            icons: false,
-        };
-        assert_eq!(style.paint("\x1b[31m", "hello"), "hello");
-    }
-
-    #[test]
-    fn paint_with_color_wraps_ansi() {
-        let style = Style {
-            color: true,
-            icons: true,
-        };
-        assert_eq!(style.paint("\x1b[31m", "x"), "\x1b[31mx\x1b[0m");
-    }
+    pub depth: ColorDepth,
 }
 
 impl Style {
     pub fn for_stdout() -> Self {
-        let tty = atty::is(Stream::Stdout);
+        let tty = atty::is(Stream::Stdout) || pager::is_active();
         let no_color = env::var_os("NO_COLOR").is_some();
-        let force_color = env::var("DUSK_COLOR")
-            .map(|v| v.eq_ignore_ascii_case("always"))
-            .unwrap_or(false)
+        let settings = settings::current();
+        let color_mode = env::var("DUSK_COLOR")
+            .ok()
+            .or_else(|| settings.get_str("global", "color").map(str::to_string))
+            .unwrap_or_default();
+        let force_color = color_mode.eq_ignore_ascii_case("always")
             || env::var("CLICOLOR_FORCE")
                 .map(|v| v == "1")
                 .unwrap_or(false);
+        let never_color = color_mode.eq_ignore_ascii_case("never");
         let term_dumb = env::var("TERM")
             .map(|v| v.eq_ignore_ascii_case("dumb"))
             .unwrap_or(false);
 
-        let color = (tty && !no_color && !term_dumb) || force_color;
-        let icons = tty || force_color;
+        let color = !never_color && ((tty && !no_color && !term_dumb) || force_color);
+        let icons = (tty || force_color) && settings.get_bool("global", "icons") != Some(false);
 
-        Self { color, icons }
+        Self {
+            color,
+            icons,
+            depth: color::depth(),
+        }
     }
 
     pub fn paint(&self, ansi: &str, text: impl AsRef<str>) -> String {
@@ -63,3 +53,29 @@ impl Style {
         if self.icons { icon } else { "" }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Style;
+    use crate::core::color::ColorDepth;
+
+    #[test]
+    fn paint_without_color_is_plain() {
+        let style = Style {
+            color: false,
+            icons: false,
+            depth: ColorDepth::Ansi16,
+        };
+        assert_eq!(style.paint("\x1b[31m", "hello"), "hello");
+    }
+
+    #[test]
+    fn paint_with_color_wraps_ansi() {
+        let style = Style {
+            color: true,
+            icons: true,
+            depth: ColorDepth::TrueColor,
+        };
+        assert_eq!(style.paint("\x1b[31m", "x"), "\x1b[31mx\x1b[0m");
+    }
+}
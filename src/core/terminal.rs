@@ -0,0 +1,35 @@
+use std::io;
+
+use crossterm::cursor::{Hide, Show};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+
+/// Full-screen mode for the interactive views: raw input, the alternate
+/// screen, a hidden cursor and mouse capture. Dropping the guard restores
+/// the terminal, so an early `?` return or a panic unwinding through the
+/// view still leaves the shell usable.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> Result<Self, String> {
+        terminal::enable_raw_mode().map_err(|e| format!("failed to enable raw mode: {e}"))?;
+        // Built before the screen switch so a failure below still restores.
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen, Hide, EnableMouseCapture)
+            .map_err(|e| format!("failed to enter alternate screen: {e}"))?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(
+            io::stdout(),
+            Show,
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
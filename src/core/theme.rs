@@ -1,5 +1,13 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::core::color::{self, Color, ColorDepth};
+use crate::core::paths;
+use crate::core::settings;
 use crate::core::style::Style;
 
+/// A palette rendered to escape sequences for one color depth.
 #[derive(Clone, Copy)]
 pub struct Theme {
     pub name: &'static str,
@@ -10,177 +18,647 @@ pub struct Theme {
     pub info: &'static str,
     pub number: &'static str,
     pub title: &'static str,
+    pub directory: &'static str,
+    pub executable: &'static str,
+    pub symlink: &'static str,
+    pub file: &'static str,
+    pub size: &'static str,
+    pub date: &'static str,
+    pub diff_add: &'static str,
+    pub diff_del: &'static str,
     pub reset: &'static str,
 }
 
-pub const THEMES: [Theme; 14] = [
-    Theme {
+/// Theme colors as RGB. `render` downsamples them for the terminal.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub name: &'static str,
+    pub accent: Color,
+    pub ok: Color,
+    pub warn: Color,
+    pub subtle: Color,
+    pub info: Color,
+    pub number: Color,
+    pub title: Color,
+    pub directory: Color,
+    pub executable: Color,
+    pub symlink: Color,
+    pub file: Color,
+    pub size: Color,
+    pub date: Color,
+    pub diff_add: Color,
+    pub diff_del: Color,
+}
+
+impl Palette {
+    pub fn render(&self, depth: ColorDepth) -> Theme {
+        let sgr = |c: Color| leak(c.sgr(depth));
+        Theme {
+            name: self.name,
+            accent: sgr(self.accent),
+            ok: sgr(self.ok),
+            warn: sgr(self.warn),
+            subtle: sgr(self.subtle),
+            info: sgr(self.info),
+            number: sgr(self.number),
+            title: sgr(self.title),
+            directory: sgr(self.directory),
+            executable: sgr(self.executable),
+            symlink: sgr(self.symlink),
+            file: sgr(self.file),
+            size: sgr(self.size),
+            date: sgr(self.date),
+            diff_add: sgr(self.diff_add),
+            diff_del: sgr(self.diff_del),
+            reset: "\x1b[0m",
+        }
+    }
+}
+
+pub const PALETTES: [Palette; 14] = [
+    Palette {
         name: "default",
-        accent: "\x1b[1;94m",
-        ok: "\x1b[1;92m",
-        warn: "\x1b[1;91m",
-        subtle: "\x1b[1;96m",
-        info: "\x1b[1;97m",
-        number: "\x1b[1;95m",
-        title: "\x1b[1;95m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x5c5cff).bold(),
+        ok: Color::hex(0x00ff00).bold(),
+        warn: Color::hex(0xff0000).bold(),
+        subtle: Color::hex(0x00ffff).bold(),
+        info: Color::hex(0xffffff).bold(),
+        number: Color::hex(0xff00ff).bold(),
+        title: Color::hex(0xff00ff).bold(),
+        directory: Color::hex(0x0000ee).bold(),
+        executable: Color::hex(0x00cd00).bold(),
+        symlink: Color::hex(0x00cdcd).bold(),
+        file: Color::hex(0xe5e5e5),
+        size: Color::hex(0xcdcd00),
+        date: Color::hex(0xcd00cd),
+        diff_add: Color::hex(0x00ff00).bold(),
+        diff_del: Color::hex(0xff0000).bold(),
     },
-    Theme {
+    Palette {
         name: "nord",
-        accent: "\x1b[38;5;81m",
-        ok: "\x1b[38;5;121m",
-        warn: "\x1b[38;5;204m",
-        subtle: "\x1b[38;5;117m",
-        info: "\x1b[38;5;159m",
-        number: "\x1b[38;5;111m",
-        title: "\x1b[1;38;5;153m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x5fd7ff),
+        ok: Color::hex(0x87ffaf),
+        warn: Color::hex(0xff5f87),
+        subtle: Color::hex(0x87d7ff),
+        info: Color::hex(0xafffff),
+        number: Color::hex(0x87afff),
+        title: Color::hex(0xafd7ff).bold(),
+        directory: Color::hex(0x5fd7ff),
+        executable: Color::hex(0xafd787),
+        symlink: Color::hex(0xaf87af),
+        file: Color::hex(0xffaf87),
+        size: Color::hex(0xd7af5f),
+        date: Color::hex(0x87afaf),
+        diff_add: Color::hex(0x87ffaf),
+        diff_del: Color::hex(0xff5f87),
     },
-    Theme {
+    Palette {
         name: "gruvbox",
-        accent: "\x1b[38;5;214m",
-        ok: "\x1b[38;5;148m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;220m",
-        info: "\x1b[38;5;229m",
-        number: "\x1b[38;5;180m",
-        title: "\x1b[1;38;5;214m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xffaf00),
+        ok: Color::hex(0xafd700),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0xffd700),
+        info: Color::hex(0xffffaf),
+        number: Color::hex(0xd7af87),
+        title: Color::hex(0xffaf00).bold(),
+        directory: Color::hex(0x87afaf),
+        executable: Color::hex(0xafaf00),
+        symlink: Color::hex(0xd787af),
+        file: Color::hex(0xffd7af),
+        size: Color::hex(0xffaf00),
+        date: Color::hex(0xd75f5f),
+        diff_add: Color::hex(0xafd700),
+        diff_del: Color::hex(0xff5f5f),
     },
-    Theme {
+    Palette {
         name: "dracula",
-        accent: "\x1b[38;5;141m",
-        ok: "\x1b[38;5;84m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;147m",
-        info: "\x1b[38;5;225m",
-        number: "\x1b[38;5;213m",
-        title: "\x1b[1;38;5;213m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xaf87ff),
+        ok: Color::hex(0x5fff87),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0xafafff),
+        info: Color::hex(0xffd7ff),
+        number: Color::hex(0xff87ff),
+        title: Color::hex(0xff87ff).bold(),
+        directory: Color::hex(0xaf87ff),
+        executable: Color::hex(0x5fff87),
+        symlink: Color::hex(0xff87d7),
+        file: Color::hex(0x87d7ff),
+        size: Color::hex(0xffff87),
+        date: Color::hex(0x5f5faf),
+        diff_add: Color::hex(0x5fff87),
+        diff_del: Color::hex(0xff5f5f),
     },
-    Theme {
+    Palette {
         name: "solarized",
-        accent: "\x1b[38;5;136m",
-        ok: "\x1b[38;5;64m",
-        warn: "\x1b[38;5;166m",
-        subtle: "\x1b[38;5;37m",
-        info: "\x1b[38;5;230m",
-        number: "\x1b[38;5;74m",
-        title: "\x1b[1;38;5;136m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xaf8700),
+        ok: Color::hex(0x5f8700),
+        warn: Color::hex(0xd75f00),
+        subtle: Color::hex(0x00afaf),
+        info: Color::hex(0xffffd7),
+        number: Color::hex(0x5fafd7),
+        title: Color::hex(0xaf8700).bold(),
+        directory: Color::hex(0xaf8700),
+        executable: Color::hex(0x5f8700),
+        symlink: Color::hex(0xd75f00),
+        file: Color::hex(0xffffd7),
+        size: Color::hex(0xd7875f),
+        date: Color::hex(0x87afaf),
+        diff_add: Color::hex(0x5f8700),
+        diff_del: Color::hex(0xd75f00),
     },
-    Theme {
+    Palette {
         name: "catppuccin",
-        accent: "\x1b[38;5;183m",
-        ok: "\x1b[38;5;114m",
-        warn: "\x1b[38;5;210m",
-        subtle: "\x1b[38;5;153m",
-        info: "\x1b[38;5;225m",
-        number: "\x1b[38;5;147m",
-        title: "\x1b[1;38;5;183m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xd7afff),
+        ok: Color::hex(0x87d787),
+        warn: Color::hex(0xff8787),
+        subtle: Color::hex(0xafd7ff),
+        info: Color::hex(0xffd7ff),
+        number: Color::hex(0xafafff),
+        title: Color::hex(0xd7afff).bold(),
+        directory: Color::hex(0xd7afff),
+        executable: Color::hex(0x87d7af),
+        symlink: Color::hex(0xafafd7),
+        file: Color::hex(0xffd7ff),
+        size: Color::hex(0xffd787),
+        date: Color::hex(0x87afd7),
+        diff_add: Color::hex(0x87d787),
+        diff_del: Color::hex(0xff8787),
     },
-    Theme {
+    Palette {
         name: "tokyonight",
-        accent: "\x1b[38;5;111m",
-        ok: "\x1b[38;5;121m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;117m",
-        info: "\x1b[38;5;189m",
-        number: "\x1b[38;5;147m",
-        title: "\x1b[1;38;5;147m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x87afff),
+        ok: Color::hex(0x87ffaf),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0x87d7ff),
+        info: Color::hex(0xd7d7ff),
+        number: Color::hex(0xafafff),
+        title: Color::hex(0xafafff).bold(),
+        directory: Color::hex(0x87afff),
+        executable: Color::hex(0x87d787),
+        symlink: Color::hex(0x87d7ff),
+        file: Color::hex(0xafd7ff),
+        size: Color::hex(0xd7af87),
+        date: Color::hex(0x87d7ff),
+        diff_add: Color::hex(0x87ffaf),
+        diff_del: Color::hex(0xff5f5f),
     },
-    Theme {
+    Palette {
         name: "onedark-pro",
-        accent: "\x1b[38;5;75m",
-        ok: "\x1b[38;5;114m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;110m",
-        info: "\x1b[38;5;188m",
-        number: "\x1b[38;5;152m",
-        title: "\x1b[1;38;5;75m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x5fafff),
+        ok: Color::hex(0x87d787),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0x87afd7),
+        info: Color::hex(0xd7d7d7),
+        number: Color::hex(0xafd7d7),
+        title: Color::hex(0x5fafff).bold(),
+        directory: Color::hex(0x5fafff),
+        executable: Color::hex(0x87d787),
+        symlink: Color::hex(0x87afaf),
+        file: Color::hex(0xd7d7d7),
+        size: Color::hex(0xd7af87),
+        date: Color::hex(0x87afd7),
+        diff_add: Color::hex(0x87d787),
+        diff_del: Color::hex(0xff5f5f),
     },
-    Theme {
+    Palette {
         name: "monokai",
-        accent: "\x1b[38;5;81m",
-        ok: "\x1b[38;5;118m",
-        warn: "\x1b[38;5;197m",
-        subtle: "\x1b[38;5;141m",
-        info: "\x1b[38;5;227m",
-        number: "\x1b[38;5;213m",
-        title: "\x1b[1;38;5;227m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x5fd7ff),
+        ok: Color::hex(0x87ff00),
+        warn: Color::hex(0xff005f),
+        subtle: Color::hex(0xaf87ff),
+        info: Color::hex(0xffff5f),
+        number: Color::hex(0xff87ff),
+        title: Color::hex(0xffff5f).bold(),
+        directory: Color::hex(0x5fd7ff),
+        executable: Color::hex(0xafd700),
+        symlink: Color::hex(0xaf87ff),
+        file: Color::hex(0xd7d7d7),
+        size: Color::hex(0xffff5f),
+        date: Color::hex(0xaf87ff),
+        diff_add: Color::hex(0x87ff00),
+        diff_del: Color::hex(0xff005f),
     },
-    Theme {
+    Palette {
         name: "kanagawa",
-        accent: "\x1b[38;5;110m",
-        ok: "\x1b[38;5;150m",
-        warn: "\x1b[38;5;174m",
-        subtle: "\x1b[38;5;180m",
-        info: "\x1b[38;5;223m",
-        number: "\x1b[38;5;109m",
-        title: "\x1b[1;38;5;180m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x87afd7),
+        ok: Color::hex(0xafd787),
+        warn: Color::hex(0xd78787),
+        subtle: Color::hex(0xd7af87),
+        info: Color::hex(0xffd7af),
+        number: Color::hex(0x87afaf),
+        title: Color::hex(0xd7af87).bold(),
+        directory: Color::hex(0x87afd7),
+        executable: Color::hex(0xafd787),
+        symlink: Color::hex(0xd7af87),
+        file: Color::hex(0xffd7af),
+        size: Color::hex(0xd7af5f),
+        date: Color::hex(0x87afaf),
+        diff_add: Color::hex(0xafd787),
+        diff_del: Color::hex(0xd78787),
     },
-    Theme {
+    Palette {
         name: "everforest",
-        accent: "\x1b[38;5;108m",
-        ok: "\x1b[38;5;142m",
-        warn: "\x1b[38;5;167m",
-        subtle: "\x1b[38;5;180m",
-        info: "\x1b[38;5;223m",
-        number: "\x1b[38;5;151m",
-        title: "\x1b[1;38;5;108m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x87af87),
+        ok: Color::hex(0xafaf00),
+        warn: Color::hex(0xd75f5f),
+        subtle: Color::hex(0xd7af87),
+        info: Color::hex(0xffd7af),
+        number: Color::hex(0xafd7af),
+        title: Color::hex(0x87af87).bold(),
+        directory: Color::hex(0x87af87),
+        executable: Color::hex(0xafaf00),
+        symlink: Color::hex(0x87afaf),
+        file: Color::hex(0xffd7af),
+        size: Color::hex(0xd7af5f),
+        date: Color::hex(0xd7af87),
+        diff_add: Color::hex(0xafaf00),
+        diff_del: Color::hex(0xd75f5f),
     },
-    Theme {
+    Palette {
         name: "rose-pine",
-        accent: "\x1b[38;5;181m",
-        ok: "\x1b[38;5;151m",
-        warn: "\x1b[38;5;217m",
-        subtle: "\x1b[38;5;146m",
-        info: "\x1b[38;5;224m",
-        number: "\x1b[38;5;182m",
-        title: "\x1b[1;38;5;181m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xd7afaf),
+        ok: Color::hex(0xafd7af),
+        warn: Color::hex(0xffafaf),
+        subtle: Color::hex(0xafafd7),
+        info: Color::hex(0xffd7d7),
+        number: Color::hex(0xd7afd7),
+        title: Color::hex(0xd7afaf).bold(),
+        directory: Color::hex(0xd7afaf),
+        executable: Color::hex(0xafd7af),
+        symlink: Color::hex(0xafafd7),
+        file: Color::hex(0xffd7d7),
+        size: Color::hex(0xffaf87),
+        date: Color::hex(0xd7afd7),
+        diff_add: Color::hex(0xafd7af),
+        diff_del: Color::hex(0xffafaf),
     },
-    Theme {
+    Palette {
         name: "ayu",
-        accent: "\x1b[38;5;215m",
-        ok: "\x1b[38;5;114m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;151m",
-        info: "\x1b[38;5;223m",
-        number: "\x1b[38;5;179m",
-        title: "\x1b[1;38;5;215m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0xffaf5f),
+        ok: Color::hex(0x87d787),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0xafd7af),
+        info: Color::hex(0xffd7af),
+        number: Color::hex(0xd7af5f),
+        title: Color::hex(0xffaf5f).bold(),
+        directory: Color::hex(0xffaf5f),
+        executable: Color::hex(0x87d787),
+        symlink: Color::hex(0xafd7af),
+        file: Color::hex(0xffd7af),
+        size: Color::hex(0xffd787),
+        date: Color::hex(0xd7af5f),
+        diff_add: Color::hex(0x87d787),
+        diff_del: Color::hex(0xff5f5f),
     },
-    Theme {
+    Palette {
         name: "nightfox",
-        accent: "\x1b[38;5;81m",
-        ok: "\x1b[38;5;114m",
-        warn: "\x1b[38;5;203m",
-        subtle: "\x1b[38;5;117m",
-        info: "\x1b[38;5;189m",
-        number: "\x1b[38;5;153m",
-        title: "\x1b[1;38;5;81m",
-        reset: "\x1b[0m",
+        accent: Color::hex(0x5fd7ff),
+        ok: Color::hex(0x87d787),
+        warn: Color::hex(0xff5f5f),
+        subtle: Color::hex(0x87d7ff),
+        info: Color::hex(0xd7d7ff),
+        number: Color::hex(0xafd7ff),
+        title: Color::hex(0x5fd7ff).bold(),
+        directory: Color::hex(0x5fafff),
+        executable: Color::hex(0x87d787),
+        symlink: Color::hex(0x87d7ff),
+        file: Color::hex(0xd7d7ff),
+        size: Color::hex(0xd7af87),
+        date: Color::hex(0xafd7ff),
+        diff_add: Color::hex(0x87d787),
+        diff_del: Color::hex(0xff5f5f),
     },
 ];
 
+const DEFAULT_THEME: &str = "onedark-pro";
+/// Keys a theme file may set: the base slots followed by the semantic roles
+/// shared by every command (file kinds, sizes, dates, diff lines).
+const SLOTS: [&str; 15] = [
+    "accent",
+    "ok",
+    "warn",
+    "subtle",
+    "info",
+    "number",
+    "title",
+    "directory",
+    "executable",
+    "symlink",
+    "file",
+    "size",
+    "date",
+    "diff-add",
+    "diff-del",
+];
+
+fn slot_mut<'a>(theme: &'a mut Palette, slot: &str) -> &'a mut Color {
+    match slot {
+        "accent" => &mut theme.accent,
+        "ok" => &mut theme.ok,
+        "warn" => &mut theme.warn,
+        "subtle" => &mut theme.subtle,
+        "info" => &mut theme.info,
+        "number" => &mut theme.number,
+        "directory" => &mut theme.directory,
+        "executable" => &mut theme.executable,
+        "symlink" => &mut theme.symlink,
+        "file" => &mut theme.file,
+        "size" => &mut theme.size,
+        "date" => &mut theme.date,
+        "diff-add" => &mut theme.diff_add,
+        "diff-del" => &mut theme.diff_del,
+        _ => &mut theme.title,
+    }
+}
+
+struct Registry {
+    palettes: Vec<Palette>,
+    user: Vec<&'static str>,
+    rendered: [OnceLock<Vec<Theme>>; 3],
+}
+
+impl Registry {
+    fn builtin() -> Self {
+        Self {
+            palettes: PALETTES.to_vec(),
+            user: Vec::new(),
+            rendered: [OnceLock::new(), OnceLock::new(), OnceLock::new()],
+        }
+    }
+
+    /// Adds a user theme. Names match case-insensitively, like [`find`], so
+    /// `Nord` replaces the built-in `nord` in place (keeping its spelling)
+    /// instead of listing a second entry.
+    fn add_user(&mut self, mut theme: Palette) {
+        match self
+            .palettes
+            .iter_mut()
+            .find(|t| t.name.eq_ignore_ascii_case(theme.name))
+        {
+            Some(slot) => {
+                theme.name = slot.name;
+                *slot = theme;
+            }
+            None => self.palettes.push(theme),
+        }
+        if !self.is_user_defined(theme.name) {
+            self.user.push(theme.name);
+        }
+    }
+
+    fn is_user_defined(&self, name: &str) -> bool {
+        self.user.iter().any(|u| u.eq_ignore_ascii_case(name))
+    }
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| {
+        let mut reg = Registry::builtin();
+        if let Some(dir) = paths::themes_dir() {
+            for (path, result) in load_dir(&dir, &reg.palettes) {
+                match result {
+                    Ok(theme) => reg.add_user(theme),
+                    Err(err) => {
+                        eprintln!("dusk: ignoring theme file {}: {err}", path.display());
+                    }
+                }
+            }
+        }
+        reg
+    })
+}
+
+/// Built-in themes followed by user themes from `<config>/dusk/themes/*.toml`,
+/// rendered for the terminal's color depth. A user theme named like a
+/// built-in replaces it in place.
+pub fn all() -> &'static [Theme] {
+    all_at(color::depth())
+}
+
+fn all_at(depth: ColorDepth) -> &'static [Theme] {
+    let reg = registry();
+    reg.rendered[depth.index()]
+        .get_or_init(|| reg.palettes.iter().map(|p| p.render(depth)).collect())
+}
+
+pub fn is_user_defined(name: &str) -> bool {
+    registry().is_user_defined(name)
+}
+
+pub fn find(name: &str) -> Option<Theme> {
+    find_at(name, color::depth())
+}
+
+fn find_at(name: &str, depth: ColorDepth) -> Option<Theme> {
+    let wanted = if name == "onedark" {
+        DEFAULT_THEME
+    } else {
+        name
+    };
+    all_at(depth)
+        .iter()
+        .copied()
+        .find(|theme| theme.name.eq_ignore_ascii_case(wanted))
+}
+
 pub fn resolve(name: Option<&str>) -> Theme {
+    resolve_at(name, color::depth())
+}
+
+fn resolve_at(name: Option<&str>, depth: ColorDepth) -> Theme {
     let env_theme = std::env::var("DUSK_THEME").ok();
-    let mut wanted = name.or(env_theme.as_deref()).unwrap_or("onedark-pro");
-    if wanted == "onedark" {
-        wanted = "onedark-pro";
+    let wanted = name
+        .or(env_theme.as_deref())
+        .or_else(|| settings::current().get_str("global", "theme"))
+        .unwrap_or(DEFAULT_THEME);
+    find_at(wanted, depth)
+        .or_else(|| find_at(DEFAULT_THEME, depth))
+        .unwrap_or_else(|| PALETTES[7].render(depth))
+}
+
+fn load_dir(
+    dir: &Path,
+    builtins: &[Palette],
+) -> Vec<(std::path::PathBuf, Result<Palette, String>)> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = read_dir
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("toml"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|path| {
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let result = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|content| parse_theme_file(&content, &stem, builtins));
+            (path, result)
+        })
+        .collect()
+}
+
+/// Parses a theme definition. `name` defaults to the file stem and `base`
+/// (defaulting to the built-in of the same name, then `onedark-pro`) supplies
+/// every slot the file leaves out.
+fn parse_theme_file(content: &str, stem: &str, builtins: &[Palette]) -> Result<Palette, String> {
+    let table = content
+        .parse::<toml::Table>()
+        .map_err(|err| format!("invalid toml: {}", err.message()))?;
+
+    for key in table.keys() {
+        if key != "name" && key != "base" && !SLOTS.contains(&key.as_str()) {
+            return Err(format!("unknown key `{key}`"));
+        }
+    }
+
+    let name = match table.get("name") {
+        Some(toml::Value::String(s)) if !s.trim().is_empty() => s.trim().to_string(),
+        Some(_) => return Err("`name` must be a non-empty string".to_string()),
+        None => stem.to_string(),
+    };
+    if name.is_empty() {
+        return Err("theme has no name".to_string());
     }
-    THEMES
+
+    let base_name = match table.get("base") {
+        Some(toml::Value::String(s)) => s.as_str(),
+        Some(_) => return Err("`base` must be a string".to_string()),
+        None if builtins.iter().any(|t| t.name.eq_ignore_ascii_case(&name)) => name.as_str(),
+        None => DEFAULT_THEME,
+    };
+    let base_name = if base_name == "onedark" {
+        DEFAULT_THEME
+    } else {
+        base_name
+    };
+    let mut theme = builtins
         .iter()
         .copied()
-        .find(|theme| theme.name == wanted)
-        .unwrap_or(THEMES[7])
+        .find(|t| t.name.eq_ignore_ascii_case(base_name))
+        .ok_or_else(|| format!("unknown base theme `{base_name}`"))?;
+    theme.name = leak(name);
+
+    for slot in SLOTS {
+        let Some(value) = table.get(slot) else {
+            continue;
+        };
+        let spec = match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(n) => n.to_string(),
+            _ => return Err(format!("`{slot}` must be a string or a 256-color index")),
+        };
+        *slot_mut(&mut theme, slot) =
+            parse_color(&spec).map_err(|err| format!("`{slot}`: {err}"))?;
+    }
+
+    Ok(theme)
+}
+
+/// Turns a color spec into an RGB color. Accepted forms, optionally prefixed
+/// with `bold`: `#rrggbb`/`#rgb`, `0`-`255` (256-color index), or raw SGR
+/// parameters such as `1;94` or `38;5;75`.
+fn parse_color(spec: &str) -> Result<Color, String> {
+    let mut bold = false;
+    let mut color = None;
+    for word in spec.split_whitespace() {
+        if word.eq_ignore_ascii_case("bold") {
+            bold = true;
+        } else if color.is_none() {
+            color = Some(word);
+        } else {
+            return Err(format!("unexpected `{word}` in color `{spec}`"));
+        }
+    }
+    let Some(color) = color else {
+        return Err("empty color".to_string());
+    };
+
+    let parsed = if let Some(hex) = color.strip_prefix('#') {
+        let (r, g, b) = parse_hex(hex).ok_or_else(|| format!("invalid hex color `{color}`"))?;
+        Color {
+            r,
+            g,
+            b,
+            bold: false,
+        }
+    } else if let Ok(idx) = color.parse::<u8>() {
+        Color::from_ansi256(idx)
+    } else {
+        parse_sgr(color).ok_or_else(|| format!("invalid color `{color}`"))?
+    };
+
+    Ok(if bold { parsed.bold() } else { parsed })
+}
+
+/// Reads SGR parameters: an optional `0`/`1` and one foreground color
+/// (`30`-`37`, `90`-`97`, `38;5;N` or `38;2;R;G;B`).
+fn parse_sgr(params: &str) -> Option<Color> {
+    let nums = params
+        .split(';')
+        .map(|p| p.parse::<u8>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let mut bold = false;
+    let mut color = None;
+    let mut i = 0;
+    while i < nums.len() {
+        match nums[i] {
+            0 => {}
+            1 => bold = true,
+            n @ 30..=37 => color = Some(Color::from_ansi256(n - 30)),
+            n @ 90..=97 => color = Some(Color::from_ansi256(n - 90 + 8)),
+            38 if nums.get(i + 1) == Some(&5) => {
+                color = Some(Color::from_ansi256(*nums.get(i + 2)?));
+                i += 2;
+            }
+            38 if nums.get(i + 1) == Some(&2) && nums.len() >= i + 5 => {
+                color = Some(Color {
+                    r: nums[i + 2],
+                    g: nums[i + 3],
+                    b: nums[i + 4],
+                    bold: false,
+                });
+                i += 4;
+            }
+            _ => return None,
+        }
+        i += 1;
+    }
+    color.map(|c| if bold { c.bold() } else { c })
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        6 => Some((
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        )),
+        3 => {
+            let expand = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+            Some((expand(0)?, expand(1)?, expand(2)?))
+        }
+        _ => None,
+    }
+}
+
+// Theme slots are `&'static str` so `Theme` stays `Copy`; palettes are
+// rendered at most once per color depth, so leaking their strings is bounded.
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
 }
 
 pub fn plain() -> Theme {
@@ -193,18 +671,31 @@ pub fn plain() -> Theme {
         info: "",
         number: "",
         title: "",
+        directory: "",
+        executable: "",
+        symlink: "",
+        file: "",
+        size: "",
+        date: "",
+        diff_add: "",
+        diff_del: "",
         reset: "",
     }
 }
 
 pub fn active(name: Option<&str>) -> Theme {
     let style = Style::for_stdout();
-    if style.color { resolve(name) } else { plain() }
+    if style.color {
+        resolve_at(name, style.depth)
+    } else {
+        plain()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::resolve;
+    use super::{PALETTES, Registry, parse_color, parse_theme_file, resolve};
+    use crate::core::color::Color;
 
     #[test]
     fn resolves_onedark_alias_to_onedark_pro() {
@@ -217,4 +708,82 @@ mod tests {
         let t = resolve(Some("unknown-theme"));
         assert_eq!(t.name, "onedark-pro");
     }
+
+    #[test]
+    fn parse_color_supports_hex_index_and_raw_sgr() {
+        assert_eq!(parse_color("#61afef").unwrap(), Color::hex(0x61afef));
+        assert_eq!(
+            parse_color("bold #fff").unwrap(),
+            Color::hex(0xffffff).bold()
+        );
+        assert_eq!(parse_color("75").unwrap(), Color::from_ansi256(75));
+        assert_eq!(parse_color("1;94").unwrap(), Color::hex(0x5c5cff).bold());
+        assert_eq!(parse_color("38;5;75").unwrap(), Color::from_ansi256(75));
+        assert!(parse_color("4;94").is_err());
+        assert!(parse_color("#12345").is_err());
+        assert!(parse_color("blue").is_err());
+    }
+
+    #[test]
+    fn theme_file_inherits_missing_slots_from_base() {
+        let t = parse_theme_file(
+            "base = \"nord\"\naccent = \"#ff0000\"\n",
+            "house",
+            &PALETTES,
+        )
+        .expect("parse");
+        let nord = PALETTES.iter().find(|t| t.name == "nord").unwrap();
+        assert_eq!(t.name, "house");
+        assert_eq!(t.accent, Color::hex(0xff0000));
+        assert_eq!(t.ok, nord.ok);
+        assert_eq!(t.title, nord.title);
+    }
+
+    #[test]
+    fn theme_file_named_like_builtin_tweaks_that_builtin() {
+        let t = parse_theme_file("warn = 196\n", "gruvbox", &PALETTES).expect("parse");
+        let gruvbox = PALETTES.iter().find(|t| t.name == "gruvbox").unwrap();
+        assert_eq!(t.warn, Color::from_ansi256(196));
+        assert_eq!(t.accent, gruvbox.accent);
+    }
+
+    #[test]
+    fn user_theme_names_match_builtins_case_insensitively() {
+        let mut reg = Registry::builtin();
+        let nord = parse_theme_file("warn = 196\n", "Nord", &PALETTES).expect("parse");
+        assert_eq!(
+            nord.accent,
+            PALETTES.iter().find(|t| t.name == "nord").unwrap().accent
+        );
+        reg.add_user(nord);
+        reg.add_user(parse_theme_file("", "house", &PALETTES).expect("parse"));
+        reg.add_user(parse_theme_file("", "House", &PALETTES).expect("parse"));
+
+        assert_eq!(reg.palettes.len(), PALETTES.len() + 1);
+        let slot = reg.palettes.iter().find(|t| t.name == "nord").unwrap();
+        assert_eq!(slot.warn, Color::from_ansi256(196));
+        assert!(reg.is_user_defined("NORD"));
+        assert!(reg.is_user_defined("house"));
+        assert_eq!(reg.user, ["nord", "house"]);
+        assert!(!reg.is_user_defined("gruvbox"));
+    }
+
+    #[test]
+    fn theme_file_sets_semantic_roles() {
+        let t = parse_theme_file(
+            "directory = \"#0000ff\"\ndiff-add = 34\ndiff-del = \"#f00\"\n",
+            "x",
+            &PALETTES,
+        )
+        .expect("parse");
+        assert_eq!(t.directory, Color::hex(0x0000ff));
+        assert_eq!(t.diff_add, Color::from_ansi256(34));
+        assert_eq!(t.diff_del, Color::hex(0xff0000));
+    }
+
+    #[test]
+    fn theme_file_rejects_unknown_keys_and_bases() {
+        assert!(parse_theme_file("accnt = \"#fff\"\n", "x", &PALETTES).is_err());
+        assert!(parse_theme_file("base = \"nope\"\n", "x", &PALETTES).is_err());
+    }
 }
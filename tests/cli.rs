@@ -360,6 +360,248 @@ fn ls_sort_by_ext_works() {
         .success();
 }
 
+#[test]
+fn ls_lays_names_out_in_a_grid_sized_to_the_width() {
+    let td = tempdir().expect("tmpdir");
+    for name in ["a", "bb", "ccc", "dddd", "eeeee"] {
+        fs::write(td.path().join(name), "").expect("write");
+    }
+    let ls = |args: &[&str]| {
+        let out = dusk()
+            .arg("ls")
+            .args(["--basic"])
+            .args(args)
+            .arg(td.path())
+            .output()
+            .expect("run");
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).expect("utf8")
+    };
+
+    // Piped output stays one per line unless a grid is asked for.
+    assert_eq!(ls(&[]), "a\nbb\nccc\ndddd\neeeee\n");
+    assert_eq!(ls(&["-C", "--width", "14"]), "a    dddd\nbb   eeeee\nccc\n");
+    assert_eq!(ls(&["-x", "--width=16"]), "a     bb     ccc\ndddd  eeeee\n");
+    assert_eq!(ls(&["-C", "-1", "--width", "80"]), ls(&[]));
+
+    let out = dusk()
+        .env("COLUMNS", "80")
+        .args(["ls", "--basic", "-C"])
+        .arg(td.path())
+        .output()
+        .expect("run");
+    assert_eq!(
+        String::from_utf8(out.stdout).expect("utf8"),
+        "a  bb  ccc  dddd  eeeee\n"
+    );
+}
+
+#[test]
+fn ls_recurses_and_draws_trees_limited_by_level() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("src/deep")).expect("mkdir");
+    fs::write(td.path().join("src/deep/leaf.rs"), "").expect("write");
+    fs::write(td.path().join("src/main.rs"), "").expect("write");
+    fs::write(td.path().join("top.txt"), "").expect("write");
+    let ls = |args: &[&str]| {
+        let out = dusk()
+            .current_dir(td.path())
+            .args(["ls", "--basic"])
+            .args(args)
+            .output()
+            .expect("run");
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).expect("utf8")
+    };
+
+    assert_eq!(
+        ls(&["-R"]),
+        ".:\nsrc/\ntop.txt\n\n./src:\ndeep/\nmain.rs\n\n./src/deep:\nleaf.rs\n"
+    );
+    assert_eq!(ls(&["-R", "--level", "1"]), ".:\nsrc/\ntop.txt\n");
+    assert_eq!(
+        ls(&["--tree"]),
+        "./\n├── src/\n│   ├── deep/\n│   │   └── leaf.rs\n│   └── main.rs\n└── top.txt\n"
+    );
+    assert_eq!(ls(&["-T", "--level=1"]), "./\n├── src/\n└── top.txt\n");
+
+    // Long columns stay aligned while the branches indent the names.
+    let long = ls(&["-lT", "--level", "2"]);
+    let name_at = |name: &str| {
+        let line = long.lines().find(|l| l.ends_with(name)).expect(name);
+        line.find(['.', '├', '└', '│']).expect("tree column")
+    };
+    assert_eq!(name_at("./"), name_at("main.rs"));
+    assert!(!long.contains("leaf.rs"));
+
+    if !command_available("git") {
+        return;
+    }
+    Command::new("git")
+        .current_dir(td.path())
+        .args(["init", "-q"])
+        .output()
+        .expect("git");
+    fs::write(td.path().join(".gitignore"), "deep/\n*.txt\n").expect("write");
+    assert_eq!(
+        ls(&["-T", "--git-ignore"]),
+        "./\n└── src/\n    └── main.rs\n"
+    );
+}
+
+#[test]
+fn ls_git_column_marks_entries_and_rolls_up_directories() {
+    if !command_available("git") {
+        return;
+    }
+    let td = tempdir().expect("tmpdir");
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(td.path())
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .expect("git");
+    };
+    git(&["init", "-q"]);
+    fs::create_dir_all(td.path().join("src")).expect("mkdir");
+    fs::write(td.path().join("src/lib.rs"), "a\n").expect("write");
+    fs::write(td.path().join("README.md"), "r\n").expect("write");
+    fs::write(td.path().join(".gitignore"), "*.log\n").expect("write");
+    git(&["add", "."]);
+    git(&["commit", "-qm", "init"]);
+    fs::write(td.path().join("src/lib.rs"), "changed\n").expect("write");
+    fs::write(td.path().join("staged.txt"), "s\n").expect("write");
+    git(&["add", "staged.txt"]);
+    fs::write(td.path().join("debug.log"), "").expect("write");
+
+    dusk()
+        .current_dir(td.path())
+        .args(["ls", "-l", "--git", "--basic"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-M src/"))
+        .stdout(predicate::str::contains("-- README.md"))
+        .stdout(predicate::str::contains("N- staged.txt"))
+        .stdout(predicate::str::contains("-I debug.log"));
+
+    dusk()
+        .current_dir(td.path())
+        .args(["ls", "-lH", "--git", "--git-ignore", "--basic", "src"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GIT NAME"))
+        .stdout(predicate::str::contains("-M  lib.rs"));
+
+    dusk()
+        .current_dir(td.path())
+        .args(["ls", "-l", "--git", "--git-ignore", "--basic"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("debug.log").not());
+}
+
+#[test]
+fn ls_long_shows_type_special_bits_links_group_inode_and_blocks() {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+        let td = tempdir().expect("tmpdir");
+        let tool = td.path().join("tool");
+        fs::write(&tool, "x".repeat(5000)).expect("write");
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o4755)).expect("chmod");
+        fs::hard_link(&tool, td.path().join("alias")).expect("link");
+        let shared = td.path().join("shared");
+        fs::create_dir(&shared).expect("mkdir");
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o1777)).expect("chmod");
+        let md = fs::metadata(&tool).expect("meta");
+
+        dusk()
+            .args(["ls", "-l", "--basic"])
+            .arg(td.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("-rwsr-xr-x"))
+            .stdout(predicate::str::contains("drwxrwxrwt"));
+
+        let row = format!(
+            r"(?m)^{} +{} -rwsr-xr-x +2 \S+ +\S+ +5000 .* tool\*$",
+            md.ino(),
+            md.blocks().div_ceil(2)
+        );
+        dusk()
+            .args(["ls", "-lHis", "--links", "--group", "--basic"])
+            .arg(td.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match(r"INODE +BLOCKS PERMS +LINKS OWNER +GROUP").unwrap())
+            .stdout(predicate::str::is_match(row).unwrap());
+    }
+}
+
+#[test]
+fn ls_long_shows_symlink_targets_and_dereferences_with_l() {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+        let td = tempdir().expect("tmpdir");
+        fs::write(td.path().join("real.txt"), "hello").expect("write");
+        fs::create_dir(td.path().join("dir")).expect("mkdir");
+        symlink("real.txt", td.path().join("to-file")).expect("symlink");
+        symlink("dir", td.path().join("to-dir")).expect("symlink");
+        symlink("missing", td.path().join("dangling")).expect("symlink");
+
+        dusk()
+            .args(["ls", "-l", "--basic"])
+            .arg(td.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match(r"(?m)^l\S+ .* to-file -> real\.txt$").unwrap())
+            .stdout(predicate::str::contains("to-dir -> dir"))
+            .stdout(predicate::str::contains("dangling -> missing"));
+
+        // Targets take their kind's color; dangling ones stand out.
+        let out = dusk()
+            .args(["ls", "-l", "--no-icons", "--color=always"])
+            .arg(td.path())
+            .output()
+            .expect("run");
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        // The color of the last thing on the line: the name, or the target.
+        let last_sgr = |name: &str| {
+            let line = stdout.lines().find(|l| l.contains(name)).expect("listed");
+            let reset = line.rfind('\x1b').expect("painted");
+            let start = line[..reset].rfind('\x1b').expect("painted");
+            line[start..reset]
+                .split('m')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(last_sgr("to-dir"), last_sgr("dir/"));
+        assert_eq!(last_sgr("to-file"), last_sgr("real.txt"));
+        assert_ne!(last_sgr("dangling"), last_sgr("real.txt"));
+        assert_ne!(last_sgr("dangling"), last_sgr("dir/"));
+
+        dusk()
+            .args(["ls", "-lL", "--basic"])
+            .arg(td.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match(r"(?m)^-\S+ +\S+ +5 .* to-file$").unwrap())
+            .stdout(predicate::str::is_match(r"(?m)^d\S+ .* to-dir/$").unwrap())
+            .stdout(predicate::str::contains("dangling -> missing"));
+
+        // A link operand is listed itself with -l and followed with -L.
+        dusk()
+            .args(["ls", "-l", "--basic"])
+            .arg(td.path().join("to-dir"))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("to-dir -> dir"));
+    }
+}
+
 #[test]
 fn cat_plain_reads_stdin() {
     dusk()
@@ -413,6 +655,441 @@ fn tree_noreport_hides_summary_line() {
         .stdout(predicate::str::contains("directories").not());
 }
 
+#[test]
+fn xtree_applies_nested_gitignores_info_exclude_and_global_excludes() {
+    let td = tempdir().expect("tmpdir");
+    let repo = td.path().join("repo");
+    let home = td.path().join("home");
+    for dir in [
+        "repo/.git/info",
+        "repo/pkg/dist-out",
+        "repo/pkg/src",
+        "home",
+    ] {
+        fs::create_dir_all(td.path().join(dir)).expect("mkdir");
+    }
+    fs::write(repo.join(".gitignore"), "*.log\n").expect("write");
+    fs::write(repo.join("pkg/.gitignore"), "dist-out/\n!keep.log\n").expect("write");
+    fs::write(repo.join(".git/info/exclude"), "secret.txt\n").expect("write");
+    fs::write(
+        home.join(".gitconfig"),
+        "[core]\n\texcludesFile = ~/globalignore\n",
+    )
+    .expect("write");
+    fs::write(home.join("globalignore"), "*.bak\n").expect("write");
+    for file in [
+        "pkg/keep.log",
+        "pkg/drop.log",
+        "pkg/dist-out/bundle.js",
+        "pkg/src/main.rs",
+        "pkg/src/main.rs.bak",
+        "pkg/secret.txt",
+    ] {
+        fs::write(repo.join(file), "x\n").expect("write");
+    }
+
+    // Run from the package so the repository root has to be discovered.
+    dusk()
+        .current_dir(repo.join("pkg"))
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .args(["xtree", "--json", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("keep.log"))
+        .stdout(predicate::str::contains("main.rs\""))
+        .stdout(predicate::str::contains("drop.log").not())
+        .stdout(predicate::str::contains("bundle.js").not())
+        .stdout(predicate::str::contains("main.rs.bak").not())
+        .stdout(predicate::str::contains("secret.txt").not());
+
+    dusk()
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .args(["xtree", "--no-git"])
+        .arg(&repo)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("drop.log"))
+        .stdout(predicate::str::contains("main.rs.bak"));
+}
+
+#[test]
+fn xtree_reports_a_malformed_nested_gitignore() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join(".git")).expect("mkdir");
+    fs::create_dir_all(td.path().join("pkg")).expect("mkdir");
+    fs::write(td.path().join("pkg/.gitignore"), "{a\n").expect("write");
+    fs::write(td.path().join("pkg/main.rs"), "x\n").expect("write");
+
+    dusk()
+        .args(["xtree"])
+        .arg(td.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pkg/.gitignore"))
+        .stderr(predicate::str::contains("unclosed alternate group"));
+}
+
+#[test]
+fn xtree_default_ignores_can_be_negated_or_disabled() {
+    let td = tempdir().expect("tmpdir");
+    for dir in ["build", "out", "node_modules/pkg"] {
+        fs::create_dir_all(td.path().join(dir)).expect("mkdir");
+    }
+    fs::write(td.path().join("build/app.o"), "o").expect("write");
+    fs::write(td.path().join("out/report.txt"), "r").expect("write");
+    fs::write(td.path().join("notes.md"), "n").expect("write");
+
+    dusk()
+        .args(["xtree", "--no-git"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("app.o").not())
+        .stdout(predicate::str::contains("hidden: 3 by defaults"));
+
+    fs::write(td.path().join(".treeignore"), "!build\nnotes.md\n").expect("write");
+    dusk()
+        .args(["xtree", "--no-git"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("app.o"))
+        .stdout(predicate::str::contains("report.txt").not())
+        .stdout(predicate::str::contains(
+            "hidden: 2 by defaults, 1 by .treeignore, 1 by dotfiles",
+        ));
+
+    dusk()
+        .args(["xtree", "--no-git", "--no-default-ignores"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("report.txt"))
+        .stdout(predicate::str::contains("node_modules"));
+}
+
+#[test]
+fn xtree_parallel_walk_output_is_deterministic() {
+    let td = tempdir().expect("tmpdir");
+    for dir in 0..8 {
+        let sub = td.path().join(format!("pkg{dir}"));
+        fs::create_dir_all(&sub).expect("mkdir");
+        for file in 0..12 {
+            fs::write(sub.join(format!("f{file:02}.rs")), format!("// {file}\n")).expect("write");
+        }
+    }
+
+    let run = || {
+        let out = dusk()
+            .args(["xtree", "--no-git", "--stats", "--dupes", "-g", "//"])
+            .arg(td.path())
+            .output()
+            .expect("run");
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).expect("utf8")
+    };
+    let first = run();
+    assert_eq!(first, run());
+    assert_eq!(first, run());
+
+    let pkg0 = first.find("pkg0/").expect("pkg0");
+    let pkg7 = first.find("pkg7/").expect("pkg7");
+    assert!(pkg0 < pkg7);
+    assert!(first.contains("Total LOC: 96"));
+    assert!(first.contains("(8 files)"));
+}
+
+#[test]
+fn xtree_output_formats_share_depth_focus_and_sort() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("src/deep")).expect("mkdir");
+    fs::write(td.path().join("src/deep/nested.rs"), "fn n() {}\n").expect("write");
+    fs::write(td.path().join("src/lib.rs"), "fn l() {}\n").expect("write");
+    fs::write(td.path().join("notes.txt"), "n\n").expect("write");
+    fs::write(td.path().join("big.txt"), "x".repeat(4096)).expect("write");
+
+    for format in ["--json", "--md", "--prompt"] {
+        dusk()
+            .args(["xtree", "--no-git", format, "-L", "2"])
+            .arg(td.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("lib.rs"))
+            .stdout(predicate::str::contains("nested.rs").not());
+
+        // `--focus` takes every following word, so the path goes first.
+        dusk()
+            .args(["xtree", "--no-git"])
+            .arg(td.path())
+            .args([format, "--focus", "rs"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("nested.rs"))
+            .stdout(predicate::str::contains("notes.txt").not());
+    }
+
+    let out = dusk()
+        .args(["xtree", "--no-git", "--prompt", "-L", "1", "--sort", "size"])
+        .arg(td.path())
+        .output()
+        .expect("run");
+    let text = String::from_utf8(out.stdout).expect("utf8");
+    let big = text.find("- big.txt").expect("big.txt");
+    let notes = text.find("- notes.txt").expect("notes.txt");
+    assert!(big < notes);
+}
+
+#[test]
+fn xtree_git_status_marks_files_and_rolls_up_directories() {
+    if !command_available("git") {
+        return;
+    }
+    let td = tempdir().expect("tmpdir");
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(td.path())
+            .args(args)
+            .output()
+            .expect("git");
+    };
+    git(&["init", "-q"]);
+    fs::create_dir_all(td.path().join("src")).expect("mkdir");
+    fs::create_dir_all(td.path().join("scratch")).expect("mkdir");
+    fs::write(td.path().join("src/lib.rs"), "a\n").expect("write");
+    fs::write(td.path().join("src/main.rs"), "b\n").expect("write");
+    git(&["add", "src"]);
+    fs::write(td.path().join("src/lib.rs"), "changed\n").expect("rewrite");
+    fs::write(td.path().join("scratch/notes.txt"), "n\n").expect("write");
+
+    dusk()
+        .current_dir(td.path())
+        .args(["xtree", "--git-status", "--no-icon", "-s", "--noreport"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-N scratch/"))
+        .stdout(predicate::str::contains("-N notes.txt"))
+        .stdout(predicate::str::contains("NM src/"))
+        .stdout(predicate::str::contains("NM lib.rs"))
+        .stdout(predicate::str::contains("N- main.rs"));
+
+    // Paths stay right when run from a subdirectory.
+    dusk()
+        .current_dir(td.path().join("src"))
+        .args(["xtree", "--git-status", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"git\": \"NM\""));
+}
+
+#[test]
+fn xtree_du_totals_directories_with_shares_and_min_size() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("big/nested")).expect("mkdir");
+    fs::create_dir_all(td.path().join("small")).expect("mkdir");
+    fs::write(td.path().join("big/nested/blob.bin"), vec![b'x'; 3000]).expect("write");
+    fs::write(td.path().join("big/extra.txt"), vec![b'y'; 1000]).expect("write");
+    fs::write(td.path().join("small/tiny.txt"), "hi\n").expect("write");
+
+    // `-L 1` hides the nested file, but its bytes still count.
+    dusk()
+        .args([
+            "xtree",
+            "--du",
+            "--sort",
+            "size",
+            "--no-icon",
+            "--noreport",
+            "-L",
+            "1",
+        ])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[3.91K]"))
+        .stdout(
+            predicate::str::is_match(r"\[ 3\.91K 100% █+ *\] big/\n.*\[    3B   0% +\] small/")
+                .expect("regex"),
+        )
+        .stdout(predicate::str::contains("blob.bin").not());
+
+    dusk()
+        .args(["xtree", "--min-size", "2K", "--no-icon", "--noreport"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("blob.bin"))
+        .stdout(predicate::str::contains("extra.txt").not())
+        .stdout(predicate::str::contains("small").not());
+
+    dusk()
+        .args(["xtree", "--du=blocks"])
+        .arg(td.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("apparent | disk"));
+}
+
+#[test]
+fn xtree_tui_requires_a_terminal() {
+    dusk()
+        .args(["xtree", "--tui", "."])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--tui needs an interactive terminal",
+        ));
+}
+
+#[test]
+fn xtree_grep_nests_matches_under_files_and_prunes_the_rest() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("src")).expect("mkdir");
+    fs::create_dir_all(td.path().join("docs")).expect("mkdir");
+    fs::write(
+        td.path().join("src/main.rs"),
+        "fn main() {\n    // TODO: start\n    let x = 1;\n    todos();\n}\n",
+    )
+    .expect("write");
+    fs::write(td.path().join("src/lib.rs"), "pub fn todo() {}\n").expect("write");
+    fs::write(td.path().join("docs/notes.md"), "nothing here\n").expect("write");
+
+    dusk()
+        .args(["xtree", "-g", "TODO", "-C", "1", "--no-icon", "--noreport"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"src/ \(1 hit\)\n.*main\.rs \(1 hit\)\n.*1- fn main\(\) \{\n.*2:     // TODO: start\n.*3-     let x = 1;\n",
+            )
+            .expect("regex"),
+        )
+        .stdout(predicate::str::contains("lib.rs").not())
+        .stdout(predicate::str::contains("docs").not());
+
+    dusk()
+        .args(["xtree", "-i", "-g", "todo", "-w", "--no-icon"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("src/ (2 hits)"))
+        .stdout(predicate::str::contains("1: pub fn todo() {}"))
+        .stdout(predicate::str::contains("todos()").not());
+
+    dusk()
+        .args(["xtree", "-g", "todos(", "-F", "--no-icon"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4:     todos();"));
+
+    dusk()
+        .args(["xtree", "-g", "todos("])
+        .arg(td.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid --grep pattern"));
+}
+
+#[test]
+fn xtree_time_and_git_change_filters_prune_the_tree() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("old/deep")).expect("mkdir");
+    fs::create_dir_all(td.path().join("fresh")).expect("mkdir");
+    fs::write(td.path().join("old/deep/stale.txt"), "s\n").expect("write");
+    fs::write(td.path().join("fresh/new.txt"), "n\n").expect("write");
+    let stale = fs::File::options()
+        .write(true)
+        .open(td.path().join("old/deep/stale.txt"))
+        .expect("open");
+    stale
+        .set_modified(std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(86_400))
+        .expect("set mtime");
+
+    dusk()
+        .args([
+            "xtree",
+            "--no-git",
+            "--since",
+            "1d",
+            "--no-icon",
+            "--noreport",
+        ])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("new.txt"))
+        .stdout(predicate::str::contains("old/").not());
+
+    dusk()
+        .args(["xtree", "--no-git", "--before=2000-01-01", "--no-icon"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stale.txt"))
+        .stdout(predicate::str::contains("fresh").not());
+
+    dusk()
+        .args(["xtree", "--since", "soon"])
+        .arg(td.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time for --since"));
+
+    if !command_available("git") {
+        return;
+    }
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(td.path())
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .expect("git");
+    };
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-qm", "init"]);
+    git(&["branch", "base"]);
+    fs::write(td.path().join("old/deep/stale.txt"), "edited\n").expect("write");
+    fs::write(td.path().join("fresh/untracked.txt"), "u\n").expect("write");
+
+    dusk()
+        .current_dir(td.path())
+        .args([
+            "xtree",
+            "--changed-since",
+            "base",
+            "--no-icon",
+            "--noreport",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stale.txt"))
+        .stdout(predicate::str::contains("untracked.txt"))
+        .stdout(predicate::str::contains("new.txt").not());
+
+    // Option-looking revisions never reach git.
+    let sink = td.path().join("sink");
+    dusk()
+        .current_dir(td.path())
+        .arg("xtree")
+        .arg(format!("--changed-since=--output={}", sink.display()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid --changed-since revision"));
+    assert!(!sink.exists());
+
+    dusk()
+        .current_dir(td.path())
+        .args(["xtree", "--changed-since", "no-such-branch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown --changed-since revision"));
+}
+
 #[test]
 fn xtree_loc_prints_total_loc() {
     let td = tempdir().expect("tmpdir");
@@ -426,6 +1103,44 @@ fn xtree_loc_prints_total_loc() {
         .stdout(predicate::str::contains("Total LOC:"));
 }
 
+#[test]
+fn xtree_loc_splits_code_comment_and_blank_per_language() {
+    let td = tempdir().expect("tmpdir");
+    fs::write(
+        td.path().join("main.rs"),
+        "// entry\nfn main() {\n\n    /* a\n       b */\n}\n",
+    )
+    .expect("write");
+    fs::write(td.path().join("tool.py"), "# helper\nimport os\n").expect("write");
+
+    dusk()
+        .args(["xtree", "--loc", "--noreport"])
+        .arg(td.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Total LOC: 8 (code 3, comments 4, blank 1)",
+        ))
+        .stdout(
+            predicate::str::is_match(r"Rust +1 +6 +2 +3 +1\n.*Python +1 +2 +1 +1 +0\n")
+                .expect("regex"),
+        )
+        .stdout(predicate::str::is_match(r"Total +2 +8 +3 +4 +1").expect("regex"));
+
+    let out = dusk()
+        .args(["xtree", "--loc", "--json"])
+        .arg(td.path())
+        .output()
+        .expect("run");
+    assert!(out.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("json");
+    assert_eq!(json["languages"][0]["name"], "Rust");
+    assert_eq!(json["languages"][0]["comment"], 3);
+    assert_eq!(json["total"]["code"], 3);
+    assert_eq!(json["total"]["files"], 2);
+    assert_eq!(json["root"]["loc"]["blank"], 1);
+}
+
 #[test]
 fn xtree_stats_includes_loc_column() {
     let td = tempdir().expect("tmpdir");
@@ -659,3 +1374,493 @@ fn bat_lexical_highlighting_marks_keywords_when_forced_color() {
         .success()
         .stdout(predicate::str::is_match("\\x1b\\[[0-9;]*mfn\\x1b\\[0m").unwrap());
 }
+
+#[test]
+fn bat_detects_language_from_shebang_and_keeps_attributes_out_of_comments() {
+    let td = tempdir().expect("tmpdir");
+    let script = td.path().join("bootstrap");
+    fs::write(&script, "#!/usr/bin/env bash\nif true; then echo hi; fi\n").expect("write");
+    let rs = td.path().join("lib.rs");
+    fs::write(&rs, "// note\n#[derive(Debug)]\nstruct S;\n").expect("write");
+
+    dusk()
+        .env("DUSK_COLOR", "always")
+        .args(["bat", "--no-number", script.to_string_lossy().as_ref()])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("\\x1b\\[[0-9;]*mthen\\x1b\\[0m").unwrap());
+
+    let out = dusk()
+        .env("DUSK_COLOR", "always")
+        .args(["bat", "--no-number", rs.to_string_lossy().as_ref()])
+        .output()
+        .expect("run");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let sgr = |text: &str| {
+        let end = stdout.find(text).expect("token present");
+        let start = stdout[..end].rfind('\x1b').expect("painted");
+        stdout[start..end].to_string()
+    };
+    assert_ne!(sgr("#[derive(Debug)]"), sgr("// note"));
+
+    dusk()
+        .args(["bat", "-l", "klingon", rs.to_string_lossy().as_ref()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown language: klingon"));
+}
+
+#[test]
+fn paging_flag_is_validated_and_skipped_when_stdout_is_not_a_tty() {
+    let td = tempdir().expect("tmpdir");
+    let p = td.path().join("notes.txt");
+    fs::write(&p, "first\nsecond\n").expect("write");
+
+    dusk()
+        .env("DUSK_PAGER", "false")
+        .args(["bat", "--paging=always", "--no-number"])
+        .arg(&p)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("second"));
+
+    dusk()
+        .args(["xtree", "--paging", "sometimes"])
+        .arg(td.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--paging supports: auto | always | never",
+        ));
+}
+
+#[test]
+fn bat_view_requires_a_terminal() {
+    dusk()
+        .args(["bat", "--view", "Cargo.toml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--view needs an interactive terminal",
+        ));
+}
+
+#[test]
+fn bat_line_ranges_keep_real_line_numbers() {
+    let td = tempdir().expect("tmpdir");
+    let p = td.path().join("lines.txt");
+    fs::write(&p, "one\ntwo\nthree\nfour\nfive\n").expect("write");
+
+    dusk()
+        .args(["bat", "-r", "2:3", "--line-range", "5"])
+        .arg(&p)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "    2 | two\n    3 | three\n  ⋯\n    5 | five",
+        ))
+        .stdout(predicate::str::contains("one").not());
+
+    dusk()
+        .args(["cat", "-r", "4:"])
+        .arg(&p)
+        .assert()
+        .success()
+        .stdout("four\nfive\n");
+}
+
+#[test]
+fn bat_gutter_marks_changes_against_git_index() {
+    if !command_available("git") {
+        return;
+    }
+    let td = tempdir().expect("tmpdir");
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(td.path())
+            .args(args)
+            .output()
+            .expect("git");
+    };
+    git(&["init", "-q"]);
+    let p = td.path().join("notes.txt");
+    fs::write(&p, "a\nb\nc\n").expect("write");
+    git(&["add", "notes.txt"]);
+    fs::write(&p, "a\nB\nc\nd\n").expect("rewrite");
+
+    dusk()
+        .env("DUSK_COLOR", "always")
+        .args(["bat", "--highlight-line", "3"])
+        .arg(&p)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[7mc"));
+
+    dusk()
+        .arg("bat")
+        .arg(&p)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("     1 | a"))
+        .stdout(predicate::str::contains("~    2 | B"))
+        .stdout(predicate::str::contains("+    4 | d"));
+}
+
+#[test]
+fn bat_summarizes_binary_files_and_cat_shows_nonprinting() {
+    let td = tempdir().expect("tmpdir");
+    let bin = td.path().join("blob.dat");
+    fs::write(&bin, b"ELF\x00\x01\x02hello").expect("write");
+
+    dusk()
+        .arg("bat")
+        .arg(&bin)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("binary file, 11 bytes"))
+        .stdout(predicate::str::contains("hello").not());
+
+    dusk()
+        .args(["bat", "--binary", "hex"])
+        .arg(&bin)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "00000000  45 4c 46 00 01 02 68 65 6c 6c 6f",
+        ));
+
+    let text = td.path().join("text.txt");
+    fs::write(&text, "a\tb\r\nc\x01d \n").expect("write");
+    dusk()
+        .args(["cat", "-A"])
+        .arg(&text)
+        .assert()
+        .success()
+        .stdout("a^Ib^M$\nc^Ad $\n");
+    dusk()
+        .args(["bat", "--show-all", "--no-number"])
+        .arg(&text)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a→b␍␊\nc␁d·␊\n"));
+}
+
+#[test]
+fn bat_decodes_utf16_and_latin1_and_reports_encoding() {
+    let td = tempdir().expect("tmpdir");
+    let utf16 = td.path().join("export.csv");
+    fs::write(
+        &utf16,
+        b"\xff\xfei\x00d\x00\r\x00\n\x00\xe9\x00\r\x00\n\x00",
+    )
+    .expect("write");
+    dusk()
+        .arg("bat")
+        .arg(&utf16)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("export.csv -- UTF-16LE · CRLF"))
+        .stdout(predicate::str::contains("    1 | id\n    2 | é\n"));
+
+    let latin1 = td.path().join("legacy.txt");
+    fs::write(&latin1, b"caf\xe9\n").expect("write");
+    dusk()
+        .arg("bat")
+        .arg(&latin1)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("legacy.txt -- Latin-1 · LF"))
+        .stdout(predicate::str::contains("    1 | café"));
+    dusk()
+        .args(["cat", "--encoding", "latin1"])
+        .arg(&latin1)
+        .assert()
+        .success()
+        .stdout("café\n");
+    dusk()
+        .args(["bat", "--encoding", "ebcdic"])
+        .arg(&latin1)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown encoding: ebcdic"));
+}
+
+#[test]
+fn bat_streams_input_as_it_arrives() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dusk"))
+        .args(["bat", "--paging=never", "-l", "rust"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn");
+    let mut stdin = child.stdin.take().expect("stdin");
+    let stdout = child.stdout.take().expect("stdout");
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if tx.send(line.expect("line")).is_err() {
+                break;
+            }
+        }
+    });
+
+    // The first line shows up while the writer still holds the pipe open.
+    stdin.write_all(b"fn first() {}\n").expect("write");
+    stdin.flush().expect("flush");
+    let wait = |want: &str| loop {
+        let line = rx
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap_or_else(|_| panic!("no `{want}` before the input ended"));
+        if line.contains(want) {
+            break;
+        }
+    };
+    wait("fn first");
+    stdin.write_all(b"fn second() {}\n").expect("write");
+    stdin.flush().expect("flush");
+    wait("fn second");
+    drop(stdin);
+    assert!(child.wait().expect("wait").success());
+}
+
+#[test]
+fn bat_hex_dumps_large_binaries_with_running_offsets() {
+    let td = tempdir().expect("tmpdir");
+    let blob = td.path().join("blob.bin");
+    fs::write(&blob, vec![0u8; 70_000]).expect("write");
+    dusk()
+        .args(["bat", "--binary", "hex"])
+        .arg(&blob)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n00010000  00"))
+        .stdout(predicate::str::contains("\n00011160  00"))
+        .stdout(predicate::str::contains("00011170").not());
+    dusk()
+        .arg("bat")
+        .arg(&blob)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("binary file, 70000 bytes"));
+}
+
+#[test]
+fn themes_list_includes_user_theme_files() {
+    let td = tempdir().expect("tmpdir");
+    let themes = td.path().join("dusk/themes");
+    fs::create_dir_all(&themes).expect("mkdir");
+    fs::write(
+        themes.join("house.toml"),
+        "base = \"nord\"\naccent = \"#ff8800\"\n",
+    )
+    .expect("write theme");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .args(["themes", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("house (user)"))
+        .stdout(predicate::str::contains("onedark-pro"));
+}
+
+#[test]
+fn user_theme_colors_are_used_by_commands() {
+    let td = tempdir().expect("tmpdir");
+    let themes = td.path().join("dusk/themes");
+    fs::create_dir_all(&themes).expect("mkdir");
+    fs::write(themes.join("house.toml"), "directory = \"#ff8800\"\n").expect("write theme");
+    fs::create_dir_all(td.path().join("listing/sub")).expect("mkdir");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .env("DUSK_COLOR", "always")
+        .env("COLORTERM", "truecolor")
+        .args([
+            "ls",
+            "--theme",
+            "house",
+            td.path().join("listing").to_string_lossy().as_ref(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[38;2;255;136;0m"));
+}
+
+#[test]
+fn xtree_and_ls_share_theme_roles() {
+    let td = tempdir().expect("tmpdir");
+    let themes = td.path().join("dusk/themes");
+    fs::create_dir_all(&themes).expect("mkdir");
+    fs::write(themes.join("house.toml"), "directory = \"#ff8800\"\n").expect("write theme");
+    fs::create_dir_all(td.path().join("listing/sub")).expect("mkdir");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .env("DUSK_COLOR", "always")
+        .env("COLORTERM", "truecolor")
+        .args([
+            "xtree",
+            "--theme",
+            "house",
+            td.path().join("listing").to_string_lossy().as_ref(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[38;2;255;136;0m"));
+}
+
+#[test]
+fn color_depth_flag_downsamples_theme_colors() {
+    let td = tempdir().expect("tmpdir");
+    let themes = td.path().join("dusk/themes");
+    fs::create_dir_all(&themes).expect("mkdir");
+    fs::write(themes.join("house.toml"), "directory = \"#ff8800\"\n").expect("write theme");
+    fs::create_dir_all(td.path().join("listing/sub")).expect("mkdir");
+    let listing = td.path().join("listing");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .env("DUSK_COLOR", "always")
+        .env("COLORTERM", "truecolor")
+        .args([
+            "--color-depth",
+            "256",
+            "ls",
+            "--theme",
+            "house",
+            listing.to_string_lossy().as_ref(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[38;5;208m"))
+        .stdout(predicate::str::contains("38;2;").not());
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .env("DUSK_COLOR", "always")
+        .env("COLORTERM", "")
+        .env("TERM", "linux")
+        .args(["ls", "--theme", "house", listing.to_string_lossy().as_ref()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("38;5;").not());
+
+    // After the command it is an ordinary argument, here a --grep pattern.
+    fs::write(listing.join("flags.txt"), "pass --color-depth 16\n").expect("write");
+    dusk()
+        .args(["xtree", "--no-icon", "--grep", "--color-depth"])
+        .arg(&listing)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("flags.txt (1 hit)"));
+}
+
+#[test]
+fn config_file_sets_command_defaults_and_cli_flags_still_apply() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("dusk")).expect("mkdir");
+    fs::write(
+        td.path().join("dusk/config.toml"),
+        "[ls]\nlong = true\nheaders = true\n",
+    )
+    .expect("write config");
+    let listing = td.path().join("listing");
+    fs::create_dir_all(&listing).expect("mkdir");
+    fs::write(listing.join("a.txt"), "x").expect("write");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .args(["ls", "--basic", listing.to_string_lossy().as_ref()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PERMS"));
+}
+
+#[test]
+fn section_theme_in_config_yields_to_dusk_theme() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("dusk")).expect("mkdir");
+    fs::write(
+        td.path().join("dusk/config.toml"),
+        "[ls]\ntheme = \"nord\"\n\n[bat]\ntheme = \"nord\"\n",
+    )
+    .expect("write config");
+    let listing = td.path().join("listing");
+    fs::create_dir_all(listing.join("sub")).expect("mkdir");
+    let file = listing.join("main.rs");
+    fs::write(&file, "fn main() {}\n").expect("write");
+
+    let run = |env_theme: Option<&str>, args: &[&str]| {
+        let mut cmd = dusk();
+        cmd.env("XDG_CONFIG_HOME", td.path())
+            .env("DUSK_COLOR", "always")
+            .env_remove("DUSK_THEME")
+            .args(args);
+        if let Some(name) = env_theme {
+            cmd.env("DUSK_THEME", name);
+        }
+        let out = cmd.output().expect("run");
+        assert!(out.status.success());
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+    let ls = [
+        "ls",
+        "--color=always",
+        "--no-icons",
+        listing.to_str().unwrap(),
+    ];
+    let bat = ["bat", "--no-number", file.to_str().unwrap()];
+    for args in [&ls[..], &bat[..]] {
+        let with = |theme: &'static str| [args, &["--theme", theme]].concat();
+        // The section theme applies without the variable...
+        assert_eq!(run(None, args), run(None, &with("nord")));
+        // ...but DUSK_THEME wins over it, and --theme over both.
+        assert_eq!(run(Some("gruvbox"), args), run(None, &with("gruvbox")));
+        assert_ne!(run(Some("gruvbox"), args), run(None, args));
+        assert_eq!(
+            run(Some("gruvbox"), &with("dracula")),
+            run(None, &with("dracula"))
+        );
+    }
+}
+
+#[test]
+fn config_show_reports_value_sources() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("dusk")).expect("mkdir");
+    fs::write(
+        td.path().join("dusk/config.toml"),
+        "[global]\ntheme = \"nord\"\n\n[xtree]\nloc = true\n",
+    )
+    .expect("write config");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .env_remove("DUSK_THEME")
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("theme += \"nord\" +# config file").unwrap())
+        .stdout(predicate::str::is_match("loc += true +# config file").unwrap())
+        .stdout(predicate::str::is_match("stats += false +# default").unwrap());
+}
+
+#[test]
+fn invalid_config_file_is_reported() {
+    let td = tempdir().expect("tmpdir");
+    fs::create_dir_all(td.path().join("dusk")).expect("mkdir");
+    fs::write(td.path().join("dusk/config.toml"), "[ls]\nlng = true\n").expect("write config");
+
+    dusk()
+        .env("XDG_CONFIG_HOME", td.path())
+        .args(["ls"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown key `lng` in [ls]"));
+}